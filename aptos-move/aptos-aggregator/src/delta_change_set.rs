@@ -13,6 +13,7 @@ use aptos_types::{
     write_set::{WriteOp, WriteSet, WriteSetMut},
 };
 use move_binary_format::errors::{Location, PartialVMError, PartialVMResult};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 /// When `Addition` operation overflows the `limit`.
@@ -22,7 +23,7 @@ const EADD_OVERFLOW: u64 = 0x02_0001;
 const ESUB_UNDERFLOW: u64 = 0x02_0002;
 
 /// Represents an update from aggregator's operation.
-#[derive(Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Copy, Clone, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DeltaOp {
     /// Maximum positive delta seen during execution.
     max_positive: u128,
@@ -36,7 +37,7 @@ pub struct DeltaOp {
 }
 
 /// Different delta functions.
-#[derive(Copy, Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Hash, PartialOrd, Ord, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DeltaUpdate {
     Plus(u128),
     Minus(u128),
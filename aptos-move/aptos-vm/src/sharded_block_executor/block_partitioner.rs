@@ -4,12 +4,65 @@
 use aptos_types::transaction::Transaction;
 
 pub trait BlockPartitioner: Send + Sync {
+    /// Splits the transactions into at most `num_shards` partitions, the i-th partition being
+    /// executed by the i-th shard. Trailing shards without transactions are omitted (i.e. the
+    /// result may have fewer than `num_shards` partitions), while shards without transactions
+    /// followed by non-empty ones are represented as empty partitions.
     fn partition(&self, transactions: Vec<Transaction>, num_shards: usize)
         -> Vec<Vec<Transaction>>;
+
+    /// Whether `partition` is a pure function of its inputs, i.e. the same transactions and
+    /// number of shards always result in the same partitioning (e.g. no dependence on
+    /// randomness or HashMap iteration order). Verifiable execution can rely on it to
+    /// re-derive the partitioning instead of checking it.
+    fn is_deterministic(&self) -> bool {
+        false
+    }
 }
 
 /// An implementation of partitioner that splits the transactions into equal-sized chunks.
-pub struct UniformPartitioner {}
+/// If per-shard capacity weights are provided, the chunks are instead sized proportionally
+/// to the weights, e.g. to give more transactions to faster shards in heterogeneous clusters.
+#[derive(Default)]
+pub struct UniformPartitioner {
+    shard_weights: Option<Vec<usize>>,
+}
+
+impl UniformPartitioner {
+    pub fn new(shard_weights: Option<Vec<usize>>) -> Self {
+        if let Some(weights) = &shard_weights {
+            assert!(
+                weights.iter().sum::<usize>() > 0,
+                "At least one shard must have a non-zero weight"
+            );
+        }
+        Self { shard_weights }
+    }
+
+    /// Returns the number of transactions assigned to each shard, proportionally to the
+    /// weights. The remainder of the integer division is assigned one by one to the shards
+    /// with the largest fractional parts (ties broken by the lower shard index).
+    fn get_weighted_shard_sizes(weights: &[usize], total_txns: usize) -> Vec<usize> {
+        let total_weight: usize = weights.iter().sum();
+        let mut sizes: Vec<usize> = weights
+            .iter()
+            .map(|w| total_txns * w / total_weight)
+            .collect();
+
+        let mut remainders: Vec<(usize, usize)> = weights
+            .iter()
+            .enumerate()
+            .map(|(shard_id, w)| (total_txns * w % total_weight, shard_id))
+            .collect();
+        remainders.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+
+        let assigned: usize = sizes.iter().sum();
+        for (_, shard_id) in remainders.into_iter().take(total_txns - assigned) {
+            sizes[shard_id] += 1;
+        }
+        sizes
+    }
+}
 
 impl BlockPartitioner for UniformPartitioner {
     fn partition(
@@ -21,12 +74,124 @@ impl BlockPartitioner for UniformPartitioner {
         if total_txns == 0 {
             return vec![];
         }
+
+        if let Some(weights) = &self.shard_weights {
+            assert_eq!(
+                weights.len(),
+                num_shards,
+                "Number of shard weights must match the number of shards"
+            );
+            let mut shard_sizes = Self::get_weighted_shard_sizes(weights, total_txns);
+            // Trailing shards with no transactions are omitted, same as in the uniform case
+            // when the block is smaller than the number of shards. Other empty shards must
+            // be kept, so that the transactions are executed by the shard they are assigned to.
+            while shard_sizes.last() == Some(&0) {
+                shard_sizes.pop();
+            }
+            let mut txns_iter = transactions.into_iter();
+            return shard_sizes
+                .into_iter()
+                .map(|shard_size| txns_iter.by_ref().take(shard_size).collect())
+                .collect();
+        }
+
+        assert!(num_shards > 0, "Number of shards must be positive");
         let txns_per_shard = (total_txns as f64 / num_shards as f64).ceil() as usize;
 
         let mut result = Vec::new();
         for chunk in transactions.chunks(txns_per_shard) {
             result.push(chunk.to_vec());
         }
+        // With at least ceil(total / num_shards) transactions per chunk, there are never more
+        // chunks than shards.
+        debug_assert!(result.len() <= num_shards);
         result
     }
+
+    fn is_deterministic(&self) -> bool {
+        // Shards are contiguous chunks whose sizes only depend on the number of transactions.
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+
+    fn create_transactions(num_txns: usize) -> Vec<Transaction> {
+        (0..num_txns)
+            .map(|_| Transaction::StateCheckpoint(HashValue::random()))
+            .collect()
+    }
+
+    #[test]
+    fn test_uniform_partitioner() {
+        let partitioner = UniformPartitioner::default();
+        let partitions = partitioner.partition(create_transactions(8), 3);
+        let sizes: Vec<usize> = partitions.iter().map(|p| p.len()).collect();
+        assert_eq!(sizes, vec![3, 3, 2]);
+    }
+
+    #[test]
+    fn test_weighted_partitioner() {
+        let transactions = create_transactions(8);
+        let partitioner = UniformPartitioner::new(Some(vec![2, 1, 1]));
+        let partitions = partitioner.partition(transactions.clone(), 3);
+        let sizes: Vec<usize> = partitions.iter().map(|p| p.len()).collect();
+        assert_eq!(sizes, vec![4, 2, 2]);
+        // The order of transactions is preserved across the shards.
+        assert_eq!(partitions.concat(), transactions);
+
+        let partitioner = UniformPartitioner::new(Some(vec![3, 1, 1]));
+        let partitions = partitioner.partition(create_transactions(8), 3);
+        let sizes: Vec<usize> = partitions.iter().map(|p| p.len()).collect();
+        assert_eq!(sizes, vec![5, 2, 1]);
+    }
+
+    #[test]
+    fn test_more_shards_than_transactions() {
+        let transactions = create_transactions(2);
+        let num_shards = 4;
+
+        let partitions = UniformPartitioner::default().partition(transactions.clone(), num_shards);
+        assert!(partitions.len() <= num_shards);
+        assert_eq!(partitions.len(), 2);
+        assert_eq!(partitions.concat(), transactions);
+
+        // Empty shards before non-empty ones are kept, so transactions are assigned to the
+        // shards with a non-zero weight.
+        let partitions = UniformPartitioner::new(Some(vec![0, 1, 0, 1]))
+            .partition(transactions.clone(), num_shards);
+        assert!(partitions.len() <= num_shards);
+        assert_eq!(partitions, vec![
+            vec![],
+            vec![transactions[0].clone()],
+            vec![],
+            vec![transactions[1].clone()]
+        ]);
+
+        let partitions = UniformPartitioner::new(Some(vec![0, 1, 1, 0]))
+            .partition(transactions.clone(), num_shards);
+        assert_eq!(partitions, vec![
+            vec![],
+            vec![transactions[0].clone()],
+            vec![transactions[1].clone()]
+        ]);
+    }
+
+    #[test]
+    fn test_uniform_partitioner_is_deterministic() {
+        let transactions = create_transactions(10);
+        for partitioner in [
+            UniformPartitioner::default(),
+            UniformPartitioner::new(Some(vec![3, 1, 2])),
+        ] {
+            assert!(partitioner.is_deterministic());
+            assert_eq!(
+                partitioner.partition(transactions.clone(), 3),
+                partitioner.partition(transactions.clone(), 3)
+            );
+        }
+    }
 }
@@ -42,8 +42,32 @@ impl<S: StateView + Sync + Send + 'static> ShardedBlockExecutor<S> {
         num_executor_shards: usize,
         executor_threads_per_shard: Option<usize>,
         maybe_gas_limit: Option<u64>,
+    ) -> Self {
+        Self::new_with_shard_weights(
+            num_executor_shards,
+            executor_threads_per_shard,
+            maybe_gas_limit,
+            None,
+        )
+    }
+
+    /// Same as new, but if per-shard capacity weights (one per shard) are provided, the
+    /// transactions of a block are split proportionally to them instead of evenly, e.g. to
+    /// give more transactions to shards with more executor threads.
+    pub fn new_with_shard_weights(
+        num_executor_shards: usize,
+        executor_threads_per_shard: Option<usize>,
+        maybe_gas_limit: Option<u64>,
+        shard_weights: Option<Vec<usize>>,
     ) -> Self {
         assert!(num_executor_shards > 0, "num_executor_shards must be > 0");
+        if let Some(weights) = &shard_weights {
+            assert_eq!(
+                weights.len(),
+                num_executor_shards,
+                "Number of shard weights must match the number of shards"
+            );
+        }
         let executor_threads_per_shard = executor_threads_per_shard.unwrap_or_else(|| {
             (num_cpus::get() as f64 / num_executor_shards as f64).ceil() as usize
         });
@@ -70,7 +94,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedBlockExecutor<S> {
         );
         Self {
             num_executor_shards,
-            partitioner: Arc::new(UniformPartitioner {}),
+            partitioner: Arc::new(UniformPartitioner::new(shard_weights)),
             command_txs,
             shard_threads: shard_join_handles,
             result_rxs,
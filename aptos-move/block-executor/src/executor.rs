@@ -113,7 +113,9 @@ where
                 if !prev_modified_keys.remove(&k) {
                     updates_outside = true;
                 }
-                versioned_cache.write(&k, write_version, v);
+                versioned_cache
+                    .write(&k, write_version, v)
+                    .expect("[BlockSTM]: Committed transactions must not be re-executed");
             }
 
             // Then, apply deltas.
@@ -121,7 +123,9 @@ where
                 if !prev_modified_keys.remove(&k) {
                     updates_outside = true;
                 }
-                versioned_cache.add_delta(&k, idx_to_execute, d);
+                versioned_cache
+                    .add_delta(&k, idx_to_execute, d)
+                    .expect("[BlockSTM]: Committed transactions must not be re-executed");
             }
         };
 
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    types::{MVCodeError, MVCodeOutput, MVDataError, MVDataOutput, TxnIndex, Version},
+    types::{
+        Incarnation, MVBoundedDataError, MVCodeError, MVCodeOutput, MVDataError, MVDataOutput,
+        MVLenientDataOutput, MVSnapshot, MVWriteError, TxnIndex, Version, VersionEntry,
+    },
     versioned_code::VersionedCode,
     versioned_data::VersionedData,
 };
@@ -12,8 +15,21 @@ use aptos_types::{
     executable::{Executable, ExecutableDescriptor, ModulePath},
     write_set::TransactionWrite,
 };
-use std::{fmt::Debug, hash::Hash};
+#[cfg(feature = "contention-metrics")]
+use dashmap::DashMap;
+use either::Either;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Debug,
+    hash::Hash,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
+pub mod state_view;
 pub mod types;
 pub mod versioned_code;
 pub mod versioned_data;
@@ -34,6 +50,12 @@ mod unit_tests;
 pub struct MVHashMap<K, V: TransactionWrite, X: Executable> {
     data: VersionedData<K, V>,
     code: VersionedCode<K, V, X>,
+    // Number of transactions known to be committed (i.e. the commit watermark plus one), whose
+    // writes and deltas are rejected.
+    commit_watermark: AtomicU64,
+    // Number of fetch and write operations per key, see top_contended_keys.
+    #[cfg(feature = "contention-metrics")]
+    access_counts: DashMap<K, u64>,
 }
 
 impl<K: ModulePath + Hash + Clone + Eq + Debug, V: TransactionWrite, X: Executable>
@@ -42,18 +64,115 @@ impl<K: ModulePath + Hash + Clone + Eq + Debug, V: TransactionWrite, X: Executab
     // -----------------------------------
     // Functions shared for data and code.
 
-    // Option<VersionedCode> is passed to allow re-using code cache between blocks.
-    pub fn new(code_cache: Option<VersionedCode<K, V, X>>) -> MVHashMap<K, V, X> {
+    fn from_parts(data: VersionedData<K, V>, code: VersionedCode<K, V, X>) -> MVHashMap<K, V, X> {
         MVHashMap {
-            data: VersionedData::new(),
-            code: code_cache.unwrap_or_default(),
+            data,
+            code,
+            commit_watermark: AtomicU64::new(0),
+            #[cfg(feature = "contention-metrics")]
+            access_counts: DashMap::new(),
         }
     }
 
+    // Option<VersionedCode> is passed to allow re-using code cache between blocks.
+    pub fn new(code_cache: Option<VersionedCode<K, V, X>>) -> MVHashMap<K, V, X> {
+        Self::from_parts(VersionedData::new(), code_cache.unwrap_or_default())
+    }
+
+    /// Same as new, but the code map is a handle to the given shared code cache, so that the
+    /// MVHashMaps of concurrently executing blocks (e.g. in a pipelined executor) all read
+    /// from and publish to the same cache. Concurrent access is synchronized by the cache
+    /// itself, see clone_code_cache for the implications of sharing it.
+    pub fn new_with_shared_code(code_cache: Arc<VersionedCode<K, V, X>>) -> MVHashMap<K, V, X> {
+        Self::from_parts(VersionedData::new(), code_cache.shared_handle())
+    }
+
+    /// Same as new, but pre-allocates the underlying maps to hold the given number of keys,
+    /// avoiding resizing during execution of large blocks. The code capacity only applies
+    /// when a new code map is created, i.e. code_cache is None.
+    pub fn with_capacity(
+        code_cache: Option<VersionedCode<K, V, X>>,
+        data_capacity: usize,
+        code_capacity: usize,
+    ) -> MVHashMap<K, V, X> {
+        Self::from_parts(
+            VersionedData::with_capacity(data_capacity),
+            code_cache.unwrap_or_else(|| VersionedCode::with_capacity(code_capacity)),
+        )
+    }
+
+    /// Same as new, but the total size in bytes of the values written to the data map is
+    /// tracked (see data_bytes), e.g. to enforce a memory budget per block. Sizes are
+    /// computed from the raw bytes of each value, once when it is written.
+    pub fn new_with_size_accounting(
+        code_cache: Option<VersionedCode<K, V, X>>,
+    ) -> MVHashMap<K, V, X> {
+        Self::from_parts(
+            VersionedData::new().with_size_accounting(),
+            code_cache.unwrap_or_default(),
+        )
+    }
+
+    /// Returns the total size in bytes of the values of all writes currently recorded in
+    /// the data map (including estimates, excluding deltas), updated as entries are written
+    /// or deleted. Always 0 unless the MVHashMap was created by new_with_size_accounting.
+    pub fn data_bytes(&self) -> usize {
+        self.data.data_bytes()
+    }
+
+    /// Removes all writes and deltas from the data map, retaining its allocated capacity, so
+    /// that the data-structure can be reused for the next block. The code map is not
+    /// affected, preserving the code cache across blocks (see clear_code). The commit
+    /// watermark is reset, as transaction indices of the next block start over.
+    pub fn clear_data(&mut self) {
+        self.data.clear();
+        *self.commit_watermark.get_mut() = 0;
+    }
+
+    /// Removes all modules and executables from the code map, retaining its allocated
+    /// capacity. As the code map may be shared (see clone_code_cache), the removal is
+    /// observed through all handles to it.
+    pub fn clear_code(&mut self) {
+        self.code.clear();
+    }
+
     pub fn take(self) -> (VersionedData<K, V>, VersionedCode<K, V, X>) {
         (self.data, self.code)
     }
 
+    /// Returns a handle to the code cache, e.g. to be passed to the MVHashMap of the next
+    /// block, without consuming the data-structure (unlike take). The handle is not a copy:
+    /// it shares the underlying storage, so any module write, estimate, deletion or
+    /// executable recorded through either the returned handle or this MVHashMap is observed
+    /// through both. Hence, the caller must ensure the code of the current block is no
+    /// longer modified (or that the modifications are meant to be shared) once the next
+    /// block starts using the returned cache.
+    pub fn clone_code_cache(&self) -> VersionedCode<K, V, X> {
+        self.code.shared_handle()
+    }
+
+    /// Moves all versioned writes, deltas, modules and executables of 'other' into this
+    /// data-structure, preserving their transaction indices (e.g. to fold the outputs of
+    /// a pipeline stage, executed in a local MVHashMap, into a shared one). Panics if both
+    /// data-structures record an entry at the same key and transaction index. The code
+    /// is not merged if both share the same code cache (see clone_code_cache).
+    pub fn merge_from(&self, other: MVHashMap<K, V, X>) {
+        self.data.merge_from(other.data);
+        self.code.merge_from(other.code);
+    }
+
+    /// Consumes the data-structure and returns the materialized data, as observed by
+    /// a transaction at index 'up_to' (use the block size for the final state). Each
+    /// key's version chain is resolved once: writes and resolved aggregator values are
+    /// returned in the first map, while keys whose deltas could not be resolved without
+    /// a storage base value are returned in the second map. Code is discarded.
+    pub fn into_materialized(
+        self,
+        up_to: TxnIndex,
+    ) -> (HashMap<K, MVDataOutput<V>>, HashMap<K, DeltaOp>) {
+        self.data.into_materialized(up_to)
+    }
+
     /// Mark an entry from transaction 'txn_idx' at access path 'key' as an estimated write
     /// (for future incarnation). Will panic if the entry is not in the data-structure.
     pub fn mark_estimate(&self, key: &K, txn_idx: TxnIndex) {
@@ -63,35 +182,206 @@ impl<K: ModulePath + Hash + Clone + Eq + Debug, V: TransactionWrite, X: Executab
         }
     }
 
+    /// Mark an entry from transaction 'txn_idx' at data access path 'key' as aborted, i.e.
+    /// the transaction is known not to write it (unlike an estimate, no value is coming),
+    /// so reads skip the entry and resolve to the entries below it instead of waiting on
+    /// the transaction. The entry remains aborted until it is re-written or deleted. Will
+    /// panic if the entry is not in the data map (modules can't be marked as aborted).
+    pub fn mark_aborted(&self, key: &K, txn_idx: TxnIndex) {
+        self.data.mark_aborted(key, txn_idx);
+    }
+
     /// Delete an entry from transaction 'txn_idx' at access path 'key'. Will panic
     /// if the corresponding entry does not exist.
     pub fn delete(&self, key: &K, txn_idx: TxnIndex) {
         // This internally deserializes the path, TODO: fix.
+        self.delete_typed(key, key.module_path().is_some(), txn_idx);
+    }
+
+    /// Same as delete, but deletes from the code map if 'is_code' holds and from the data
+    /// map otherwise, for callers that already know the type of the key (avoids
+    /// deserializing the path).
+    pub fn delete_typed(&self, key: &K, is_code: bool, txn_idx: TxnIndex) {
+        if is_code {
+            self.code.delete(key, txn_idx);
+        } else {
+            self.data.delete(key, txn_idx);
+        }
+    }
+
+    /// Returns all entries currently recorded at access path 'key' in increasing order of
+    /// transaction indices, in code or data map according to the key. The entries are
+    /// captured under the key's lock, hence provide a consistent snapshot (for auditing
+    /// and debugging purposes).
+    pub fn version_history(&self, key: &K) -> Vec<(TxnIndex, VersionEntry<V>)> {
         match key.module_path() {
-            Some(_) => self.code.delete(key, txn_idx),
-            None => self.data.delete(key, txn_idx),
-        };
+            Some(_) => self.code.version_history(key),
+            None => self.data.version_history(key),
+        }
+    }
+
+    /// Returns the (sorted) indices of transactions that recorded a write (not a delta) at
+    /// access path 'key', in code or data map according to the key. Entries marked as
+    /// estimates are included. Used to detect write-write conflicts without reading values.
+    pub fn conflicting_writers(&self, key: &K) -> Vec<TxnIndex> {
+        match key.module_path() {
+            Some(_) => self.code.conflicting_writers(key),
+            None => self.data.conflicting_writers(key),
+        }
+    }
+
+    /// Returns the write (not a delta) with the highest transaction index at access path
+    /// 'key' across the whole block, i.e. not relative to a reading transaction, in code or
+    /// data map according to the key. Entries marked as estimates are skipped.
+    pub fn latest_write(&self, key: &K) -> Option<(TxnIndex, Arc<V>)> {
+        match key.module_path() {
+            Some(_) => self.code.latest_write(key),
+            None => self.data.latest_write(key),
+        }
+    }
+
+    /// Returns a histogram of version chain depths (number of entries recorded at a key),
+    /// mapping each depth to the number of keys with that depth, across both data and code
+    /// maps. Useful to tell a few hot keys apart from uniformly deep chains when tuning.
+    /// Each key's chain is read under its lock, but the scan is not an atomic snapshot.
+    pub fn version_depth_histogram(&self) -> BTreeMap<usize, usize> {
+        let mut histogram = BTreeMap::new();
+        for depth in self
+            .data
+            .version_depths()
+            .into_iter()
+            .chain(self.code.version_depths())
+        {
+            *histogram.entry(depth).or_insert(0) += 1;
+        }
+        histogram
+    }
+
+    /// Returns the number of entries currently marked as estimates across both data and code
+    /// maps, i.e. writes of aborted incarnations awaiting re-execution. A rising count
+    /// indicates heavy re-execution. Like version_depth_histogram, each key's chain is read
+    /// under its lock, but the scan is not an atomic snapshot.
+    pub fn pending_estimates(&self) -> usize {
+        self.data.num_estimates() + self.code.num_estimates()
+    }
+
+    /// Returns the 'n' keys with the most fetch and write operations so far (across data and
+    /// code), with their counts, in decreasing order of counts (ties in arbitrary order).
+    /// Hot keys drive the write-write conflicts behind re-executions, so this helps to
+    /// diagnose parallel execution slowdowns.
+    #[cfg(feature = "contention-metrics")]
+    pub fn top_contended_keys(&self, n: usize) -> Vec<(K, u64)> {
+        let mut counts: Vec<(K, u64)> = self
+            .access_counts
+            .iter()
+            .map(|entry| (entry.key().clone(), *entry.value()))
+            .collect();
+        counts.sort_by(|(_, a), (_, b)| b.cmp(a));
+        counts.truncate(n);
+        counts
+    }
+
+    #[cfg(feature = "contention-metrics")]
+    fn record_access(&self, key: &K) {
+        *self.access_counts.entry(key.clone()).or_insert(0) += 1;
+    }
+
+    #[cfg(not(feature = "contention-metrics"))]
+    #[inline]
+    fn record_access(&self, _key: &K) {}
+
+    /// Returns a serializable summary of all entries recorded in the data and code maps (see
+    /// MVSnapshot), e.g. for tests to assert that two executions produced the same state.
+    /// Each key's chain is read under its lock, but the scan is not an atomic snapshot, so
+    /// it should be taken while no transactions are executing.
+    pub fn snapshot(&self) -> MVSnapshot<K> {
+        MVSnapshot {
+            data: self.data.snapshot(),
+            code: self.code.snapshot(),
+        }
     }
 
     /// Add a versioned write at a specified key, in code or data map according to the key.
-    pub fn write(&self, key: &K, version: Version, value: V) {
+    /// Returns an error (and does not write) if the transaction is at or below the commit
+    /// watermark (see set_commit_watermark), so that committed outputs are never overwritten.
+    pub fn write(&self, key: &K, version: Version, value: V) -> Result<(), MVWriteError> {
+        self.write_typed(key, key.module_path().is_some(), version, value)
+    }
+
+    /// Same as write, but writes to the code map if 'is_code' holds and to the data map
+    /// otherwise, for callers that already know the type of the key (avoids deserializing
+    /// the path).
+    pub fn write_typed(
+        &self,
+        key: &K,
+        is_code: bool,
+        version: Version,
+        value: V,
+    ) -> Result<(), MVWriteError> {
+        self.check_commit_watermark(version.0)?;
+        self.record_access(key);
+        if is_code {
+            self.code.write(key, version.0, value);
+        } else {
+            self.data.write(key, version, value);
+        }
+        Ok(())
+    }
+
+    /// Records that all transactions up to (and including) 'watermark' are committed, so that
+    /// writes and deltas from their (aborted) incarnations are rejected. The watermark only
+    /// moves forward, i.e. setting a lower watermark has no effect.
+    pub fn set_commit_watermark(&self, watermark: TxnIndex) {
+        self.commit_watermark
+            .fetch_max(watermark as u64 + 1, Ordering::Relaxed);
+    }
+
+    fn check_commit_watermark(&self, txn_idx: TxnIndex) -> Result<(), MVWriteError> {
+        if (txn_idx as u64) < self.commit_watermark.load(Ordering::Relaxed) {
+            return Err(MVWriteError::BelowCommitWatermark(txn_idx));
+        }
+        Ok(())
+    }
+
+    /// Read at access path 'key', from the perspective of transaction 'txn_idx', from code
+    /// or data map according to the key. Returns the result of fetch_data for data keys
+    /// (Left), and the result of fetch_code for code keys (Right), so that generic callers
+    /// do not have to route on the module path themselves.
+    pub fn fetch_either(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> Either<
+        anyhow::Result<MVDataOutput<V>, MVDataError>,
+        anyhow::Result<MVCodeOutput<V, X>, MVCodeError>,
+    > {
+        self.record_access(key);
         match key.module_path() {
-            Some(_) => self.code.write(key, version.0, value),
-            None => self.data.write(key, version, value),
+            Some(_) => Either::Right(self.code.fetch_code(key, txn_idx)),
+            None => Either::Left(self.data.fetch_data(key, txn_idx)),
         }
     }
 
     // -----------------------------------------------
     // Functions specific to the multi-versioned data.
 
-    /// Add a delta at a specified key.
-    pub fn add_delta(&self, key: &K, txn_idx: TxnIndex, delta: DeltaOp) {
+    /// Add a delta at a specified key. Returns an error (and does not add the delta) if the
+    /// transaction is at or below the commit watermark (see set_commit_watermark).
+    pub fn add_delta(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+        delta: DeltaOp,
+    ) -> Result<(), MVWriteError> {
         debug_assert!(
             key.module_path().is_none(),
             "Delta must be stored at a path corresponding to data"
         );
 
+        self.check_commit_watermark(txn_idx)?;
+        self.record_access(key);
         self.data.add_delta(key, txn_idx, delta);
+        Ok(())
     }
 
     pub fn materialize_delta(&self, key: &K, txn_idx: TxnIndex) -> Result<u128, DeltaOp> {
@@ -118,9 +408,80 @@ impl<K: ModulePath + Hash + Clone + Eq + Debug, V: TransactionWrite, X: Executab
         key: &K,
         txn_idx: TxnIndex,
     ) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
+        self.record_access(key);
         self.data.fetch_data(key, txn_idx)
     }
 
+    /// Same as fetch_data, but a key written by the reading transaction itself (in its current
+    /// incarnation, not yet recorded in the map) is read from the given overlay of its writes,
+    /// mapping keys to the incarnation and the value. The overlay shadows the map, and such
+    /// self-reads are resolved without accessing the map (and its locks).
+    pub fn fetch_data_with_overlay(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+        overlay: &HashMap<K, (Incarnation, Arc<V>)>,
+    ) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
+        match overlay.get(key) {
+            Some((incarnation, value)) => Ok(MVDataOutput::Versioned(
+                (txn_idx, *incarnation),
+                value.clone(),
+            )),
+            None => self.fetch_data(key, txn_idx),
+        }
+    }
+
+    /// Same as fetch_data, but retries (yielding the thread in between) while the read
+    /// results in a dependency, up to 'max_retries' times. Returns ExhaustedRetries if the
+    /// dependency is still there, e.g. as a safety valve against cyclic dependencies.
+    pub fn fetch_data_bounded(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+        max_retries: usize,
+    ) -> anyhow::Result<MVDataOutput<V>, MVBoundedDataError> {
+        self.record_access(key);
+        let mut retries = 0;
+        loop {
+            match self.data.fetch_data(key, txn_idx) {
+                Ok(output) => return Ok(output),
+                Err(MVDataError::Dependency(dep_idx)) => {
+                    if retries == max_retries {
+                        return Err(MVBoundedDataError::ExhaustedRetries(dep_idx));
+                    }
+                    retries += 1;
+                    std::thread::yield_now();
+                },
+                Err(err) => return Err(MVBoundedDataError::Data(err)),
+            }
+        }
+    }
+
+    /// Same as fetch_data, but instead of returning the value read by transaction 'txn_idx'
+    /// at access path 'key', applies 'f' to a reference to it (Left), avoiding to hand out
+    /// a shared pointer for every read on hot paths. If the read resolves to an aggregator
+    /// value (deltas on top of a write), the value is returned instead (Right), and 'f' is
+    /// not called. Errors are the same as for fetch_data.
+    ///
+    /// 'f' is called while holding the lock of the key's shard, so it must be short, and
+    /// must not access the MVHashMap (e.g. read or write any key), which may deadlock.
+    pub fn with_data<R>(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+        f: impl FnOnce(&V) -> R,
+    ) -> anyhow::Result<Either<R, u128>, MVDataError> {
+        self.record_access(key);
+        self.data.with_data(key, txn_idx, f)
+    }
+
+    /// Same as fetch_data, but reads through estimates instead of returning a dependency
+    /// error, and reports whether an estimate was encountered. For read-only inspection
+    /// (e.g. diagnostics), must not be used during execution.
+    pub fn fetch_data_lenient(&self, key: &K, txn_idx: TxnIndex) -> MVLenientDataOutput<V> {
+        self.data.fetch_data_lenient(key, txn_idx)
+    }
+
     // ----------------------------------------------
     // Functions specific to the multi-versioned code.
 
@@ -131,13 +492,80 @@ impl<K: ModulePath + Hash + Clone + Eq + Debug, V: TransactionWrite, X: Executab
         self.code.store_executable(key, descriptor, executable);
     }
 
+    /// Read code at access path 'key', from the perspective of transaction 'txn_idx'.
+    /// Returns Err(Dependency(idx)) if the latest module below 'txn_idx' is an estimate by
+    /// transaction 'idx' (the caller must wait for it to be re-executed), and Err(NotFound)
+    /// if neither a module nor a storage-version executable is recorded (the caller should
+    /// fall back to storage).
     pub fn fetch_code(
         &self,
         key: &K,
         txn_idx: TxnIndex,
     ) -> anyhow::Result<MVCodeOutput<V, X>, MVCodeError> {
+        self.record_access(key);
         self.code.fetch_code(key, txn_idx)
     }
+
+    /// Same as fetch_code, but only returns the descriptor of the code that would be read,
+    /// i.e. Published(hash) if a module published in the block is read (whether or not its
+    /// executable is stored), and Storage if the storage-version executable is read. Neither
+    /// the module nor the executable is cloned, for callers that only need their identity.
+    pub fn fetch_code_descriptor(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> anyhow::Result<ExecutableDescriptor, MVCodeError> {
+        self.record_access(key);
+        self.code.fetch_code_descriptor(key, txn_idx)
+    }
+}
+
+impl<K, V, X> MVHashMap<K, V, X>
+where
+    K: ModulePath + Hash + Clone + Eq + Debug + Send + Sync + 'static,
+    V: TransactionWrite + Serialize + DeserializeOwned + Send + Sync + 'static,
+    X: Executable,
+{
+    /// Same as new, but if 'max_keys_in_memory' is provided, the data map is bounded in
+    /// memory: once more keys are in memory, the version chains of the least recently
+    /// written keys are spilled to a temporary file, and reloaded when accessed again.
+    /// Results are unaffected, but accesses to spilled keys incur file I/O, and all data
+    /// accesses take exclusive locks, so this should only be used when the data of a
+    /// block would not fit in memory otherwise.
+    pub fn new_with_spilling(
+        code_cache: Option<VersionedCode<K, V, X>>,
+        max_keys_in_memory: Option<usize>,
+    ) -> MVHashMap<K, V, X> {
+        let data = match max_keys_in_memory {
+            Some(max_keys_in_memory) => VersionedData::new().with_spilling(max_keys_in_memory),
+            None => VersionedData::new(),
+        };
+        Self::from_parts(data, code_cache.unwrap_or_default())
+    }
+}
+
+impl<K, V, X> MVHashMap<K, V, X>
+where
+    K: ModulePath + Hash + Clone + Eq + Debug,
+    V: TransactionWrite + PartialEq,
+    X: Executable,
+{
+    /// Same as new, but if 'dedup_identical_writes' is set, a data write is skipped when
+    /// the previous incarnation of the transaction wrote an equal value at the same key.
+    /// The recorded entry keeps its incarnation (and is no longer an estimate), so reads
+    /// of the value by higher transactions are not invalidated, avoiding re-validation
+    /// cascades when a re-execution produces the same output.
+    pub fn new_with_write_dedup(
+        code_cache: Option<VersionedCode<K, V, X>>,
+        dedup_identical_writes: bool,
+    ) -> MVHashMap<K, V, X> {
+        let data = if dedup_identical_writes {
+            VersionedData::new().with_write_dedup()
+        } else {
+            VersionedData::new()
+        };
+        Self::from_parts(data, code_cache.unwrap_or_default())
+    }
 }
 
 impl<K: ModulePath + Hash + Clone + Debug + Eq, V: TransactionWrite, X: Executable> Default
@@ -0,0 +1,98 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    types::{MVCodeError, MVDataError, MVDataOutput, TxnIndex},
+    MVHashMap,
+};
+use aptos_aggregator::delta_change_set::{serialize, DeltaOp};
+use aptos_state_view::TStateView;
+use aptos_types::{
+    executable::{Executable, ModulePath},
+    state_store::{state_storage_usage::StateStorageUsage, state_value::StateValue},
+    write_set::TransactionWrite,
+};
+use std::{fmt, fmt::Debug, hash::Hash};
+
+/// Returned (wrapped in anyhow::Error) by reads through MVHashMapStateView that can't be
+/// resolved from the multi-version data-structure alone.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MVStateViewError {
+    /// The read depends on the given transaction (an estimate was read), so the reader must
+    /// block until the transaction is re-executed.
+    Dependency(TxnIndex),
+    /// The read resulted in deltas that can only be resolved against the storage value.
+    Unresolved(DeltaOp),
+    /// Applying the deltas failed.
+    DeltaApplicationFailure,
+}
+
+impl fmt::Display for MVStateViewError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MVStateViewError::Dependency(idx) => write!(f, "Read depends on transaction {}", idx),
+            MVStateViewError::Unresolved(delta) => {
+                write!(f, "Read resulted in unresolved delta {:?}", delta)
+            },
+            MVStateViewError::DeltaApplicationFailure => write!(f, "Delta application failed"),
+        }
+    }
+}
+
+impl std::error::Error for MVStateViewError {}
+
+/// Exposes the reads of transaction 'txn_idx' from the multi-version data-structure as a
+/// state view, so that state view based code can read from it directly. Data is read by
+/// fetch_data, and code by the module (rather than the executable) recorded in the block.
+/// Keys not written in the block (or only recorded with a storage executable) read as
+/// None, i.e. the view does not fall back to storage.
+pub struct MVHashMapStateView<'a, K, V: TransactionWrite, X: Executable> {
+    map: &'a MVHashMap<K, V, X>,
+    txn_idx: TxnIndex,
+}
+
+impl<'a, K, V: TransactionWrite, X: Executable> MVHashMapStateView<'a, K, V, X> {
+    pub fn new(map: &'a MVHashMap<K, V, X>, txn_idx: TxnIndex) -> Self {
+        Self { map, txn_idx }
+    }
+}
+
+impl<'a, K, V, X> TStateView for MVHashMapStateView<'a, K, V, X>
+where
+    K: ModulePath + Hash + Clone + Eq + Debug,
+    V: TransactionWrite,
+    X: Executable,
+{
+    type Key = K;
+
+    fn get_state_value(&self, state_key: &K) -> anyhow::Result<Option<StateValue>> {
+        if state_key.module_path().is_some() {
+            return match self.map.code.fetch_module(state_key, self.txn_idx) {
+                Ok(module) => Ok(module.as_state_value()),
+                Err(MVCodeError::NotFound) => Ok(None),
+                Err(MVCodeError::Dependency(idx)) => Err(MVStateViewError::Dependency(idx).into()),
+            };
+        }
+
+        match self.map.fetch_data(state_key, self.txn_idx) {
+            Ok(MVDataOutput::Versioned(_, value)) => Ok(value.as_state_value()),
+            Ok(MVDataOutput::Resolved(value)) => {
+                Ok(Some(StateValue::new_legacy(serialize(&value))))
+            },
+            Err(MVDataError::NotFound) => Ok(None),
+            Err(MVDataError::Dependency(idx)) => Err(MVStateViewError::Dependency(idx).into()),
+            Err(MVDataError::Unresolved(delta)) => Err(MVStateViewError::Unresolved(delta).into()),
+            Err(MVDataError::DeltaApplicationFailure) => {
+                Err(MVStateViewError::DeltaApplicationFailure.into())
+            },
+        }
+    }
+
+    fn is_genesis(&self) -> bool {
+        false
+    }
+
+    fn get_usage(&self) -> anyhow::Result<StateStorageUsage> {
+        anyhow::bail!("State storage usage is not tracked by the multi-version data-structure")
+    }
+}
@@ -4,16 +4,24 @@
 use aptos_aggregator::delta_change_set::DeltaOp;
 use aptos_crypto::hash::HashValue;
 use aptos_types::executable::ExecutableDescriptor;
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::Arc,
+};
 
 pub type TxnIndex = u32;
 pub type Incarnation = u32;
 pub type Version = (TxnIndex, Incarnation);
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(crate) enum Flag {
     Done,
     Estimate,
+    // The writing transaction was aborted, and the entry is skipped by reads until it is
+    // re-written or deleted (only for data entries).
+    Aborted,
 }
 
 /// Returned as Err(..) when failed to read from the multi-version data-structure.
@@ -29,6 +37,23 @@ pub enum MVDataError {
     DeltaApplicationFailure,
 }
 
+/// Returned as Err(..) by `MVHashMap::fetch_data_bounded`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MVBoundedDataError {
+    /// The read failed with an error other than a dependency.
+    Data(MVDataError),
+    /// The read still resulted in a dependency on the given transaction after the
+    /// maximum number of retries.
+    ExhaustedRetries(TxnIndex),
+}
+
+/// Returned as Err(..) by `MVHashMap::write` and `MVHashMap::add_delta`.
+#[derive(Debug, PartialEq, Eq)]
+pub enum MVWriteError {
+    /// The writing transaction is at or below the commit watermark.
+    BelowCommitWatermark(TxnIndex),
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub enum MVCodeError {
     /// No prior entry is found.
@@ -49,6 +74,60 @@ pub enum MVDataOutput<V> {
     Versioned(Version, Arc<V>),
 }
 
+/// Returned by `MVHashMap::fetch_data_lenient`, for inspection purposes only.
+#[derive(Debug, PartialEq, Eq)]
+pub struct MVLenientDataOutput<V> {
+    /// Result of the read, where entries marked as estimates were read through (using
+    /// their recorded contents), i.e. it never contains a `Dependency` error. Deltas that
+    /// could not be resolved are returned accumulated as `Unresolved`.
+    pub output: anyhow::Result<MVDataOutput<V>, MVDataError>,
+    /// Whether an estimate was encountered, i.e. the output may not be final.
+    pub encountered_estimate: bool,
+}
+
+/// Describes a single entry recorded at a key, as returned by `MVHashMap::version_history`.
+/// Entries removed by `delete` are no longer recorded, and hence not reported.
+#[derive(Debug, PartialEq, Eq)]
+pub enum VersionEntry<V> {
+    /// A data write with the incarnation of the writing transaction and the written value.
+    Write(Incarnation, Arc<V>),
+    /// An aggregator delta, with the materialized value if the delta has been committed.
+    Delta(DeltaOp, Option<u128>),
+    /// A module write with the (cryptographic) hash of the module.
+    Module(Arc<V>, HashValue),
+    /// An entry that was marked as an estimate, i.e. the transaction is being re-executed.
+    Estimate,
+    /// A data entry that was marked as aborted, i.e. it is skipped by reads.
+    Aborted,
+}
+
+/// Summary of a single entry recorded at a key, as captured by `MVHashMap::snapshot`.
+/// Incarnations and delta shortcuts are omitted, so that runs that re-executed transactions
+/// a different number of times, but produced the same outputs, have equal snapshots.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MVSnapshotEntry {
+    /// A data write, summarized by the written bytes (None for a deletion).
+    Write(Option<Vec<u8>>),
+    /// An aggregator delta.
+    Delta(DeltaOp),
+    /// A module write, summarized by the (cryptographic) hash of the module.
+    Module(HashValue),
+    /// An entry that was marked as an estimate.
+    Estimate,
+    /// A data entry that was marked as aborted.
+    Aborted,
+}
+
+/// Serializable summary of the contents of the multi-version data-structure, returned by
+/// `MVHashMap::snapshot`, mapping each key to the entries recorded at it by transaction
+/// index. Keys without entries are omitted. Executables are not captured. Comparing
+/// snapshots (e.g. of two runs in tests) does not depend on the order of the writes.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MVSnapshot<K: Hash + Eq> {
+    pub data: HashMap<K, BTreeMap<TxnIndex, MVSnapshotEntry>>,
+    pub code: HashMap<K, BTreeMap<TxnIndex, MVSnapshotEntry>>,
+}
+
 /// Returned as Ok(..) when read successfully from the multi-version data-structure.
 #[derive(Debug, PartialEq, Eq)]
 pub enum MVCodeOutput<M, X> {
@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{
-    types::{Incarnation, MVDataError, MVDataOutput, TxnIndex},
+    types::{Incarnation, MVBoundedDataError, MVDataError, MVDataOutput, TxnIndex},
     *,
 };
 use aptos_aggregator::{
@@ -12,15 +12,17 @@ use aptos_aggregator::{
 };
 use aptos_types::{
     access_path::AccessPath,
+    account_address::AccountAddress,
     executable::{ExecutableTestType, ModulePath},
     state_store::state_value::StateValue,
 };
 use claims::{assert_err_eq, assert_ok_eq};
-use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, sync::Arc};
 
 mod proptest_types;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct Value(Vec<u32>);
 
 impl TransactionWrite for Value {
@@ -36,7 +38,7 @@ impl TransactionWrite for Value {
     }
 
     fn as_state_value(&self) -> Option<StateValue> {
-        unimplemented!()
+        self.extract_raw_bytes().map(StateValue::new_legacy)
     }
 }
 
@@ -96,7 +98,7 @@ fn create_write_read_placeholder_struct() {
     assert_eq!(Err(NotFound), r_db);
 
     // Write by txn 10.
-    mvtbl.write(&ap1, (10, 1), value_for(10, 1));
+    mvtbl.write(&ap1, (10, 1), value_for(10, 1)).unwrap();
 
     // Reads that should go the DB return Err(NotFound)
     let r_db = mvtbl.fetch_data(&ap1, 9);
@@ -110,17 +112,17 @@ fn create_write_read_placeholder_struct() {
     assert_eq!(Ok(Versioned((10, 1), arc_value_for(10, 1))), r_10);
 
     // More deltas.
-    mvtbl.add_delta(&ap1, 11, delta_add(11, u128::MAX));
-    mvtbl.add_delta(&ap1, 12, delta_add(12, u128::MAX));
-    mvtbl.add_delta(&ap1, 13, delta_sub(74, u128::MAX));
+    mvtbl.add_delta(&ap1, 11, delta_add(11, u128::MAX)).unwrap();
+    mvtbl.add_delta(&ap1, 12, delta_add(12, u128::MAX)).unwrap();
+    mvtbl.add_delta(&ap1, 13, delta_sub(74, u128::MAX)).unwrap();
 
     // Reads have to go traverse deltas until a write is found.
     let r_sum = mvtbl.fetch_data(&ap1, 14);
     assert_eq!(Ok(Resolved(u128_for(10, 1) + 11 + 12 - (61 + 13))), r_sum);
 
     // More writes.
-    mvtbl.write(&ap1, (12, 0), value_for(12, 0));
-    mvtbl.write(&ap1, (8, 3), value_for(8, 3));
+    mvtbl.write(&ap1, (12, 0), value_for(12, 0)).unwrap();
+    mvtbl.write(&ap1, (8, 3), value_for(8, 3)).unwrap();
 
     // Verify reads.
     let r_12 = mvtbl.fetch_data(&ap1, 15);
@@ -143,15 +145,15 @@ fn create_write_read_placeholder_struct() {
 
     // Delete the entry written by 10, write to a different ap.
     mvtbl.delete(&ap1, 10);
-    mvtbl.write(&ap2, (10, 2), value_for(10, 2));
+    mvtbl.write(&ap2, (10, 2), value_for(10, 2)).unwrap();
 
     // Read by txn 11 no longer observes entry from txn 10.
     let r_8 = mvtbl.fetch_data(&ap1, 11);
     assert_eq!(Ok(Versioned((8, 3), arc_value_for(8, 3))), r_8);
 
     // Reads, writes for ap2 and ap3.
-    mvtbl.write(&ap2, (5, 0), value_for(5, 0));
-    mvtbl.write(&ap3, (20, 4), value_for(20, 4));
+    mvtbl.write(&ap2, (5, 0), value_for(5, 0)).unwrap();
+    mvtbl.write(&ap3, (20, 4), value_for(20, 4)).unwrap();
     let r_5 = mvtbl.fetch_data(&ap2, 10);
     assert_eq!(Ok(Versioned((5, 0), arc_value_for(5, 0))), r_5);
     let r_20 = mvtbl.fetch_data(&ap3, 21);
@@ -175,16 +177,18 @@ fn create_write_read_placeholder_struct() {
     assert_eq!(Ok(Versioned((10, 2), arc_value_for(10, 2))), r_10);
 
     // Both delta-write and delta-delta application failures are detected.
-    mvtbl.add_delta(&ap1, 30, delta_add(30, 32));
-    mvtbl.add_delta(&ap1, 31, delta_add(31, 32));
+    mvtbl.add_delta(&ap1, 30, delta_add(30, 32)).unwrap();
+    mvtbl.add_delta(&ap1, 31, delta_add(31, 32)).unwrap();
     let r_33 = mvtbl.fetch_data(&ap1, 33);
     assert_eq!(Err(DeltaApplicationFailure), r_33);
 
     let val = value_for(10, 3);
     // sub base sub_for for which should underflow.
     let sub_base = AggregatorValue::from_write(&val).unwrap().into();
-    mvtbl.write(&ap2, (10, 3), val);
-    mvtbl.add_delta(&ap2, 30, delta_sub(30 + sub_base, u128::MAX));
+    mvtbl.write(&ap2, (10, 3), val).unwrap();
+    mvtbl
+        .add_delta(&ap2, 30, delta_sub(30 + sub_base, u128::MAX))
+        .unwrap();
     let r_31 = mvtbl.fetch_data(&ap2, 31);
     assert_eq!(Err(DeltaApplicationFailure), r_31);
 }
@@ -267,3 +271,1020 @@ fn commit_without_entry() {
     // Must panic as there is no delta at provided index.
     let _ = vd.materialize_delta(&ap, 9);
 }
+
+#[test]
+fn into_materialized_matches_fetch_data() {
+    use MVDataOutput::*;
+
+    let ap1 = KeyType(b"/foo/b".to_vec());
+    let ap2 = KeyType(b"/foo/c".to_vec());
+    let ap3 = KeyType(b"/foo/d".to_vec());
+    let ap4 = KeyType(b"/foo/e".to_vec());
+    let keys = [ap1.clone(), ap2.clone(), ap3.clone(), ap4.clone()];
+    let block_size = 10;
+    let limit = 10000;
+
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+
+    // ap1: versioned writes, latest one wins.
+    mvtbl.write(&ap1, (2, 0), value_for(2, 0)).unwrap();
+    mvtbl.write(&ap1, (7, 1), value_for(7, 1)).unwrap();
+    // ap2: a write followed by deltas, resolves to an aggregator value.
+    mvtbl.write(&ap2, (3, 0), value_for(3, 0)).unwrap();
+    mvtbl.add_delta(&ap2, 5, delta_add(10, u128::MAX)).unwrap();
+    mvtbl.add_delta(&ap2, 8, delta_add(20, u128::MAX)).unwrap();
+    // ap3: only deltas without a base value, remains unresolved.
+    mvtbl.add_delta(&ap3, 1, delta_add(5, limit)).unwrap();
+    mvtbl.add_delta(&ap3, 4, delta_add(15, limit)).unwrap();
+    // ap4: only deltas, with a base value set.
+    mvtbl.add_delta(&ap4, 6, delta_add(25, limit)).unwrap();
+    mvtbl.set_aggregator_base_value(&ap4, 100);
+
+    let expected: Vec<_> = keys
+        .iter()
+        .map(|k| mvtbl.fetch_data(k, block_size))
+        .collect();
+    let (outputs, deltas) = mvtbl.into_materialized(block_size);
+
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(deltas.len(), 1);
+    for (key, expected) in keys.iter().zip(expected) {
+        match expected {
+            Err(MVDataError::Unresolved(op)) => assert_eq!(deltas.get(key), Some(&op)),
+            Ok(output) => assert_eq!(outputs.get(key), Some(&output)),
+            Err(e) => unreachable!("Unexpected fetch_data error {:?}", e),
+        }
+    }
+    assert_eq!(
+        outputs.get(&ap1),
+        Some(&Versioned((7, 1), arc_value_for(7, 1)))
+    );
+    assert_eq!(outputs.get(&ap2), Some(&Resolved(u128_for(3, 0) + 30)));
+    assert_eq!(outputs.get(&ap4), Some(&Resolved(125)));
+}
+
+#[test]
+fn version_history_reflects_operations() {
+    use crate::types::VersionEntry::*;
+
+    let ap = KeyType(b"/foo/b".to_vec());
+    let limit = 10000;
+
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert!(mvtbl.version_history(&ap).is_empty());
+
+    mvtbl.write(&ap, (3, 0), value_for(3, 0)).unwrap();
+    mvtbl.add_delta(&ap, 5, delta_add(10, limit)).unwrap();
+    mvtbl.write(&ap, (7, 1), value_for(7, 1)).unwrap();
+    mvtbl.write(&ap, (9, 2), value_for(9, 2)).unwrap();
+    assert_eq!(mvtbl.version_history(&ap), vec![
+        (3, Write(0, arc_value_for(3, 0))),
+        (5, Delta(delta_add(10, limit), None)),
+        (7, Write(1, arc_value_for(7, 1))),
+        (9, Write(2, arc_value_for(9, 2))),
+    ]);
+
+    // Estimates are reported, and deleted entries no longer appear.
+    mvtbl.mark_estimate(&ap, 7);
+    mvtbl.delete(&ap, 9);
+    assert_eq!(mvtbl.version_history(&ap), vec![
+        (3, Write(0, arc_value_for(3, 0))),
+        (5, Delta(delta_add(10, limit), None)),
+        (7, Estimate),
+    ]);
+
+    // A committed delta reports its materialized value.
+    mvtbl.delete(&ap, 7);
+    assert_ok_eq!(mvtbl.materialize_delta(&ap, 5), u128_for(3, 0) + 10);
+    assert_eq!(mvtbl.version_history(&ap), vec![
+        (3, Write(0, arc_value_for(3, 0))),
+        (5, Delta(delta_add(10, limit), Some(u128_for(3, 0) + 10))),
+    ]);
+}
+
+#[test]
+fn fetch_code_dependency_and_not_found() {
+    use MVCodeError::*;
+    use MVCodeOutput::*;
+
+    let vc: VersionedCode<KeyType<Vec<u8>>, Value, ExecutableTestType> = VersionedCode::new();
+    let ap = KeyType(b"/foo/b".to_vec());
+    let other_ap = KeyType(b"/foo/c".to_vec());
+
+    // Misses at an unknown path and below the first module write.
+    // (ExecutableTestType is not Debug, hence matches! instead of assert_err_eq!).
+    assert!(matches!(vc.fetch_code(&other_ap, 10), Err(NotFound)));
+    vc.write(&ap, 5, value_for(5, 0));
+    assert!(matches!(vc.fetch_code(&ap, 5), Err(NotFound)));
+    assert!(matches!(vc.fetch_code(&ap, 6), Ok(Module((m, _))) if m == arc_value_for(5, 0)));
+
+    // A module marked as an estimate results in a dependency on the writing transaction.
+    vc.mark_estimate(&ap, 5);
+    assert!(matches!(vc.fetch_code(&ap, 6), Err(Dependency(5))));
+    assert!(matches!(vc.fetch_code(&ap, 5), Err(NotFound)));
+}
+
+#[derive(Clone, Eq, Hash, PartialEq, Debug, Serialize, Deserialize)]
+enum MixedKey {
+    Data(Vec<u8>),
+    Code(Vec<u8>),
+}
+
+impl ModulePath for MixedKey {
+    fn module_path(&self) -> Option<AccessPath> {
+        match self {
+            MixedKey::Data(_) => None,
+            MixedKey::Code(path) => Some(AccessPath::new(AccountAddress::ONE, path.clone())),
+        }
+    }
+}
+
+#[test]
+fn fetch_either_routes_by_module_path() {
+    let data_ap = MixedKey::Data(b"/foo/b".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.write(&data_ap, (3, 0), value_for(3, 0)).unwrap();
+    mvtbl.write(&code_ap, (4, 0), value_for(4, 0)).unwrap();
+
+    assert!(matches!(
+        mvtbl.fetch_either(&data_ap, 5),
+        Either::Left(Ok(MVDataOutput::Versioned((3, 0), v))) if v == arc_value_for(3, 0)
+    ));
+    assert!(matches!(
+        mvtbl.fetch_either(&code_ap, 5),
+        Either::Right(Ok(MVCodeOutput::Module((m, _)))) if m == arc_value_for(4, 0)
+    ));
+    assert!(matches!(
+        mvtbl.fetch_either(&data_ap, 3),
+        Either::Left(Err(MVDataError::NotFound))
+    ));
+    assert!(matches!(
+        mvtbl.fetch_either(&code_ap, 4),
+        Either::Right(Err(MVCodeError::NotFound))
+    ));
+}
+
+#[test]
+fn fetch_data_lenient_reads_through_estimate() {
+    let ap = KeyType(b"/foo/f".to_vec());
+    let limit = 10000;
+
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.add_delta(&ap, 1, delta_add(5, limit)).unwrap();
+    mvtbl.add_delta(&ap, 2, delta_add(10, limit)).unwrap();
+    mvtbl.add_delta(&ap, 3, delta_add(20, limit)).unwrap();
+    mvtbl.mark_estimate(&ap, 2);
+
+    assert_err_eq!(mvtbl.fetch_data(&ap, 4), MVDataError::Dependency(2));
+
+    // Without a base value, the accumulated deltas are returned.
+    let lenient = mvtbl.fetch_data_lenient(&ap, 4);
+    assert!(lenient.encountered_estimate);
+    match_unresolved(lenient.output, DeltaUpdate::Plus(35));
+
+    mvtbl.set_aggregator_base_value(&ap, 100);
+    let lenient = mvtbl.fetch_data_lenient(&ap, 4);
+    assert!(lenient.encountered_estimate);
+    assert_ok_eq!(lenient.output, MVDataOutput::Resolved(135));
+
+    // Reads below the estimate are not affected.
+    let lenient = mvtbl.fetch_data_lenient(&ap, 2);
+    assert!(!lenient.encountered_estimate);
+    assert_ok_eq!(lenient.output, MVDataOutput::Resolved(105));
+}
+
+#[test]
+fn with_capacity_reads_and_writes() {
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> =
+        MVHashMap::with_capacity(None, 16, 4);
+
+    // Write more keys than the capacity, to also exercise resizing.
+    for i in 0..64u32 {
+        mvtbl
+            .write(&KeyType(i.to_be_bytes().to_vec()), (i, 0), value_for(i, 0))
+            .unwrap();
+    }
+    for i in 0..64u32 {
+        assert_ok_eq!(
+            mvtbl.fetch_data(&KeyType(i.to_be_bytes().to_vec()), i + 1),
+            MVDataOutput::Versioned((i, 0), arc_value_for(i, 0))
+        );
+    }
+}
+
+#[test]
+fn conflicting_writers_ignores_deltas() {
+    let data_ap = MixedKey::Data(b"/foo/b".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert!(mvtbl.conflicting_writers(&data_ap).is_empty());
+
+    mvtbl.write(&data_ap, (9, 0), value_for(9, 0)).unwrap();
+    mvtbl.write(&data_ap, (2, 0), value_for(2, 0)).unwrap();
+    mvtbl.add_delta(&data_ap, 3, delta_add(5, 1000)).unwrap();
+    mvtbl.write(&data_ap, (5, 1), value_for(5, 1)).unwrap();
+    mvtbl.mark_estimate(&data_ap, 5);
+    assert_eq!(mvtbl.conflicting_writers(&data_ap), vec![2, 5, 9]);
+
+    mvtbl.write(&code_ap, (5, 0), value_for(5, 0)).unwrap();
+    mvtbl.write(&code_ap, (2, 0), value_for(2, 0)).unwrap();
+    assert_eq!(mvtbl.conflicting_writers(&code_ap), vec![2, 5]);
+}
+
+#[test]
+fn version_depth_histogram_across_data_and_code() {
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert!(mvtbl.version_depth_histogram().is_empty());
+
+    let data_ap = MixedKey::Data(b"/foo/b".to_vec());
+    let aggregator_ap = MixedKey::Data(b"/foo/aggregator".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+
+    mvtbl.write(&data_ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl
+        .add_delta(&aggregator_ap, 1, delta_add(5, 1000))
+        .unwrap();
+    mvtbl
+        .add_delta(&aggregator_ap, 4, delta_add(5, 1000))
+        .unwrap();
+    mvtbl
+        .write(&aggregator_ap, (6, 0), value_for(6, 0))
+        .unwrap();
+    mvtbl.write(&code_ap, (3, 0), value_for(3, 0)).unwrap();
+
+    assert_eq!(
+        mvtbl.version_depth_histogram(),
+        BTreeMap::from([(1, 2), (3, 1)])
+    );
+}
+
+#[test]
+fn pending_estimates_across_data_and_code() {
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    let data_ap = MixedKey::Data(b"/foo/b".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+
+    mvtbl.write(&data_ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&data_ap, (2, 0), value_for(2, 0)).unwrap();
+    mvtbl.write(&code_ap, (3, 0), value_for(3, 0)).unwrap();
+    assert_eq!(mvtbl.pending_estimates(), 0);
+
+    mvtbl.mark_estimate(&data_ap, 2);
+    mvtbl.mark_estimate(&code_ap, 3);
+    assert_eq!(mvtbl.pending_estimates(), 2);
+
+    // Re-execution writing over the estimate clears it.
+    mvtbl.write(&data_ap, (2, 1), value_for(2, 1)).unwrap();
+    assert_eq!(mvtbl.pending_estimates(), 1);
+}
+
+#[test]
+fn identical_writes_are_deduplicated() {
+    use MVDataOutput::*;
+
+    let ap = KeyType(b"/foo/b".to_vec());
+    let value = || Value(vec![1, 2, 3]);
+
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> =
+        MVHashMap::new_with_write_dedup(None, true);
+    mvtbl.write(&ap, (3, 0), value()).unwrap();
+    assert_eq!(
+        mvtbl.fetch_data(&ap, 5),
+        Ok(Versioned((3, 0), Arc::new(value())))
+    );
+
+    // Re-execution writes the same value: the read version is unchanged, so readers that
+    // recorded it remain valid, and the estimate no longer blocks them.
+    mvtbl.mark_estimate(&ap, 3);
+    assert_eq!(mvtbl.fetch_data(&ap, 5), Err(MVDataError::Dependency(3)));
+    mvtbl.write(&ap, (3, 1), value()).unwrap();
+    assert_eq!(
+        mvtbl.fetch_data(&ap, 5),
+        Ok(Versioned((3, 0), Arc::new(value())))
+    );
+
+    // A different value is written as usual.
+    mvtbl.write(&ap, (3, 2), value_for(3, 2)).unwrap();
+    assert_eq!(
+        mvtbl.fetch_data(&ap, 5),
+        Ok(Versioned((3, 2), arc_value_for(3, 2)))
+    );
+
+    // Without deduplication, the same value is re-inserted with the new incarnation.
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> =
+        MVHashMap::new_with_write_dedup(None, false);
+    mvtbl.write(&ap, (3, 0), value()).unwrap();
+    mvtbl.write(&ap, (3, 1), value()).unwrap();
+    assert_eq!(
+        mvtbl.fetch_data(&ap, 5),
+        Ok(Versioned((3, 1), Arc::new(value())))
+    );
+}
+
+#[test]
+fn data_map_modes_combine() {
+    let ap = KeyType(b"/foo/b".to_vec());
+    let vd: VersionedData<KeyType<Vec<u8>>, Value> = VersionedData::new()
+        .with_write_dedup()
+        .with_size_accounting();
+
+    // The raw bytes of test values are always 16 bytes long.
+    vd.write(&ap, (3, 0), value_for(3, 0));
+    vd.mark_estimate(&ap, 3);
+    vd.write(&ap, (3, 1), value_for(3, 0));
+    assert_eq!(
+        vd.fetch_data(&ap, 5),
+        Ok(MVDataOutput::Versioned((3, 0), arc_value_for(3, 0)))
+    );
+    assert_eq!(vd.data_bytes(), 16);
+}
+
+#[test]
+fn latest_write_across_block() {
+    let data_ap = MixedKey::Data(b"/foo/b".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert!(mvtbl.latest_write(&data_ap).is_none());
+
+    mvtbl.write(&data_ap, (4, 0), value_for(4, 0)).unwrap();
+    mvtbl.write(&data_ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&data_ap, (7, 0), value_for(7, 0)).unwrap();
+    assert_eq!(mvtbl.latest_write(&data_ap), Some((7, arc_value_for(7, 0))));
+
+    // Deltas and estimates are skipped.
+    mvtbl.add_delta(&data_ap, 9, delta_add(5, 1000)).unwrap();
+    mvtbl.mark_estimate(&data_ap, 7);
+    assert_eq!(mvtbl.latest_write(&data_ap), Some((4, arc_value_for(4, 0))));
+
+    mvtbl.write(&code_ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&code_ap, (7, 0), value_for(7, 0)).unwrap();
+    assert_eq!(mvtbl.latest_write(&code_ap), Some((7, arc_value_for(7, 0))));
+}
+
+#[test]
+fn snapshots_of_equivalent_writes_are_equal() {
+    let data_ap = MixedKey::Data(b"/foo/b".to_vec());
+    let aggregator_ap = MixedKey::Data(b"/foo/aggregator".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+
+    let mvtbl_1: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl_1.write(&data_ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl_1.write(&data_ap, (3, 0), value_for(3, 0)).unwrap();
+    mvtbl_1
+        .add_delta(&aggregator_ap, 2, delta_add(5, 1000))
+        .unwrap();
+    mvtbl_1.write(&code_ap, (4, 0), value_for(4, 0)).unwrap();
+    mvtbl_1.write(&data_ap, (5, 0), value_for(5, 0)).unwrap();
+    mvtbl_1.mark_estimate(&data_ap, 5);
+
+    // Same entries, written in a different order, and with transaction 3 re-executed.
+    let mvtbl_2: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl_2.write(&data_ap, (5, 0), value_for(5, 0)).unwrap();
+    mvtbl_2.write(&code_ap, (4, 0), value_for(4, 0)).unwrap();
+    mvtbl_2.write(&data_ap, (3, 0), value_for(3, 0)).unwrap();
+    mvtbl_2.mark_estimate(&data_ap, 3);
+    mvtbl_2.write(&data_ap, (3, 1), value_for(3, 0)).unwrap();
+    mvtbl_2
+        .add_delta(&aggregator_ap, 2, delta_add(5, 1000))
+        .unwrap();
+    mvtbl_2.write(&data_ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl_2.mark_estimate(&data_ap, 5);
+
+    let snapshot = mvtbl_1.snapshot();
+    assert_eq!(snapshot.data.len(), 2);
+    assert_eq!(snapshot.code.len(), 1);
+    assert_eq!(snapshot, mvtbl_2.snapshot());
+    assert_eq!(
+        bcs::from_bytes::<MVSnapshot<MixedKey>>(&bcs::to_bytes(&snapshot).unwrap()).unwrap(),
+        snapshot
+    );
+
+    mvtbl_2
+        .add_delta(&aggregator_ap, 6, delta_add(1, 1000))
+        .unwrap();
+    assert_ne!(snapshot, mvtbl_2.snapshot());
+}
+
+#[test]
+fn with_data_borrows_value() {
+    use std::{
+        collections::hash_map::DefaultHasher,
+        hash::{Hash, Hasher},
+    };
+
+    fn hash_of(value: &Value) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.0.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    let ap = KeyType(b"/foo/b".to_vec());
+    let aggregator_ap = KeyType(b"/foo/aggregator".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert_err_eq!(mvtbl.with_data(&ap, 5, hash_of), MVDataError::NotFound);
+
+    let large_value = Value((0..100_000).collect());
+    let expected_hash = hash_of(&large_value);
+    mvtbl.write(&ap, (1, 0), large_value).unwrap();
+    assert_ok_eq!(
+        mvtbl.with_data(&ap, 5, hash_of),
+        Either::Left(expected_hash)
+    );
+    // The value is not cloned nor shared by the read.
+    let (_, value) = mvtbl.latest_write(&ap).unwrap();
+    assert_ok_eq!(
+        mvtbl.with_data(&ap, 5, |_| Arc::strong_count(&value)),
+        Either::Left(2)
+    );
+
+    mvtbl.mark_estimate(&ap, 1);
+    assert_err_eq!(mvtbl.with_data(&ap, 5, hash_of), MVDataError::Dependency(1));
+
+    // Aggregator values are returned instead of calling the closure.
+    mvtbl
+        .write(&aggregator_ap, (1, 0), value_for(1, 0))
+        .unwrap();
+    mvtbl
+        .add_delta(&aggregator_ap, 2, delta_add(5, u128::MAX))
+        .unwrap();
+    assert_ok_eq!(
+        mvtbl.with_data(&aggregator_ap, 5, |_| unreachable!()),
+        Either::<(), u128>::Right(u128_for(1, 0) + 5)
+    );
+}
+
+#[test]
+fn cloned_code_cache_is_shared() {
+    let ap = MixedKey::Code(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    let next_mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> =
+        MVHashMap::new(Some(mvtbl.clone_code_cache()));
+
+    // An executable stored through one handle can be fetched through the other.
+    mvtbl.store_executable(
+        &ap,
+        ExecutableDescriptor::Storage,
+        ExecutableTestType::default(),
+    );
+    assert!(matches!(
+        next_mvtbl.fetch_code(&ap, 0),
+        Ok(MVCodeOutput::Executable((_, ExecutableDescriptor::Storage)))
+    ));
+
+    // The same holds for module writes, in both directions.
+    next_mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    assert!(matches!(
+        mvtbl.fetch_code(&ap, 2),
+        Ok(MVCodeOutput::Module((_, _)))
+    ));
+    assert_eq!(mvtbl.latest_write(&ap), Some((1, arc_value_for(1, 0))));
+}
+
+#[test]
+fn data_bytes_are_accounted() {
+    let ap1 = KeyType(b"/foo/b".to_vec());
+    let ap2 = KeyType(b"/foo/c".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> =
+        MVHashMap::new_with_size_accounting(None);
+    assert_eq!(mvtbl.data_bytes(), 0);
+
+    // The raw bytes of test values are always 16 bytes long.
+    mvtbl.write(&ap1, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&ap2, (2, 0), value_for(2, 0)).unwrap();
+    assert_eq!(mvtbl.data_bytes(), 32);
+
+    // Re-executions replace the previous write, and deltas have no size.
+    mvtbl.write(&ap1, (1, 1), value_for(1, 1)).unwrap();
+    mvtbl.add_delta(&ap2, 3, delta_add(5, 1000)).unwrap();
+    assert_eq!(mvtbl.data_bytes(), 32);
+    mvtbl.add_delta(&ap2, 2, delta_add(5, 1000)).unwrap();
+    assert_eq!(mvtbl.data_bytes(), 16);
+
+    mvtbl.delete(&ap1, 1);
+    assert_eq!(mvtbl.data_bytes(), 0);
+
+    // Not accounted by default.
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.write(&ap1, (1, 0), value_for(1, 0)).unwrap();
+    assert_eq!(mvtbl.data_bytes(), 0);
+}
+
+#[test]
+fn spilled_keys_are_reloaded() {
+    use MVDataOutput::*;
+
+    let vd: VersionedData<KeyType<Vec<u8>>, Value> = VersionedData::new().with_spilling(4);
+    let aggregator_ap = KeyType(b"/foo/aggregator".to_vec());
+    let limit = 10000;
+
+    vd.add_delta(&aggregator_ap, 1, delta_add(5, limit));
+    vd.add_delta(&aggregator_ap, 2, delta_add(10, limit));
+    vd.set_aggregator_base_value(&aggregator_ap, 100);
+    vd.mark_estimate(&aggregator_ap, 2);
+
+    // Writing more keys than the threshold spills the least recently written ones.
+    for i in 0..10u32 {
+        vd.write(&KeyType(i.to_be_bytes().to_vec()), (i, 0), value_for(i, 0));
+        assert!(vd.num_keys_in_memory() <= 4);
+    }
+
+    // Spilled keys are transparently reloaded, with their estimates and base values.
+    assert_eq!(
+        vd.fetch_data(&KeyType(0u32.to_be_bytes().to_vec()), 1),
+        Ok(Versioned((0, 0), arc_value_for(0, 0)))
+    );
+    assert_eq!(
+        vd.fetch_data(&aggregator_ap, 3),
+        Err(MVDataError::Dependency(2))
+    );
+    assert_eq!(vd.fetch_data(&aggregator_ap, 2), Ok(Resolved(105)));
+    assert_ok_eq!(
+        vd.fetch_data_lenient(&aggregator_ap, 3).output,
+        Resolved(115)
+    );
+
+    // All keys, in memory or spilled, are materialized (once re-executed).
+    vd.add_delta(&aggregator_ap, 2, delta_add(10, limit));
+    let (outputs, deltas) = vd.into_materialized(10);
+    assert_eq!(outputs.len(), 11);
+    assert!(deltas.is_empty());
+}
+
+#[test]
+fn fetch_data_bounded_retries_dependencies() {
+    let ap = KeyType(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert_err_eq!(
+        mvtbl.fetch_data_bounded(&ap, 5, 3),
+        MVBoundedDataError::Data(MVDataError::NotFound)
+    );
+
+    mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    assert_ok_eq!(
+        mvtbl.fetch_data_bounded(&ap, 5, 0),
+        MVDataOutput::Versioned((1, 0), arc_value_for(1, 0))
+    );
+
+    // The estimate is never cleared, so all retries hit the dependency.
+    mvtbl.mark_estimate(&ap, 1);
+    assert_err_eq!(
+        mvtbl.fetch_data_bounded(&ap, 5, 0),
+        MVBoundedDataError::ExhaustedRetries(1)
+    );
+    assert_err_eq!(
+        mvtbl.fetch_data_bounded(&ap, 5, 10),
+        MVBoundedDataError::ExhaustedRetries(1)
+    );
+
+    // A re-execution clearing the estimate while retrying resolves the read.
+    std::thread::scope(|s| {
+        s.spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+            mvtbl.write(&ap, (1, 1), value_for(1, 1)).unwrap();
+        });
+        assert_ok_eq!(
+            mvtbl.fetch_data_bounded(&ap, 5, usize::MAX),
+            MVDataOutput::Versioned((1, 1), arc_value_for(1, 1))
+        );
+    });
+}
+
+#[test]
+fn merged_entries_are_present() {
+    let data_ap1 = MixedKey::Data(b"/foo/b".to_vec());
+    let data_ap2 = MixedKey::Data(b"/foo/c".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/d".to_vec());
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.write(&data_ap1, (1, 0), value_for(1, 0)).unwrap();
+
+    let stage: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    stage.write(&data_ap2, (2, 0), value_for(2, 0)).unwrap();
+    stage
+        .add_delta(&data_ap2, 3, delta_add(5, u128::MAX))
+        .unwrap();
+    stage.write(&code_ap, (4, 0), value_for(4, 0)).unwrap();
+    stage.mark_estimate(&code_ap, 4);
+    stage.store_executable(
+        &code_ap,
+        ExecutableDescriptor::Storage,
+        ExecutableTestType::default(),
+    );
+
+    mvtbl.merge_from(stage);
+    assert_ok_eq!(
+        mvtbl.fetch_data(&data_ap1, 5),
+        MVDataOutput::Versioned((1, 0), arc_value_for(1, 0))
+    );
+    assert_ok_eq!(
+        mvtbl.fetch_data(&data_ap2, 3),
+        MVDataOutput::Versioned((2, 0), arc_value_for(2, 0))
+    );
+    assert_ok_eq!(
+        mvtbl.fetch_data(&data_ap2, 5),
+        MVDataOutput::Resolved(u128_for(2, 0) + 5)
+    );
+    // Estimates and executables are preserved.
+    assert!(matches!(
+        mvtbl.fetch_code(&code_ap, 5),
+        Err(MVCodeError::Dependency(4))
+    ));
+    assert!(matches!(
+        mvtbl.fetch_code(&code_ap, 4),
+        Ok(MVCodeOutput::Executable((_, ExecutableDescriptor::Storage)))
+    ));
+}
+
+#[test]
+fn typed_writes_skip_module_path() {
+    // The key type always routes to data, so typed writes are the only way to reach code.
+    let ap = KeyType(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+
+    mvtbl
+        .write_typed(&ap, true, (1, 0), value_for(1, 0))
+        .unwrap();
+    assert!(matches!(
+        mvtbl.code.fetch_code(&ap, 2),
+        Ok(MVCodeOutput::Module((_, _)))
+    ));
+    assert_err_eq!(mvtbl.fetch_data(&ap, 2), MVDataError::NotFound);
+
+    mvtbl
+        .write_typed(&ap, false, (1, 0), value_for(1, 0))
+        .unwrap();
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 2),
+        MVDataOutput::Versioned((1, 0), arc_value_for(1, 0))
+    );
+
+    mvtbl.delete_typed(&ap, true, 1);
+    assert!(matches!(
+        mvtbl.code.fetch_code(&ap, 2),
+        Err(MVCodeError::NotFound)
+    ));
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 2),
+        MVDataOutput::Versioned((1, 0), arc_value_for(1, 0))
+    );
+}
+
+#[test]
+fn code_descriptor_is_fetched_without_executable() {
+    let ap = MixedKey::Code(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    assert!(matches!(
+        mvtbl.fetch_code_descriptor(&ap, 5),
+        Err(MVCodeError::NotFound)
+    ));
+
+    mvtbl.store_executable(
+        &ap,
+        ExecutableDescriptor::Storage,
+        ExecutableTestType::default(),
+    );
+    assert!(matches!(
+        mvtbl.fetch_code_descriptor(&ap, 5),
+        Ok(ExecutableDescriptor::Storage)
+    ));
+
+    mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    let hash = match mvtbl.fetch_code(&ap, 5) {
+        Ok(MVCodeOutput::Module((_, hash))) => hash,
+        _ => unreachable!("Module must be read"),
+    };
+    mvtbl.store_executable(
+        &ap,
+        ExecutableDescriptor::Published(hash),
+        ExecutableTestType::default(),
+    );
+    let executable = match mvtbl.fetch_code(&ap, 5) {
+        Ok(MVCodeOutput::Executable((executable, _))) => executable,
+        _ => unreachable!("Executable must be read"),
+    };
+    assert_eq!(Arc::strong_count(&executable), 2);
+
+    assert!(matches!(
+        mvtbl.fetch_code_descriptor(&ap, 5),
+        Ok(ExecutableDescriptor::Published(h)) if h == hash
+    ));
+    // The executable is not cloned.
+    assert_eq!(Arc::strong_count(&executable), 2);
+    // Below the module write, the storage version is read.
+    assert!(matches!(
+        mvtbl.fetch_code_descriptor(&ap, 1),
+        Ok(ExecutableDescriptor::Storage)
+    ));
+
+    mvtbl.mark_estimate(&ap, 1);
+    assert!(matches!(
+        mvtbl.fetch_code_descriptor(&ap, 5),
+        Err(MVCodeError::Dependency(1))
+    ));
+}
+
+#[test]
+fn custom_module_hasher_shares_executables() {
+    use aptos_crypto::HashValue;
+
+    // All test modules have the same length, hence the same custom hash.
+    fn length_hash(bytes: &[u8]) -> HashValue {
+        HashValue::sha3_256_of(&bytes.len().to_be_bytes())
+    }
+
+    let ap = KeyType(b"/foo/c".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> =
+        MVHashMap::new(Some(VersionedCode::with_hasher(length_hash)));
+
+    mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    let hash = match mvtbl.fetch_code(&ap, 2) {
+        Ok(MVCodeOutput::Module((_, hash))) => hash,
+        _ => unreachable!("Module must be read"),
+    };
+    assert_eq!(
+        hash,
+        length_hash(&value_for(1, 0).extract_raw_bytes().unwrap())
+    );
+    mvtbl.store_executable(
+        &ap,
+        ExecutableDescriptor::Published(hash),
+        ExecutableTestType::default(),
+    );
+
+    // A different module with the same custom digest shares the executable's cache slot.
+    mvtbl.write(&ap, (2, 0), value_for(2, 0)).unwrap();
+    assert!(matches!(
+        mvtbl.fetch_code(&ap, 3),
+        Ok(MVCodeOutput::Executable((_, ExecutableDescriptor::Published(h)))) if h == hash
+    ));
+}
+
+#[test]
+fn overlay_shadows_map_value() {
+    use std::collections::HashMap;
+
+    let ap = KeyType(b"/foo/b".to_vec());
+    let other_ap = KeyType(b"/foo/c".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&other_ap, (1, 0), value_for(1, 0)).unwrap();
+
+    // Transaction 3 (incarnation 2) wrote to ap, but not to other_ap.
+    let overlay = HashMap::from([(ap.clone(), (2, arc_value_for(3, 2)))]);
+    assert_ok_eq!(
+        mvtbl.fetch_data_with_overlay(&ap, 3, &overlay),
+        MVDataOutput::Versioned((3, 2), arc_value_for(3, 2))
+    );
+    assert_ok_eq!(
+        mvtbl.fetch_data_with_overlay(&other_ap, 3, &overlay),
+        MVDataOutput::Versioned((1, 0), arc_value_for(1, 0))
+    );
+    // Without the overlay, the map value is read.
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 3),
+        MVDataOutput::Versioned((1, 0), arc_value_for(1, 0))
+    );
+}
+
+#[cfg(feature = "contention-metrics")]
+#[test]
+fn top_contended_keys_are_ranked() {
+    let ap1 = KeyType(b"/foo/b".to_vec());
+    let ap2 = KeyType(b"/foo/c".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+
+    // 5 operations at ap1, 2 at ap2.
+    mvtbl.write(&ap1, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&ap1, (2, 0), value_for(2, 0)).unwrap();
+    for txn_idx in 3..6 {
+        let _ = mvtbl.fetch_data(&ap1, txn_idx);
+    }
+    mvtbl.write(&ap2, (1, 0), value_for(1, 0)).unwrap();
+    let _ = mvtbl.fetch_data(&ap2, 2);
+
+    assert_eq!(mvtbl.top_contended_keys(2), vec![
+        (ap1.clone(), 5),
+        (ap2, 2)
+    ]);
+    assert_eq!(mvtbl.top_contended_keys(1), vec![(ap1, 5)]);
+}
+
+#[test]
+fn cleared_data_is_reusable() {
+    let ap = MixedKey::Data(b"/foo/b".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+    let mut mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> =
+        MVHashMap::new_with_size_accounting(None);
+    mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl.write(&code_ap, (1, 0), value_for(1, 0)).unwrap();
+    let capacity = mvtbl.data.capacity();
+
+    mvtbl.clear_data();
+    assert_err_eq!(mvtbl.fetch_data(&ap, 2), MVDataError::NotFound);
+    assert_eq!(mvtbl.data_bytes(), 0);
+    assert_eq!(mvtbl.data.capacity(), capacity);
+    // The code map is preserved.
+    assert!(matches!(
+        mvtbl.fetch_code(&code_ap, 2),
+        Ok(MVCodeOutput::Module((_, _)))
+    ));
+
+    // The data-structure can be reused for the next block right away.
+    mvtbl.write(&ap, (0, 0), value_for(0, 0)).unwrap();
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 2),
+        MVDataOutput::Versioned((0, 0), arc_value_for(0, 0))
+    );
+    assert_eq!(mvtbl.data_bytes(), 16);
+
+    mvtbl.clear_code();
+    assert!(matches!(
+        mvtbl.fetch_code(&code_ap, 2),
+        Err(MVCodeError::NotFound)
+    ));
+}
+
+#[test]
+fn state_view_reads_match_fetch_data() {
+    use crate::state_view::{MVHashMapStateView, MVStateViewError};
+    use aptos_state_view::TStateView;
+
+    let ap = MixedKey::Data(b"/foo/b".to_vec());
+    let aggregator_ap = MixedKey::Data(b"/foo/c".to_vec());
+    let code_ap = MixedKey::Code(b"/foo/d".to_vec());
+    let mvtbl: MVHashMap<MixedKey, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.write(&ap, (1, 0), value_for(1, 0)).unwrap();
+    mvtbl
+        .add_delta(&aggregator_ap, 1, delta_add(5, 1000))
+        .unwrap();
+    mvtbl.write(&code_ap, (1, 0), value_for(1, 0)).unwrap();
+
+    let view = MVHashMapStateView::new(&mvtbl, 2);
+    let expected = match mvtbl.fetch_data(&ap, 2) {
+        Ok(MVDataOutput::Versioned(_, value)) => value.as_state_value(),
+        output => panic!("Unexpected output {:?}", output),
+    };
+    assert_eq!(view.get_state_value(&ap).unwrap(), expected);
+    assert_eq!(
+        view.get_state_value(&code_ap).unwrap(),
+        value_for(1, 0).as_state_value()
+    );
+    // Writes of higher transactions are not observed.
+    assert_eq!(
+        MVHashMapStateView::new(&mvtbl, 1)
+            .get_state_value(&ap)
+            .unwrap(),
+        None
+    );
+
+    // Deltas without a base value can't be resolved, and estimates block the reader.
+    assert!(view.get_state_value(&aggregator_ap).is_err());
+    mvtbl.mark_estimate(&ap, 1);
+    assert_eq!(
+        view.get_state_value(&ap)
+            .unwrap_err()
+            .downcast::<MVStateViewError>()
+            .unwrap(),
+        MVStateViewError::Dependency(1)
+    );
+}
+
+#[test]
+fn writes_below_commit_watermark_are_rejected() {
+    use crate::types::MVWriteError;
+
+    let ap = KeyType(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.set_commit_watermark(5);
+
+    assert_eq!(
+        mvtbl.write(&ap, (3, 1), value_for(3, 1)),
+        Err(MVWriteError::BelowCommitWatermark(3))
+    );
+    assert_eq!(
+        mvtbl.add_delta(&ap, 5, delta_add(5, 1000)),
+        Err(MVWriteError::BelowCommitWatermark(5))
+    );
+    assert_err_eq!(mvtbl.fetch_data(&ap, 10), MVDataError::NotFound);
+
+    assert_eq!(mvtbl.write(&ap, (6, 0), value_for(6, 0)), Ok(()));
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 10),
+        MVDataOutput::Versioned((6, 0), arc_value_for(6, 0))
+    );
+
+    // The watermark doesn't move backwards.
+    mvtbl.set_commit_watermark(2);
+    assert!(mvtbl.write(&ap, (4, 1), value_for(4, 1)).is_err());
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct SerializableExecutable(u64);
+
+impl Executable for SerializableExecutable {
+    fn size_bytes(&self) -> usize {
+        8
+    }
+}
+
+#[test]
+fn code_cache_round_trip() {
+    use crate::versioned_code::default_module_hash;
+    use aptos_temppath::TempPath;
+    use MVCodeOutput::*;
+
+    let storage_ap = MixedKey::Code(b"/foo/b".to_vec());
+    let published_ap = MixedKey::Code(b"/foo/c".to_vec());
+    let vc: VersionedCode<MixedKey, Value, SerializableExecutable> = VersionedCode::new();
+    vc.store_executable(
+        &storage_ap,
+        ExecutableDescriptor::Storage,
+        SerializableExecutable(7),
+    );
+    vc.write(&published_ap, 3, value_for(3, 0));
+    let hash = default_module_hash(&value_for(3, 0).extract_raw_bytes().unwrap());
+    vc.store_executable(
+        &published_ap,
+        ExecutableDescriptor::Published(hash),
+        SerializableExecutable(8),
+    );
+
+    let path = TempPath::new();
+    vc.save_to(path.path()).unwrap();
+    let loaded: VersionedCode<MixedKey, Value, SerializableExecutable> =
+        VersionedCode::load_from(path.path()).unwrap();
+
+    assert!(matches!(
+        loaded.fetch_code(&storage_ap, 10),
+        Ok(Executable((x, ExecutableDescriptor::Storage))) if *x == SerializableExecutable(7)
+    ));
+    // Executables of modules published in the block are not persisted.
+    assert!(matches!(
+        loaded.fetch_code(&published_ap, 10),
+        Err(MVCodeError::NotFound)
+    ));
+}
+
+#[test]
+fn maps_share_code_cache() {
+    use MVCodeOutput::*;
+
+    let code_ap = MixedKey::Code(b"/foo/c".to_vec());
+    let code_cache = Arc::new(VersionedCode::new());
+    let publisher: MVHashMap<MixedKey, Value, SerializableExecutable> =
+        MVHashMap::new_with_shared_code(code_cache.clone());
+    let reader: MVHashMap<MixedKey, Value, SerializableExecutable> =
+        MVHashMap::new_with_shared_code(code_cache);
+
+    assert!(matches!(
+        reader.fetch_code(&code_ap, 0),
+        Err(MVCodeError::NotFound)
+    ));
+    publisher.store_executable(
+        &code_ap,
+        ExecutableDescriptor::Storage,
+        SerializableExecutable(7),
+    );
+    assert!(matches!(
+        reader.fetch_code(&code_ap, 0),
+        Ok(Executable((x, ExecutableDescriptor::Storage))) if *x == SerializableExecutable(7)
+    ));
+}
+
+#[test]
+fn reads_skip_aborted_entries() {
+    use crate::types::VersionEntry;
+
+    let ap = KeyType(b"/foo/b".to_vec());
+    let mvtbl: MVHashMap<KeyType<Vec<u8>>, Value, ExecutableTestType> = MVHashMap::new(None);
+    mvtbl.write(&ap, (2, 0), value_for(2, 0)).unwrap();
+    mvtbl.write(&ap, (5, 0), value_for(5, 0)).unwrap();
+
+    // Unlike an estimate, an aborted entry resolves to the entry below it.
+    mvtbl.mark_aborted(&ap, 5);
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 10),
+        MVDataOutput::Versioned((2, 0), arc_value_for(2, 0))
+    );
+    assert!(matches!(
+        mvtbl.version_history(&ap)[1],
+        (5, VersionEntry::Aborted)
+    ));
+
+    // Deltas on top of an aborted write are applied to the write below it.
+    mvtbl.add_delta(&ap, 7, delta_add(5, u128::MAX)).unwrap();
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 10),
+        MVDataOutput::Resolved(u128_for(2, 0) + 5)
+    );
+
+    // Re-writing the entry makes it visible again.
+    mvtbl.write(&ap, (5, 1), value_for(5, 1)).unwrap();
+    assert_ok_eq!(
+        mvtbl.fetch_data(&ap, 6),
+        MVDataOutput::Versioned((5, 1), arc_value_for(5, 1))
+    );
+}
@@ -205,7 +205,8 @@ where
         })
         .collect::<Vec<_>>();
     for (key, idx) in versions_to_write {
-        map.write(&KeyType(key.clone()), (idx as TxnIndex, 0), Value(None));
+        map.write(&KeyType(key.clone()), (idx as TxnIndex, 0), Value(None))
+            .unwrap();
         map.mark_estimate(&KeyType(key), idx as TxnIndex);
     }
 
@@ -283,18 +284,20 @@ where
                         }
                     },
                     Operator::Remove => {
-                        map.write(&KeyType(key.clone()), (idx as TxnIndex, 1), Value(None));
+                        map.write(&KeyType(key.clone()), (idx as TxnIndex, 1), Value(None))
+                            .unwrap();
                     },
                     Operator::Insert(v) => {
                         map.write(
                             &KeyType(key.clone()),
                             (idx as TxnIndex, 1),
                             Value(Some(v.clone())),
-                        );
-                    },
-                    Operator::Update(delta) => {
-                        map.add_delta(&KeyType(key.clone()), idx as TxnIndex, *delta)
+                        )
+                        .unwrap();
                     },
+                    Operator::Update(delta) => map
+                        .add_delta(&KeyType(key.clone()), idx as TxnIndex, *delta)
+                        .unwrap(),
                 }
             })
         }
@@ -1,7 +1,8 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::{Flag, MVCodeError, MVCodeOutput, TxnIndex};
+use crate::types::{Flag, MVCodeError, MVCodeOutput, MVSnapshotEntry, TxnIndex, VersionEntry};
+use anyhow::Context;
 use aptos_crypto::hash::{DefaultHasher, HashValue};
 use aptos_types::{
     executable::{Executable, ExecutableDescriptor},
@@ -9,9 +10,11 @@ use aptos_types::{
 };
 use crossbeam::utils::CachePadded;
 use dashmap::DashMap;
+use serde::{de::DeserializeOwned, Serialize};
 use std::{
     collections::{btree_map::BTreeMap, HashMap},
     hash::Hash,
+    path::Path,
     sync::Arc,
 };
 
@@ -41,20 +44,29 @@ struct VersionedValue<V: TransactionWrite, X: Executable> {
     executables: HashMap<HashValue, Arc<X>>,
 }
 
-/// Maps each key (access path) to an internal VersionedValue.
+/// Computes the hash identifying a module (and its executables) from its bytes.
+pub type ModuleHasher = fn(&[u8]) -> HashValue;
+
+/// The default module hash, a cryptographic hash of the module bytes.
+pub fn default_module_hash(bytes: &[u8]) -> HashValue {
+    let mut hasher = DefaultHasher::new(b"Module");
+    hasher.update(bytes);
+    hasher.finish()
+}
+
+/// Maps each key (access path) to an internal VersionedValue. The map is shared by all
+/// handles obtained by shared_handle.
 pub struct VersionedCode<K, V: TransactionWrite, X: Executable> {
-    values: DashMap<K, VersionedValue<V, X>>,
+    values: Arc<DashMap<K, VersionedValue<V, X>>>,
+    // Used to compute the hash of written modules.
+    hasher: ModuleHasher,
 }
 
 impl<V: TransactionWrite> Entry<V> {
-    pub fn new_write_from(module: V) -> Entry<V> {
+    pub fn new_write_from(module: V, hasher: ModuleHasher) -> Entry<V> {
         let hash = module
             .extract_raw_bytes()
-            .map(|bytes| {
-                let mut hasher = DefaultHasher::new(b"Module");
-                hasher.update(&bytes);
-                hasher.finish()
-            })
+            .map(|bytes| hasher(&bytes))
             .expect("Module can't be deleted");
 
         Entry {
@@ -71,6 +83,22 @@ impl<V: TransactionWrite> Entry<V> {
     pub fn mark_estimate(&mut self) {
         self.flag = Flag::Estimate;
     }
+
+    fn to_version_entry(&self) -> VersionEntry<V> {
+        match self.flag() {
+            Flag::Estimate => VersionEntry::Estimate,
+            Flag::Done => VersionEntry::Module(self.module.clone(), self.hash),
+            Flag::Aborted => unreachable!("Modules are never marked as aborted"),
+        }
+    }
+
+    fn to_snapshot_entry(&self) -> MVSnapshotEntry {
+        match self.flag() {
+            Flag::Estimate => MVSnapshotEntry::Estimate,
+            Flag::Done => MVSnapshotEntry::Module(self.hash),
+            Flag::Aborted => unreachable!("Modules are never marked as aborted"),
+        }
+    }
 }
 
 impl<V: TransactionWrite, X: Executable> VersionedValue<V, X> {
@@ -82,6 +110,17 @@ impl<V: TransactionWrite, X: Executable> VersionedValue<V, X> {
         }
     }
 
+    // Same as read, but only returns the hash of the module (without cloning the pointer).
+    fn read_hash(&self, txn_idx: TxnIndex) -> anyhow::Result<HashValue, MVCodeError> {
+        use MVCodeError::*;
+
+        match self.versioned_map.range(0..txn_idx).next_back() {
+            Some((idx, entry)) if entry.flag() == Flag::Estimate => Err(Dependency(*idx)),
+            Some((_, entry)) => Ok(entry.hash),
+            None => Err(NotFound),
+        }
+    }
+
     fn read(&self, txn_idx: TxnIndex) -> anyhow::Result<(Arc<V>, HashValue), MVCodeError> {
         use MVCodeError::*;
 
@@ -106,8 +145,37 @@ impl<V: TransactionWrite, X: Executable> Default for VersionedValue<V, X> {
 
 impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedCode<K, V, X> {
     pub(crate) fn new() -> Self {
+        Self::with_hasher(default_module_hash)
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: Arc::new(DashMap::with_capacity(capacity)),
+            hasher: default_module_hash,
+        }
+    }
+
+    /// Creates a code cache (e.g. to be passed to MVHashMap::new) identifying modules by the
+    /// given hash of their bytes instead of default_module_hash. Modules with the same hash
+    /// at a key are considered identical, i.e. share validation and executables, so the hash
+    /// must be collision-resistant outside of experimentation.
+    pub fn with_hasher(hasher: ModuleHasher) -> Self {
         Self {
-            values: DashMap::new(),
+            values: Arc::new(DashMap::new()),
+            hasher,
+        }
+    }
+
+    // Removes all keys, retaining the allocated capacity. Observed through all shared handles.
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+    }
+
+    // Returns another handle to the same underlying map (not a copy).
+    pub(crate) fn shared_handle(&self) -> Self {
+        Self {
+            values: self.values.clone(),
+            hasher: self.hasher,
         }
     }
 
@@ -121,8 +189,10 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedCode<K,
 
     pub(crate) fn write(&self, key: &K, txn_idx: TxnIndex, data: V) {
         let mut v = self.values.entry(key.clone()).or_default();
-        v.versioned_map
-            .insert(txn_idx, CachePadded::new(Entry::new_write_from(data)));
+        v.versioned_map.insert(
+            txn_idx,
+            CachePadded::new(Entry::new_write_from(data, self.hasher)),
+        );
     }
 
     pub(crate) fn store_executable(
@@ -169,6 +239,136 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedCode<K,
         }
     }
 
+    // Same as fetch_code, but always returns the module published in the block (never the
+    // executable), and NotFound if no module was published below txn_idx.
+    pub(crate) fn fetch_module(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> anyhow::Result<Arc<V>, MVCodeError> {
+        match self.values.get(key) {
+            Some(v) => v.read(txn_idx).map(|(module, _)| module),
+            None => Err(MVCodeError::NotFound),
+        }
+    }
+
+    pub(crate) fn fetch_code_descriptor(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+    ) -> anyhow::Result<ExecutableDescriptor, MVCodeError> {
+        use MVCodeError::*;
+
+        match self.values.get(key) {
+            Some(v) => match v.read_hash(txn_idx) {
+                Ok(hash) => Ok(ExecutableDescriptor::Published(hash)),
+                Err(NotFound) => v
+                    .base_executable
+                    .as_ref()
+                    .map(|_| ExecutableDescriptor::Storage)
+                    .ok_or(NotFound),
+                Err(Dependency(idx)) => Err(Dependency(idx)),
+            },
+            None => Err(NotFound),
+        }
+    }
+
+    pub(crate) fn version_history(&self, key: &K) -> Vec<(TxnIndex, VersionEntry<V>)> {
+        // Holding the DashMap reference guarantees a consistent snapshot of the entries.
+        self.values
+            .get(key)
+            .map(|v| {
+                v.versioned_map
+                    .iter()
+                    .map(|(idx, entry)| (*idx, entry.to_version_entry()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn conflicting_writers(&self, key: &K) -> Vec<TxnIndex> {
+        self.values
+            .get(key)
+            .map(|v| v.versioned_map.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn latest_write(&self, key: &K) -> Option<(TxnIndex, Arc<V>)> {
+        self.values.get(key).and_then(|v| {
+            v.versioned_map
+                .iter()
+                .rev()
+                .find(|(_, entry)| entry.flag() == Flag::Done)
+                .map(|(idx, entry)| (*idx, entry.module.clone()))
+        })
+    }
+
+    pub(crate) fn version_depths(&self) -> Vec<usize> {
+        self.values.iter().map(|v| v.versioned_map.len()).collect()
+    }
+
+    pub(crate) fn num_estimates(&self) -> usize {
+        self.values
+            .iter()
+            .map(|v| {
+                v.versioned_map
+                    .values()
+                    .filter(|entry| entry.flag() == Flag::Estimate)
+                    .count()
+            })
+            .sum()
+    }
+
+    pub(crate) fn snapshot(&self) -> HashMap<K, BTreeMap<TxnIndex, MVSnapshotEntry>> {
+        self.values
+            .iter()
+            .filter(|v| !v.versioned_map.is_empty())
+            .map(|v| {
+                let entries = v
+                    .versioned_map
+                    .iter()
+                    .map(|(idx, entry)| (*idx, entry.to_snapshot_entry()))
+                    .collect();
+                (v.key().clone(), entries)
+            })
+            .collect()
+    }
+
+    // Adds all entries and executables of 'other' into this data-structure, preserving their
+    // transaction indices and estimate flags (modules and executables are shared, not
+    // copied). Panics if both record an entry at the same (key, txn_idx). Nothing is done if
+    // 'other' is a handle to the same map. Module hashes are kept as computed by 'other', so
+    // both should use the same hasher.
+    pub(crate) fn merge_from(&self, other: VersionedCode<K, V, X>) {
+        if Arc::ptr_eq(&self.values, &other.values) {
+            return;
+        }
+
+        for other_v in other.values.iter() {
+            let mut v = self.values.entry(other_v.key().clone()).or_default();
+            for (txn_idx, entry) in other_v.versioned_map.iter() {
+                let entry = Entry {
+                    flag: entry.flag,
+                    module: entry.module.clone(),
+                    hash: entry.hash,
+                };
+                assert!(
+                    v.versioned_map
+                        .insert(*txn_idx, CachePadded::new(entry))
+                        .is_none(),
+                    "Conflicting module entries at txn_idx = {}",
+                    txn_idx
+                );
+            }
+            if let Some(x) = &other_v.base_executable {
+                v.base_executable.get_or_insert_with(|| x.clone());
+            }
+            for (hash, x) in other_v.executables.iter() {
+                v.executables.entry(*hash).or_insert_with(|| x.clone());
+            }
+        }
+    }
+
     pub(crate) fn delete(&self, key: &K, txn_idx: TxnIndex) {
         // TODO: investigate logical deletion.
         let mut v = self.values.get_mut(key).expect("Path must exist");
@@ -179,6 +379,49 @@ impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> VersionedCode<K,
     }
 }
 
+impl<K, V, X> VersionedCode<K, V, X>
+where
+    K: Hash + Clone + Eq + Serialize + DeserializeOwned,
+    V: TransactionWrite,
+    X: Executable + Serialize + DeserializeOwned,
+{
+    /// Writes the storage-version executables of the code cache to the file at 'path', so
+    /// that a restarted process can warm its cache with load_from instead of recompiling.
+    /// Modules and executables of code published during a block are block-local, hence
+    /// skipped. Requires the executables (and keys) to be serializable.
+    pub fn save_to(&self, path: &Path) -> anyhow::Result<()> {
+        let executables: Vec<(K, Arc<X>)> = self
+            .values
+            .iter()
+            .filter_map(|entry| {
+                entry
+                    .value()
+                    .base_executable
+                    .as_ref()
+                    .map(|x| (entry.key().clone(), x.clone()))
+            })
+            .collect();
+        let bytes = bcs::to_bytes(&executables)?;
+        std::fs::write(path, bytes)
+            .with_context(|| format!("Failed to write code cache to {:?}", path))
+    }
+
+    /// Creates a code cache with the storage-version executables written by save_to to the
+    /// file at 'path'. Modules are identified by default_module_hash.
+    pub fn load_from(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read code cache from {:?}", path))?;
+        let executables: Vec<(K, X)> = bcs::from_bytes(&bytes)
+            .with_context(|| format!("Failed to deserialize code cache from {:?}", path))?;
+
+        let code = Self::new();
+        for (key, executable) in executables {
+            code.store_executable(&key, ExecutableDescriptor::Storage, executable);
+        }
+        Ok(code)
+    }
+}
+
 impl<K: Hash + Clone + Eq, V: TransactionWrite, X: Executable> Default for VersionedCode<K, V, X> {
     fn default() -> Self {
         VersionedCode::new()
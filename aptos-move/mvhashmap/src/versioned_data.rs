@@ -1,13 +1,34 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::{Flag, Incarnation, MVDataError, MVDataOutput, TxnIndex, Version};
+use crate::types::{
+    Flag, Incarnation, MVDataError, MVDataOutput, MVLenientDataOutput, MVSnapshotEntry, TxnIndex,
+    Version, VersionEntry,
+};
 use anyhow::Result;
 use aptos_aggregator::{delta_change_set::DeltaOp, transaction::AggregatorValue};
+use aptos_infallible::Mutex;
+use aptos_temppath::TempPath;
 use aptos_types::write_set::TransactionWrite;
 use crossbeam::utils::CachePadded;
-use dashmap::DashMap;
-use std::{collections::btree_map::BTreeMap, fmt::Debug, hash::Hash, sync::Arc};
+use dashmap::{
+    mapref::{entry::Entry as DashMapEntry, one::RefMut},
+    DashMap,
+};
+use either::Either;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{btree_map::BTreeMap, HashMap},
+    fmt::Debug,
+    fs::File,
+    hash::Hash,
+    io::{Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+};
 
 /// Every entry in shared multi-version data-structure has an "estimate" flag
 /// and some content.
@@ -17,6 +38,10 @@ struct Entry<V> {
 
     /// Used to mark the entry as a "write estimate".
     flag: Flag,
+
+    /// Size of the written value in bytes, computed once when the value is written if
+    /// value sizes are accounted (0 otherwise, and for deltas).
+    size: usize,
 }
 
 /// Represents the content of a single entry in multi-version data-structure.
@@ -42,18 +67,89 @@ struct VersionedValue<V> {
 
     // An aggregator value from storage can be here to avoid redundant storage calls.
     aggregator_base_value: Option<u128>,
+
+    // Logical time of the last write or delta at the key, only maintained when spilling is
+    // enabled, to find the least recently written keys.
+    last_write: u64,
 }
 
 /// Maps each key (access path) to an internal VersionedValue.
 pub struct VersionedData<K, V> {
     values: DashMap<K, VersionedValue<V>>,
+    spilling: Option<Spilling<K, V>>,
+    // If set, used to skip writes identical to the write already recorded by the same
+    // transaction (see with_write_dedup).
+    write_eq: Option<fn(&V, &V) -> bool>,
+    // If set, the total size in bytes of the values of all recorded writes (see
+    // with_size_accounting).
+    data_bytes: Option<AtomicUsize>,
+}
+
+/// Bounded-memory mode of VersionedData. Once the number of keys in memory exceeds the
+/// threshold, the version chains of the least recently written keys are moved out of
+/// memory, and are transparently reloaded when the key is accessed again.
+///
+/// Accesses to spilled keys are much slower (file I/O), and in this mode all accesses take
+/// the exclusive lock of the key's shard (so that a spilled key can be reloaded), hence it
+/// is only meant for blocks whose state would not fit in memory otherwise.
+struct Spilling<K, V> {
+    max_keys_in_memory: usize,
+    store: Box<dyn SpillStore<K, V>>,
+    write_clock: AtomicU64,
+}
+
+/// Stores the version chains of spilled keys. Type-erased so that VersionedData only
+/// requires the values to be serializable when spilling is enabled.
+trait SpillStore<K, V>: Send + Sync {
+    fn spill(&self, key: &K, value: &VersionedValue<V>);
+
+    fn reload(&self, key: &K) -> Option<VersionedValue<V>>;
+
+    fn reload_all(&self) -> Vec<(K, VersionedValue<V>)>;
+
+    // Returns the number of entries in the version chain of every spilled key.
+    fn spilled_depths(&self) -> Vec<usize>;
+
+    // Returns the total number of entries marked as estimates in spilled version chains.
+    fn spilled_estimates(&self) -> usize;
+
+    fn spilled_keys(&self) -> Vec<K>;
+
+    // Discards all spilled version chains.
+    fn clear(&self);
+}
+
+/// Serialized form of a VersionedValue: 'W' is &V when spilling, and V when reloading.
+#[derive(Serialize, Deserialize)]
+struct SpilledValue<W> {
+    // The index, flag, size and contents of each entry.
+    entries: Vec<(TxnIndex, Flag, usize, SpilledCell<W>)>,
+    aggregator_base_value: Option<u128>,
+    last_write: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SpilledCell<W> {
+    Write(Incarnation, W),
+    Delta(DeltaOp, Option<u128>),
+}
+
+/// Appends spilled version chains to a temporary file, which is removed on drop. The space
+/// of reloaded chains is not reclaimed, as the data-structure only lives for a block.
+struct FileSpillStore<K, V> {
+    // The file and the offset, length, depth and number of estimates of every spilled key's
+    // version chain in it.
+    inner: Mutex<(File, HashMap<K, (u64, usize, usize, usize)>)>,
+    _path: TempPath,
+    phantom: PhantomData<fn() -> V>,
 }
 
 impl<V> Entry<V> {
-    fn new_write_from(incarnation: Incarnation, data: V) -> Entry<V> {
+    fn new_write_from(incarnation: Incarnation, data: V, size: usize) -> Entry<V> {
         Entry {
             cell: EntryCell::Write(incarnation, Arc::new(data)),
             flag: Flag::Done,
+            size,
         }
     }
 
@@ -61,6 +157,7 @@ impl<V> Entry<V> {
         Entry {
             cell: EntryCell::Delta(data, None),
             flag: Flag::Done,
+            size: 0,
         }
     }
 
@@ -72,6 +169,39 @@ impl<V> Entry<V> {
         self.flag = Flag::Estimate;
     }
 
+    fn mark_aborted(&mut self) {
+        self.flag = Flag::Aborted;
+    }
+
+    fn to_version_entry(&self) -> VersionEntry<V> {
+        match self.flag() {
+            Flag::Estimate => return VersionEntry::Estimate,
+            Flag::Aborted => return VersionEntry::Aborted,
+            Flag::Done => {},
+        }
+
+        match &self.cell {
+            EntryCell::Write(incarnation, data) => VersionEntry::Write(*incarnation, data.clone()),
+            EntryCell::Delta(delta, maybe_shortcut) => VersionEntry::Delta(*delta, *maybe_shortcut),
+        }
+    }
+
+    fn to_snapshot_entry(&self) -> MVSnapshotEntry
+    where
+        V: TransactionWrite,
+    {
+        match self.flag() {
+            Flag::Estimate => return MVSnapshotEntry::Estimate,
+            Flag::Aborted => return MVSnapshotEntry::Aborted,
+            Flag::Done => {},
+        }
+
+        match &self.cell {
+            EntryCell::Write(_, data) => MVSnapshotEntry::Write(data.extract_raw_bytes()),
+            EntryCell::Delta(delta, _) => MVSnapshotEntry::Delta(*delta),
+        }
+    }
+
     // The entry must be a delta, will record the provided value as a base value
     // shortcut (the value in storage before block execution). If a value was already
     // recorded, the new value is asserted for equality.
@@ -95,10 +225,33 @@ impl<V: TransactionWrite> VersionedValue<V> {
         Self {
             versioned_map: BTreeMap::new(),
             aggregator_base_value: None,
+            last_write: 0,
         }
     }
 
     fn read(&self, txn_idx: TxnIndex) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
+        self.read_impl(txn_idx, None)
+    }
+
+    // Same as read, but entries marked as estimates are read through (as if they were
+    // not marked) instead of returning a dependency error. Returns whether an estimate
+    // was encountered along the way. Meant for inspection only, not for execution.
+    fn read_lenient(&self, txn_idx: TxnIndex) -> MVLenientDataOutput<V> {
+        let mut encountered_estimate = false;
+        let output = self.read_impl(txn_idx, Some(&mut encountered_estimate));
+        MVLenientDataOutput {
+            output,
+            encountered_estimate,
+        }
+    }
+
+    // If 'encountered_estimate' is provided, estimates are recorded there and the read
+    // continues with the contents of the entry, otherwise the read returns a dependency.
+    fn read_impl(
+        &self,
+        txn_idx: TxnIndex,
+        mut encountered_estimate: Option<&mut bool>,
+    ) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
         use MVDataError::*;
         use MVDataOutput::*;
 
@@ -109,9 +262,16 @@ impl<V: TransactionWrite> VersionedValue<V> {
         // During traversal, all aggregator deltas have to be accumulated together.
         let mut accumulator: Option<Result<DeltaOp, ()>> = None;
         while let Some((idx, entry)) = iter.next_back() {
+            if entry.flag() == Flag::Aborted {
+                // The writes of aborted transactions are absent, read the entry below.
+                continue;
+            }
             if entry.flag() == Flag::Estimate {
-                // Found a dependency.
-                return Err(Dependency(*idx));
+                match encountered_estimate.as_deref_mut() {
+                    Some(encountered_estimate) => *encountered_estimate = true,
+                    // Found a dependency.
+                    None => return Err(Dependency(*idx)),
+                }
             }
 
             match (&entry.cell, accumulator.as_mut()) {
@@ -197,47 +357,331 @@ impl<V: TransactionWrite> VersionedValue<V> {
     }
 }
 
+impl<V> VersionedValue<V> {
+    fn num_estimates(&self) -> usize {
+        self.versioned_map
+            .values()
+            .filter(|entry| entry.flag() == Flag::Estimate)
+            .count()
+    }
+}
+
 impl<V: TransactionWrite> Default for VersionedValue<V> {
     fn default() -> Self {
         VersionedValue::new()
     }
 }
 
+impl<V: Serialize> VersionedValue<V> {
+    fn to_bytes(&self) -> Vec<u8> {
+        let spilled = SpilledValue {
+            entries: self
+                .versioned_map
+                .iter()
+                .map(|(idx, entry)| {
+                    let cell = match &entry.cell {
+                        EntryCell::Write(incarnation, data) => {
+                            SpilledCell::Write(*incarnation, data.as_ref())
+                        },
+                        EntryCell::Delta(delta, maybe_shortcut) => {
+                            SpilledCell::Delta(*delta, *maybe_shortcut)
+                        },
+                    };
+                    (*idx, entry.flag(), entry.size, cell)
+                })
+                .collect(),
+            aggregator_base_value: self.aggregator_base_value,
+            last_write: self.last_write,
+        };
+        bcs::to_bytes(&spilled).expect("Spilled value must serialize")
+    }
+}
+
+impl<V: DeserializeOwned> VersionedValue<V> {
+    fn from_bytes(bytes: &[u8]) -> Self {
+        let spilled: SpilledValue<V> =
+            bcs::from_bytes(bytes).expect("Spilled value must deserialize");
+        Self {
+            versioned_map: spilled
+                .entries
+                .into_iter()
+                .map(|(idx, flag, size, cell)| {
+                    let cell = match cell {
+                        SpilledCell::Write(incarnation, data) => {
+                            EntryCell::Write(incarnation, Arc::new(data))
+                        },
+                        SpilledCell::Delta(delta, maybe_shortcut) => {
+                            EntryCell::Delta(delta, maybe_shortcut)
+                        },
+                    };
+                    (idx, CachePadded::new(Entry { cell, flag, size }))
+                })
+                .collect(),
+            aggregator_base_value: spilled.aggregator_base_value,
+            last_write: spilled.last_write,
+        }
+    }
+}
+
+impl<K, V> FileSpillStore<K, V> {
+    fn new() -> Self {
+        let path = TempPath::new();
+        let file = File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path.path())
+            .expect("Must be able to create the spill file");
+        Self {
+            inner: Mutex::new((file, HashMap::new())),
+            _path: path,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, V> SpillStore<K, V> for FileSpillStore<K, V>
+where
+    K: Hash + Clone + Eq + Send,
+    V: TransactionWrite + Serialize + DeserializeOwned,
+{
+    fn spill(&self, key: &K, value: &VersionedValue<V>) {
+        let bytes = value.to_bytes();
+        let mut inner = self.inner.lock();
+        let (file, offsets) = &mut *inner;
+        let offset = file
+            .seek(SeekFrom::End(0))
+            .expect("Must be able to seek the spill file");
+        file.write_all(&bytes)
+            .expect("Must be able to write the spill file");
+        offsets.insert(
+            key.clone(),
+            (
+                offset,
+                bytes.len(),
+                value.versioned_map.len(),
+                value.num_estimates(),
+            ),
+        );
+    }
+
+    fn reload(&self, key: &K) -> Option<VersionedValue<V>> {
+        let mut inner = self.inner.lock();
+        let (file, offsets) = &mut *inner;
+        let (offset, len, _, _) = offsets.remove(key)?;
+        let mut bytes = vec![0; len];
+        file.seek(SeekFrom::Start(offset))
+            .and_then(|_| file.read_exact(&mut bytes))
+            .expect("Must be able to read the spill file");
+        Some(VersionedValue::from_bytes(&bytes))
+    }
+
+    fn reload_all(&self) -> Vec<(K, VersionedValue<V>)> {
+        let keys: Vec<K> = self.inner.lock().1.keys().cloned().collect();
+        keys.into_iter()
+            .filter_map(|key| self.reload(&key).map(|value| (key, value)))
+            .collect()
+    }
+
+    fn spilled_depths(&self) -> Vec<usize> {
+        self.inner
+            .lock()
+            .1
+            .values()
+            .map(|(_, _, depth, _)| *depth)
+            .collect()
+    }
+
+    fn spilled_estimates(&self) -> usize {
+        self.inner
+            .lock()
+            .1
+            .values()
+            .map(|(_, _, _, num_estimates)| *num_estimates)
+            .sum()
+    }
+
+    fn spilled_keys(&self) -> Vec<K> {
+        self.inner.lock().1.keys().cloned().collect()
+    }
+
+    fn clear(&self) {
+        let mut inner = self.inner.lock();
+        let (file, offsets) = &mut *inner;
+        file.set_len(0)
+            .expect("Must be able to truncate the spill file");
+        offsets.clear();
+    }
+}
+
 impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
     pub(crate) fn new() -> Self {
         Self {
             values: DashMap::new(),
+            spilling: None,
+            write_eq: None,
+            data_bytes: None,
+        }
+    }
+
+    pub(crate) fn with_capacity(capacity: usize) -> Self {
+        Self {
+            values: DashMap::with_capacity(capacity),
+            spilling: None,
+            write_eq: None,
+            data_bytes: None,
+        }
+    }
+
+    /// Keeps track of the total size in bytes of the values of all recorded writes (the
+    /// size of each value is computed once, when it is written). Like the other with_*
+    /// modes, which can be combined, it must be set before anything is recorded.
+    pub(crate) fn with_size_accounting(mut self) -> Self {
+        self.data_bytes = Some(AtomicUsize::new(0));
+        self
+    }
+
+    // Returns the value at the key, reloading it into memory if it was spilled.
+    fn get_mut(&self, key: &K) -> Option<RefMut<'_, K, VersionedValue<V>>> {
+        match &self.spilling {
+            None => self.values.get_mut(key),
+            Some(spilling) => match self.values.entry(key.clone()) {
+                DashMapEntry::Occupied(entry) => Some(entry.into_ref()),
+                DashMapEntry::Vacant(entry) => spilling.store.reload(key).map(|v| entry.insert(v)),
+            },
+        }
+    }
+
+    // Same as get_mut, but creates the value if the key doesn't exist, and records the write
+    // time when spilling is enabled. Must be followed by maybe_spill, once the value is dropped.
+    fn get_mut_for_write(&self, key: &K) -> RefMut<'_, K, VersionedValue<V>> {
+        let entry = self.values.entry(key.clone());
+        match &self.spilling {
+            None => entry.or_default(),
+            Some(spilling) => {
+                let mut v = entry.or_insert_with(|| spilling.store.reload(key).unwrap_or_default());
+                v.last_write = spilling.write_clock.fetch_add(1, Ordering::Relaxed);
+                v
+            },
+        }
+    }
+
+    // Applies 'f' to the value at the key, if the key exists (possibly spilled).
+    fn read_value<R>(&self, key: &K, f: impl FnOnce(&VersionedValue<V>) -> R) -> Option<R> {
+        match &self.spilling {
+            None => self.values.get(key).map(|v| f(v.value())),
+            Some(_) => self.get_mut(key).map(|v| f(v.value())),
+        }
+    }
+
+    // If spilling is enabled and the number of keys in memory exceeds the threshold, spills
+    // the least recently written keys, down to half of the threshold (so that the cost of
+    // finding them is amortized over many writes).
+    fn maybe_spill(&self) {
+        let spilling = match &self.spilling {
+            Some(spilling) if self.values.len() > spilling.max_keys_in_memory => spilling,
+            _ => return,
+        };
+
+        let mut by_last_write: Vec<(u64, K)> = self
+            .values
+            .iter()
+            .map(|v| (v.last_write, v.key().clone()))
+            .collect();
+        by_last_write.sort_unstable_by_key(|(last_write, _)| *last_write);
+        let num_to_spill = by_last_write
+            .len()
+            .saturating_sub(spilling.max_keys_in_memory / 2);
+
+        for (_, key) in by_last_write.into_iter().take(num_to_spill) {
+            // The value is spilled while holding the lock of the key's shard, so concurrent
+            // accesses either see it in memory, or reload it from the store.
+            if let DashMapEntry::Occupied(entry) = self.values.entry(key) {
+                spilling.store.spill(entry.key(), entry.get());
+                entry.remove();
+            }
+        }
+    }
+
+    // Updates the total size of the values, if accounted, when an entry of 'added' bytes
+    // replaces an entry of 'removed' bytes (0 if there is none). Must be called while
+    // holding the lock of the key, so that an entry is never removed before it is added.
+    fn account_bytes(&self, added: usize, removed: usize) {
+        if let Some(data_bytes) = &self.data_bytes {
+            data_bytes.fetch_add(added, Ordering::Relaxed);
+            data_bytes.fetch_sub(removed, Ordering::Relaxed);
+        }
+    }
+
+    pub(crate) fn data_bytes(&self) -> usize {
+        self.data_bytes
+            .as_ref()
+            .map_or(0, |data_bytes| data_bytes.load(Ordering::Relaxed))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn num_keys_in_memory(&self) -> usize {
+        self.values.len()
+    }
+
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.values.capacity()
+    }
+
+    /// Removes all keys (including spilled ones), retaining the allocated capacity.
+    pub(crate) fn clear(&mut self) {
+        self.values.clear();
+        if let Some(spilling) = &self.spilling {
+            spilling.store.clear();
+        }
+        if let Some(data_bytes) = &self.data_bytes {
+            data_bytes.store(0, Ordering::Relaxed);
         }
     }
 
     pub(crate) fn set_aggregator_base_value(&self, key: &K, value: u128) {
-        let mut v = self.values.get_mut(key).expect("Path must exist");
+        let mut v = self.get_mut(key).expect("Path must exist");
 
         // Record base value. If a value was added by another thread, assert they're equal.
         assert_eq!(*v.aggregator_base_value.get_or_insert(value), value);
     }
 
     pub(crate) fn add_delta(&self, key: &K, txn_idx: TxnIndex, delta: DeltaOp) {
-        let mut v = self.values.entry(key.clone()).or_default();
-        v.versioned_map
+        let mut v = self.get_mut_for_write(key);
+        let prev_entry = v
+            .versioned_map
             .insert(txn_idx, CachePadded::new(Entry::new_delta_from(delta)));
+        self.account_bytes(0, prev_entry.map_or(0, |entry| entry.size));
+        drop(v);
+
+        self.maybe_spill();
     }
 
     pub(crate) fn mark_estimate(&self, key: &K, txn_idx: TxnIndex) {
-        let mut v = self.values.get_mut(key).expect("Path must exist");
+        let mut v = self.get_mut(key).expect("Path must exist");
         v.versioned_map
             .get_mut(&txn_idx)
             .expect("Entry by the txn must exist to mark estimate")
             .mark_estimate();
     }
 
+    pub(crate) fn mark_aborted(&self, key: &K, txn_idx: TxnIndex) {
+        let mut v = self.get_mut(key).expect("Path must exist");
+        v.versioned_map
+            .get_mut(&txn_idx)
+            .expect("Entry by the txn must exist to mark aborted")
+            .mark_aborted();
+    }
+
     pub(crate) fn delete(&self, key: &K, txn_idx: TxnIndex) {
         // TODO: investigate logical deletion.
-        let mut v = self.values.get_mut(key).expect("Path must exist");
-        assert!(
-            v.versioned_map.remove(&txn_idx).is_some(),
-            "Entry must exist to be deleted"
-        );
+        let mut v = self.get_mut(key).expect("Path must exist");
+        let removed_entry = v
+            .versioned_map
+            .remove(&txn_idx)
+            .expect("Entry must exist to be deleted");
+        self.account_bytes(0, removed_entry.size);
     }
 
     pub(crate) fn fetch_data(
@@ -245,20 +689,148 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
         key: &K,
         txn_idx: TxnIndex,
     ) -> anyhow::Result<MVDataOutput<V>, MVDataError> {
-        self.values
-            .get(key)
-            .map(|v| v.read(txn_idx))
+        self.read_value(key, |v| v.read(txn_idx))
             .unwrap_or(Err(MVDataError::NotFound))
     }
 
+    pub(crate) fn with_data<R>(
+        &self,
+        key: &K,
+        txn_idx: TxnIndex,
+        f: impl FnOnce(&V) -> R,
+    ) -> anyhow::Result<Either<R, u128>, MVDataError> {
+        self.read_value(key, |v| {
+            if let Some((_, entry)) = v.versioned_map.range(0..txn_idx).next_back() {
+                if let (EntryCell::Write(_, data), Flag::Done) = (&entry.cell, entry.flag()) {
+                    // Common case, the latest entry is a write: apply f in place.
+                    return Ok(Either::Left(f(data)));
+                }
+            }
+            v.read(txn_idx).map(|output| match output {
+                MVDataOutput::Versioned(_, data) => Either::Left(f(&data)),
+                MVDataOutput::Resolved(value) => Either::Right(value),
+            })
+        })
+        .unwrap_or(Err(MVDataError::NotFound))
+    }
+
+    pub(crate) fn fetch_data_lenient(&self, key: &K, txn_idx: TxnIndex) -> MVLenientDataOutput<V> {
+        self.read_value(key, |v| v.read_lenient(txn_idx))
+            .unwrap_or(MVLenientDataOutput {
+                output: Err(MVDataError::NotFound),
+                encountered_estimate: false,
+            })
+    }
+
+    pub(crate) fn version_history(&self, key: &K) -> Vec<(TxnIndex, VersionEntry<V>)> {
+        // Holding the DashMap reference guarantees a consistent snapshot of the entries.
+        self.read_value(key, |v| {
+            v.versioned_map
+                .iter()
+                .map(|(idx, entry)| (*idx, entry.to_version_entry()))
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    pub(crate) fn conflicting_writers(&self, key: &K) -> Vec<TxnIndex> {
+        self.read_value(key, |v| {
+            v.versioned_map
+                .iter()
+                .filter(|(_, entry)| matches!(entry.cell, EntryCell::Write(_, _)))
+                .map(|(idx, _)| *idx)
+                .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    pub(crate) fn latest_write(&self, key: &K) -> Option<(TxnIndex, Arc<V>)> {
+        self.read_value(key, |v| {
+            v.versioned_map.iter().rev().find_map(|(idx, entry)| {
+                match (&entry.cell, entry.flag()) {
+                    (EntryCell::Write(_, data), Flag::Done) => Some((*idx, data.clone())),
+                    _ => None,
+                }
+            })
+        })
+        .flatten()
+    }
+
+    // Returns the number of entries in the version chain of every key, without reloading
+    // spilled keys.
+    pub(crate) fn version_depths(&self) -> Vec<usize> {
+        let mut depths: Vec<usize> = self.values.iter().map(|v| v.versioned_map.len()).collect();
+        if let Some(spilling) = &self.spilling {
+            depths.extend(spilling.store.spilled_depths());
+        }
+        depths
+    }
+
+    // Returns the number of entries marked as estimates across all keys, without reloading
+    // spilled keys.
+    pub(crate) fn num_estimates(&self) -> usize {
+        let in_memory: usize = self.values.iter().map(|v| v.num_estimates()).sum();
+        in_memory
+            + self
+                .spilling
+                .as_ref()
+                .map_or(0, |spilling| spilling.store.spilled_estimates())
+    }
+
+    // Returns the snapshot entries of every key with a non-empty version chain. Spilled keys
+    // are reloaded into memory.
+    pub(crate) fn snapshot(&self) -> HashMap<K, BTreeMap<TxnIndex, MVSnapshotEntry>> {
+        // Keys are collected first, as reloading a spilled key must not happen while
+        // iterating over the map.
+        let mut keys: Vec<K> = self.values.iter().map(|v| v.key().clone()).collect();
+        if let Some(spilling) = &self.spilling {
+            keys.extend(spilling.store.spilled_keys());
+        }
+
+        keys.into_iter()
+            .filter_map(|key| {
+                let entries: BTreeMap<TxnIndex, MVSnapshotEntry> = self
+                    .read_value(&key, |v| {
+                        v.versioned_map
+                            .iter()
+                            .map(|(idx, entry)| (*idx, entry.to_snapshot_entry()))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (!entries.is_empty()).then_some((key, entries))
+            })
+            .collect()
+    }
+
     pub(crate) fn write(&self, key: &K, version: Version, data: V) {
         let (txn_idx, incarnation) = version;
-
-        let mut v = self.values.entry(key.clone()).or_default();
+        // Computed before taking the lock, as extracting the bytes may copy the value.
+        let size = match self.data_bytes {
+            Some(_) => data.extract_raw_bytes().map_or(0, |bytes| bytes.len()),
+            None => 0,
+        };
+
+        let mut v = self.get_mut_for_write(key);
+        if let Some(write_eq) = self.write_eq {
+            if let Some(entry) = v.versioned_map.get_mut(&txn_idx) {
+                if let EntryCell::Write(prev_incarnation, prev_data) = &entry.cell {
+                    if *prev_incarnation < incarnation && write_eq(prev_data, &data) {
+                        // Keep the entry (and its incarnation) so that reads of the previous
+                        // incarnation remain valid, but the write is no longer an estimate.
+                        entry.flag = Flag::Done;
+                        drop(v);
+                        self.maybe_spill();
+                        return;
+                    }
+                }
+            }
+        }
         let prev_entry = v.versioned_map.insert(
             txn_idx,
-            CachePadded::new(Entry::new_write_from(incarnation, data)),
+            CachePadded::new(Entry::new_write_from(incarnation, data, size)),
         );
+        self.account_bytes(size, prev_entry.as_ref().map_or(0, |entry| entry.size));
+        drop(v);
 
         // Assert that the previous entry for txn_idx, if present, had lower incarnation.
         assert!(prev_entry.map_or(true, |entry| -> bool {
@@ -268,6 +840,48 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
                 true
             }
         }));
+
+        self.maybe_spill();
+    }
+
+    // Moves all entries of 'other' into this data-structure, preserving their transaction
+    // indices and estimate flags. Panics if both record an entry at the same (key, txn_idx),
+    // or different aggregator base values at the same key.
+    pub(crate) fn merge_from(&self, other: VersionedData<K, V>) {
+        let spilled_values = other
+            .spilling
+            .map(|spilling| spilling.store.reload_all())
+            .unwrap_or_default();
+        for (key, other_v) in other.values.into_iter().chain(spilled_values) {
+            let mut v = self.get_mut_for_write(&key);
+            if let Some(value) = other_v.aggregator_base_value {
+                assert_eq!(
+                    *v.aggregator_base_value.get_or_insert(value),
+                    value,
+                    "Conflicting aggregator base values at key = {:?}",
+                    key
+                );
+            }
+            for (txn_idx, mut entry) in other_v.versioned_map {
+                // Sizes are recomputed, as the other data-structure may not account them.
+                entry.size = match (&self.data_bytes, &entry.cell) {
+                    (Some(_), EntryCell::Write(_, data)) => {
+                        data.extract_raw_bytes().map_or(0, |bytes| bytes.len())
+                    },
+                    _ => 0,
+                };
+                self.account_bytes(entry.size, 0);
+                assert!(
+                    v.versioned_map.insert(txn_idx, entry).is_none(),
+                    "Conflicting entries at key = {:?}, txn_idx = {}",
+                    key,
+                    txn_idx
+                );
+            }
+            drop(v);
+
+            self.maybe_spill();
+        }
     }
 
     // When a transaction is committed, this method can be called for its delta outputs to add
@@ -277,7 +891,7 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
     // transaction has indeed produced a delta recorded at the given key.
     // If the result is None, it means the base value hadn't been set.
     pub(crate) fn materialize_delta(&self, key: &K, txn_idx: TxnIndex) -> Result<u128, DeltaOp> {
-        let mut v = self.values.get_mut(key).expect("Path must exist");
+        let mut v = self.get_mut(key).expect("Path must exist");
 
         // +1 makes sure we include the delta from txn_idx.
         match v.read(txn_idx + 1) {
@@ -293,4 +907,71 @@ impl<K: Hash + Clone + Debug + Eq, V: TransactionWrite> VersionedData<K, V> {
             _ => unreachable!("Must be a delta at key = {:?}, txn_idx = {}", key, txn_idx),
         }
     }
+
+    // Consumes the data-structure and resolves the version chain of every key once, from
+    // the perspective of a transaction at index 'up_to' (i.e. the same outputs as calling
+    // fetch_data(key, up_to) for each key). Keys whose chain resolves to a write or to an
+    // aggregator value are returned in the first map, and keys that still require a storage
+    // base value to be resolved are returned with the accumulated delta in the second map.
+    // It must be guaranteed by the caller that all transactions below 'up_to' are committed.
+    pub(crate) fn into_materialized(
+        self,
+        up_to: TxnIndex,
+    ) -> (HashMap<K, MVDataOutput<V>>, HashMap<K, DeltaOp>) {
+        let mut outputs = HashMap::new();
+        let mut deltas = HashMap::new();
+
+        let spilled_values = self
+            .spilling
+            .map(|spilling| spilling.store.reload_all())
+            .unwrap_or_default();
+        for (key, v) in self.values.into_iter().chain(spilled_values) {
+            match v.read(up_to) {
+                Ok(output) => {
+                    outputs.insert(key, output);
+                },
+                Err(MVDataError::Unresolved(op)) => {
+                    deltas.insert(key, op);
+                },
+                Err(MVDataError::NotFound) => {},
+                Err(e) => unreachable!(
+                    "Committed state can't be resolved at key = {:?}, txn_idx = {}: {:?}",
+                    key, up_to, e
+                ),
+            }
+        }
+
+        (outputs, deltas)
+    }
+}
+
+impl<K, V> VersionedData<K, V>
+where
+    K: Hash + Clone + Debug + Eq + Send + Sync + 'static,
+    V: TransactionWrite + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    /// Switches to bounded-memory mode (see Spilling), where at most 'max_keys_in_memory'
+    /// keys are kept in memory after every write.
+    pub(crate) fn with_spilling(mut self, max_keys_in_memory: usize) -> Self {
+        self.spilling = Some(Spilling {
+            max_keys_in_memory,
+            store: Box::new(FileSpillStore::<K, V>::new()),
+            write_clock: AtomicU64::new(0),
+        });
+        self
+    }
+}
+
+impl<K, V> VersionedData<K, V>
+where
+    K: Hash + Clone + Debug + Eq,
+    V: TransactionWrite + PartialEq,
+{
+    /// Skips a write if the transaction's previous incarnation already wrote an equal value
+    /// at the key. The recorded entry (and its incarnation) is kept, so reads of it are not
+    /// invalidated by the re-execution.
+    pub(crate) fn with_write_dedup(mut self) -> Self {
+        self.write_eq = Some(V::eq);
+        self
+    }
 }
@@ -2,28 +2,203 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
-#[derive(Debug, Error)]
+/// Config errors serialize to JSON tagged by variant, e.g.,
+/// `{"type": "missing", "details": "value"}`, so that they can be surfaced as structured
+/// errors (e.g., by admin endpoints). Wrapped sources serialize as their display string.
+#[derive(Debug, Error, Serialize)]
+#[serde(tag = "type", content = "details", rename_all = "snake_case")]
 pub enum Error {
     #[error("Failed to sanitize the node config! Sanitizer: {0}, Error: {1}")]
     ConfigSanitizerFailed(String, String),
     #[error("Invariant violation: {0}")]
     InvariantViolation(String),
     #[error("Error accessing {0}: {1}")]
-    IO(String, #[source] std::io::Error),
+    #[serde(rename = "io")]
+    IO(
+        String,
+        #[source]
+        #[serde(serialize_with = "serialize_display")]
+        std::io::Error,
+    ),
     #[error("Error (de)serializing {0}: {1}")]
-    BCS(&'static str, #[source] bcs::Error),
+    #[serde(rename = "bcs")]
+    BCS(
+        &'static str,
+        #[source]
+        #[serde(serialize_with = "serialize_display")]
+        bcs::Error,
+    ),
     #[error("Error (de)serializing {0}: {1}")]
-    Yaml(String, #[source] serde_yaml::Error),
+    Yaml(
+        String,
+        #[source]
+        #[serde(serialize_with = "serialize_display")]
+        serde_yaml::Error,
+    ),
+    #[error("Error (de)serializing {0}: {1}")]
+    Json(
+        String,
+        #[source]
+        #[serde(serialize_with = "serialize_display")]
+        serde_json::Error,
+    ),
+    #[error("Conflicting config between {first} and {second}: {detail}")]
+    Conflict {
+        first: String,
+        second: String,
+        detail: String,
+    },
     #[error("Config is missing expected value: {0}")]
     Missing(&'static str),
     #[error("Unexpected error: {0}")]
     Unexpected(String),
 }
 
+impl Error {
+    /// Returns a converter of serde_json errors into config errors for the given
+    /// config (fragment), e.g., `serde_json::from_str(..).map_err(Error::json("features"))`.
+    pub fn json(name: impl Into<String>) -> impl FnOnce(serde_json::Error) -> Error {
+        let name = name.into();
+        move |error| Error::Json(name, error)
+    }
+
+    /// Returns an error for two config sections (or fields) with conflicting settings,
+    /// e.g., `Error::conflict("api", "inspection_service", "both use port 9101")`.
+    pub fn conflict(
+        first: impl Into<String>,
+        second: impl Into<String>,
+        detail: impl Into<String>,
+    ) -> Error {
+        Error::Conflict {
+            first: first.into(),
+            second: second.into(),
+            detail: detail.into(),
+        }
+    }
+
+    /// Returns true iff the error may be transient (e.g., an interrupted or timed out
+    /// read of the config file), so that the operation is worth retrying. Invalid or
+    /// missing config values are never retryable.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::IO(_, error) => matches!(
+                error.kind(),
+                std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        }
+    }
+}
+
+fn serialize_display<T: std::fmt::Display, S: Serializer>(
+    value: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_str(value)
+}
+
 impl From<anyhow::Error> for Error {
     fn from(error: anyhow::Error) -> Self {
         Error::Unexpected(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_error() {
+        let error = serde_json::from_str::<serde_json::Value>("{\"features\": [1, 2")
+            .map_err(Error::json("features"))
+            .unwrap_err();
+        match error {
+            Error::Json(name, _) => assert_eq!(name, "features"),
+            error => panic!("Expected a JSON error, got: {:?}", error),
+        }
+    }
+
+    #[test]
+    fn test_conflict_error() {
+        let error = Error::conflict("api", "inspection_service", "both use port 9101");
+        assert_eq!(
+            error.to_string(),
+            "Conflicting config between api and inspection_service: both use port 9101"
+        );
+        match error {
+            Error::Conflict { first, second, .. } => {
+                assert_eq!(first, "api");
+                assert_eq!(second, "inspection_service");
+            },
+            error => panic!("Expected a conflict error, got: {:?}", error),
+        }
+        assert!(!Error::conflict("a", "b", "detail").is_retryable());
+    }
+
+    #[test]
+    fn test_serialize_error() {
+        let json_error = serde_json::from_str::<u64>("a").unwrap_err();
+        let yaml_error = serde_yaml::from_str::<u64>("a").unwrap_err();
+        let errors = vec![
+            (
+                Error::ConfigSanitizerFailed("sanitizer".into(), "error".into()),
+                "config_sanitizer_failed",
+            ),
+            (
+                Error::InvariantViolation("invariant".into()),
+                "invariant_violation",
+            ),
+            (
+                Error::IO(
+                    "node.yaml".into(),
+                    std::io::Error::from(std::io::ErrorKind::NotFound),
+                ),
+                "io",
+            ),
+            (
+                Error::BCS("config", bcs::Error::Custom("custom".into())),
+                "bcs",
+            ),
+            (Error::Yaml("node.yaml".into(), yaml_error), "yaml"),
+            (Error::Json("features".into(), json_error), "json"),
+            (
+                Error::conflict("api", "inspection_service", "port"),
+                "conflict",
+            ),
+            (Error::Missing("value"), "missing"),
+            (Error::Unexpected("unexpected".into()), "unexpected"),
+        ];
+        for (error, tag) in errors {
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(json["type"], tag);
+            assert!(!json["details"].is_null());
+        }
+
+        // Wrapped sources serialize as their display string.
+        let error = Error::IO(
+            "node.yaml".into(),
+            std::io::Error::new(std::io::ErrorKind::Other, "disk on fire"),
+        );
+        assert_eq!(
+            serde_json::to_value(&error).unwrap()["details"],
+            serde_json::json!(["node.yaml", "disk on fire"])
+        );
+    }
+
+    #[test]
+    fn test_is_retryable() {
+        let interrupted = std::io::Error::from(std::io::ErrorKind::Interrupted);
+        assert!(Error::IO("node.yaml".into(), interrupted).is_retryable());
+
+        let not_found = std::io::Error::from(std::io::ErrorKind::NotFound);
+        assert!(!Error::IO("node.yaml".into(), not_found).is_retryable());
+        assert!(!Error::ConfigSanitizerFailed("sanitizer".into(), "error".into()).is_retryable());
+        assert!(!Error::InvariantViolation("invariant".into()).is_retryable());
+        assert!(!Error::Missing("value").is_retryable());
+    }
+}
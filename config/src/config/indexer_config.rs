@@ -86,6 +86,11 @@ pub struct IndexerConfig {
     /// Custom NFT points contract
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nft_points_contract: Option<String>,
+
+    /// If set, token v2 resources whose ObjectCore is missing from the batch are buffered and
+    /// retried at the end of the batch, instead of being dropped. Only for token_processor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffer_missing_token_v2_metadata: Option<bool>,
 }
 
 impl Debug for IndexerConfig {
@@ -111,6 +116,10 @@ impl Debug for IndexerConfig {
             .field("gap_lookback_versions", &self.gap_lookback_versions)
             .field("ans_contract_address", &self.ans_contract_address)
             .field("nft_points_contract", &self.nft_points_contract)
+            .field(
+                "buffer_missing_token_v2_metadata",
+                &self.buffer_missing_token_v2_metadata,
+            )
             .finish()
     }
 }
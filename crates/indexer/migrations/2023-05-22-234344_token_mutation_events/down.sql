@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS token_mutation_events;
+DROP INDEX IF EXISTS tme_tid_index;
+DROP INDEX IF EXISTS tme_insat_index;
\ No newline at end of file
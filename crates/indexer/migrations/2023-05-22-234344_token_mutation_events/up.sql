@@ -0,0 +1,19 @@
+-- Your SQL goes here
+-- History of changes to the name, uri and description of token v2 (current_token_datas_v2 only keeps the latest)
+CREATE TABLE IF NOT EXISTS token_mutation_events (
+  transaction_version BIGINT NOT NULL,
+  write_set_change_index BIGINT NOT NULL,
+  token_data_id VARCHAR(66) NOT NULL,
+  mutated_field_name VARCHAR(50) NOT NULL,
+  old_value TEXT NOT NULL,
+  new_value TEXT NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (
+    transaction_version,
+    write_set_change_index,
+    mutated_field_name
+  )
+);
+CREATE INDEX IF NOT EXISTS tme_tid_index ON token_mutation_events (token_data_id);
+CREATE INDEX IF NOT EXISTS tme_insat_index ON token_mutation_events (inserted_at);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS processor_token_standard_stats;
+DROP INDEX IF EXISTS ptss_tb_ts_index;
+DROP INDEX IF EXISTS ptss_insat_index;
\ No newline at end of file
@@ -0,0 +1,19 @@
+-- Your SQL goes here
+-- Number of token datas processed per token standard, per hour and per processed batch
+-- (the batch makes reprocessing idempotent), e.g. to track the adoption of token v2
+CREATE TABLE IF NOT EXISTS processor_token_standard_stats (
+  start_version BIGINT NOT NULL,
+  end_version BIGINT NOT NULL,
+  time_bucket TIMESTAMP NOT NULL,
+  token_standard VARCHAR(10) NOT NULL,
+  num_token_datas BIGINT NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (
+    start_version,
+    end_version,
+    time_bucket,
+    token_standard
+  )
+);
+CREATE INDEX IF NOT EXISTS ptss_tb_ts_index ON processor_token_standard_stats (time_bucket, token_standard);
+CREATE INDEX IF NOT EXISTS ptss_insat_index ON processor_token_standard_stats (inserted_at);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS collection_cap_events;
+DROP INDEX IF EXISTS cce_tv_index;
+DROP INDEX IF EXISTS cce_insat_index;
\ No newline at end of file
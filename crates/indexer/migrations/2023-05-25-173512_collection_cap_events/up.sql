@@ -0,0 +1,14 @@
+-- Your SQL goes here
+-- Records, once per collection, the collection write that brought its supply up to its
+-- max supply, e.g. to trigger "sold out" notifications without polling
+CREATE TABLE IF NOT EXISTS collection_cap_events (
+  collection_id VARCHAR(66) UNIQUE PRIMARY KEY NOT NULL,
+  token_data_id VARCHAR(66),
+  transaction_version BIGINT NOT NULL,
+  maximum NUMERIC NOT NULL,
+  supply NUMERIC NOT NULL,
+  transaction_timestamp TIMESTAMP NOT NULL,
+  inserted_at TIMESTAMP NOT NULL DEFAULT NOW()
+);
+CREATE INDEX IF NOT EXISTS cce_tv_index ON collection_cap_events (transaction_version);
+CREATE INDEX IF NOT EXISTS cce_insat_index ON collection_cap_events (inserted_at);
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- Flags token data writes that changed the description of the token, relative to the prior
+-- write in the same batch, so that description changes can be found without a self-join
+ALTER TABLE token_datas_v2
+ADD COLUMN IF NOT EXISTS description_mutated BOOLEAN NOT NULL DEFAULT FALSE;
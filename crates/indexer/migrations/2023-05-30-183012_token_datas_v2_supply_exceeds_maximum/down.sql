@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE token_datas_v2 DROP COLUMN IF EXISTS supply_exceeds_maximum;
\ No newline at end of file
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+-- Flags token data writes whose supply is above the maximum, which should be impossible, so
+-- that such anomalies can be found instead of silently stored
+ALTER TABLE token_datas_v2
+ADD COLUMN IF NOT EXISTS supply_exceeds_maximum BOOLEAN NOT NULL DEFAULT FALSE;
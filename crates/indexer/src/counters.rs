@@ -73,6 +73,25 @@ pub static FETCHED_TRANSACTION: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Number of v2 token resources without the ObjectCore in the batch metadata, counted once
+/// per resource (i.e. after the second pass if they are buffered for it)
+pub static MISSING_TOKEN_V2_METADATA: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_missing_token_v2_metadata_count",
+        "Number of v2 token resources without the ObjectCore in the batch metadata"
+    )
+    .unwrap()
+});
+
+/// Number of token data writes seen with a supply above the maximum
+pub static TOKEN_SUPPLY_EXCEEDS_MAXIMUM: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "indexer_token_supply_exceeds_maximum_count",
+        "Number of token data writes seen with a supply above the maximum"
+    )
+    .unwrap()
+});
+
 /// Max version processed
 pub static LATEST_PROCESSED_VERSION: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -0,0 +1,166 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use super::{v2_collections::CollectionV2, v2_token_datas::TokenDataV2};
+use crate::schema::collection_cap_events;
+use bigdecimal::{BigDecimal, Zero};
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Emitted when the supply of a collection with a max supply reaches it, i.e. the collection
+/// is sold out. Keyed by collection, so only the first such write is recorded (later ones,
+/// e.g. after a burn and a new mint, are not inserted).
+#[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(collection_id))]
+#[diesel(table_name = collection_cap_events)]
+pub struct CollectionCapEvent {
+    pub collection_id: String,
+    /// Token data minted by the transaction, if any (e.g. not for supply changes by the
+    /// creator).
+    pub token_data_id: Option<String>,
+    pub transaction_version: i64,
+    pub maximum: BigDecimal,
+    pub supply: BigDecimal,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl CollectionCapEvent {
+    /// Returns the cap events of the collection writes (in transaction order), at most one
+    /// per collection. Collections without a max supply (None for v2, zero for v1) are
+    /// unlimited and never capped. Token datas are only used to tell which token data was
+    /// minted by the transaction capping the collection.
+    pub fn from_collections(
+        collections: &[CollectionV2],
+        token_datas: &[TokenDataV2],
+    ) -> Vec<Self> {
+        let mut capped_collections = HashSet::new();
+        collections
+            .iter()
+            .filter_map(|collection| {
+                let max_supply = collection
+                    .max_supply
+                    .as_ref()
+                    .filter(|max_supply| !max_supply.is_zero())?;
+                if collection.current_supply < *max_supply
+                    || !capped_collections.insert(collection.collection_id.clone())
+                {
+                    return None;
+                }
+                let token_data_id = token_datas
+                    .iter()
+                    .find(|token_data| {
+                        token_data.transaction_version == collection.transaction_version
+                            && token_data.collection_id == collection.collection_id
+                    })
+                    .map(|token_data| token_data.token_data_id.clone());
+                Some(Self {
+                    collection_id: collection.collection_id.clone(),
+                    token_data_id,
+                    transaction_version: collection.transaction_version,
+                    maximum: max_supply.clone(),
+                    supply: collection.current_supply.clone(),
+                    transaction_timestamp: collection.transaction_timestamp,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_models::v2_token_utils::TokenStandard;
+
+    fn collection(
+        transaction_version: i64,
+        token_standard: TokenStandard,
+        max_supply: Option<i64>,
+        current_supply: i64,
+    ) -> CollectionV2 {
+        CollectionV2 {
+            transaction_version,
+            write_set_change_index: 0,
+            collection_id: "0x2".to_string(),
+            creator_address: "0x3".to_string(),
+            collection_name: "collection".to_string(),
+            description: "description".to_string(),
+            uri: "uri".to_string(),
+            current_supply: BigDecimal::from(current_supply),
+            max_supply: max_supply.map(BigDecimal::from),
+            total_minted_v2: None,
+            mutable_description: None,
+            mutable_uri: None,
+            table_handle_v1: None,
+            token_standard: token_standard.to_string(),
+            transaction_timestamp: chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    fn token_data(transaction_version: i64, maximum: Option<i64>, supply: i64) -> TokenDataV2 {
+        TokenDataV2 {
+            transaction_version,
+            write_set_change_index: 1,
+            token_data_id: format!("0x1{}", transaction_version),
+            collection_id: "0x2".to_string(),
+            token_name: "name".to_string(),
+            maximum: maximum.map(BigDecimal::from),
+            supply: BigDecimal::from(supply),
+            largest_property_version_v1: None,
+            token_uri: "uri".to_string(),
+            token_properties: serde_json::Value::Null,
+            description: "description".to_string(),
+            token_standard: TokenStandard::V2.to_string(),
+            is_fungible_v2: None,
+            transaction_timestamp: chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+            description_mutated: false,
+            supply_exceeds_maximum: false,
+        }
+    }
+
+    #[test]
+    fn test_cap_event_emitted_once() {
+        let collections = vec![
+            collection(2, TokenStandard::V2, Some(3), 1),
+            collection(3, TokenStandard::V2, Some(3), 2),
+            collection(4, TokenStandard::V2, Some(3), 3),
+            // Minting after a burn reaches the max supply again.
+            collection(5, TokenStandard::V2, Some(3), 2),
+            collection(6, TokenStandard::V2, Some(3), 3),
+        ];
+        // v2 NFTs have no maximum of their own.
+        let token_datas = (2..=6)
+            .map(|version| token_data(version, None, 1))
+            .collect();
+
+        let cap_events = CollectionCapEvent::from_collections(&collections, &token_datas);
+        assert_eq!(cap_events.len(), 1);
+        assert_eq!(cap_events[0].collection_id, "0x2");
+        assert_eq!(cap_events[0].token_data_id, Some("0x14".to_string()));
+        assert_eq!(cap_events[0].transaction_version, 4);
+        assert_eq!(cap_events[0].maximum, BigDecimal::from(3));
+        assert_eq!(cap_events[0].supply, BigDecimal::from(3));
+    }
+
+    #[test]
+    fn test_unlimited_collections_are_never_capped() {
+        // A v1 collection with a zero maximum is unlimited, same as a v2 one without max supply.
+        let collections = vec![
+            collection(1, TokenStandard::V1, Some(0), 5),
+            collection(2, TokenStandard::V2, None, 5),
+        ];
+        assert!(CollectionCapEvent::from_collections(&collections, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_one_of_one_token_does_not_cap_collection() {
+        // A v1 1-of-1 NFT reaches the maximum of its token data, not that of the collection.
+        let collections = vec![collection(1, TokenStandard::V1, Some(10), 1)];
+        let token_datas = vec![token_data(1, Some(1), 1)];
+        assert!(CollectionCapEvent::from_collections(&collections, &token_datas).is_empty());
+    }
+}
@@ -2,11 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod ans_lookup;
+pub mod collection_cap_events;
 pub mod collection_datas;
 pub mod nft_points;
+pub mod processor_token_standard_stats;
 pub mod token_activities;
 pub mod token_claims;
 pub mod token_datas;
+pub mod token_mutation_events;
 pub mod token_ownerships;
 pub mod token_utils;
 pub mod tokens;
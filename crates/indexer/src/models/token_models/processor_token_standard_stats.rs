@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use super::v2_token_datas::TokenDataV2;
+use crate::schema::processor_token_standard_stats;
+use chrono::Timelike;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Number of token datas (v1 and v2) processed in a batch, per hour and token standard.
+/// Rows are keyed by the version range of the batch, so reprocessing a batch doesn't count
+/// its token datas again. Summing over batches gives e.g. the adoption of token v2 per hour
+/// without scanning token_datas_v2.
+#[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(start_version, end_version, time_bucket, token_standard))]
+#[diesel(table_name = processor_token_standard_stats)]
+pub struct ProcessorTokenStandardStat {
+    pub start_version: i64,
+    pub end_version: i64,
+    pub time_bucket: chrono::NaiveDateTime,
+    pub token_standard: String,
+    pub num_token_datas: i64,
+}
+
+impl ProcessorTokenStandardStat {
+    /// Counts the token datas of the batch [start_version, end_version] by the hour of
+    /// their transaction timestamp and by token standard.
+    pub fn from_token_datas(
+        token_datas: &[TokenDataV2],
+        start_version: u64,
+        end_version: u64,
+    ) -> Vec<Self> {
+        let mut counts: BTreeMap<(chrono::NaiveDateTime, &str), i64> = BTreeMap::new();
+        for token_data in token_datas {
+            let time_bucket = Self::get_time_bucket(token_data.transaction_timestamp);
+            *counts
+                .entry((time_bucket, token_data.token_standard.as_str()))
+                .or_insert(0) += 1;
+        }
+        counts
+            .into_iter()
+            .map(|((time_bucket, token_standard), num_token_datas)| Self {
+                start_version: start_version as i64,
+                end_version: end_version as i64,
+                time_bucket,
+                token_standard: token_standard.to_string(),
+                num_token_datas,
+            })
+            .collect()
+    }
+
+    /// Truncates the timestamp to the hour.
+    fn get_time_bucket(timestamp: chrono::NaiveDateTime) -> chrono::NaiveDateTime {
+        timestamp
+            .date()
+            .and_hms_opt(timestamp.hour(), 0, 0)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_models::v2_token_utils::TokenStandard;
+    use bigdecimal::{BigDecimal, Zero};
+
+    fn token_data(
+        transaction_version: i64,
+        token_standard: TokenStandard,
+        timestamp_secs: i64,
+    ) -> TokenDataV2 {
+        TokenDataV2 {
+            transaction_version,
+            write_set_change_index: 0,
+            token_data_id: format!("0x{}", transaction_version),
+            collection_id: "0x1".to_string(),
+            token_name: "name".to_string(),
+            maximum: None,
+            supply: BigDecimal::zero(),
+            largest_property_version_v1: None,
+            token_uri: "uri".to_string(),
+            token_properties: serde_json::Value::Null,
+            description: "description".to_string(),
+            token_standard: token_standard.to_string(),
+            is_fungible_v2: None,
+            transaction_timestamp: chrono::NaiveDateTime::from_timestamp_opt(timestamp_secs, 0)
+                .unwrap(),
+            description_mutated: false,
+            supply_exceeds_maximum: false,
+        }
+    }
+
+    #[test]
+    fn test_counts_by_token_standard() {
+        let token_datas = vec![
+            token_data(10, TokenStandard::V1, 60),
+            token_data(11, TokenStandard::V2, 120),
+            token_data(12, TokenStandard::V2, 1800),
+        ];
+
+        let stats = ProcessorTokenStandardStat::from_token_datas(&token_datas, 10, 19);
+        let counts: Vec<(i64, &str, i64)> = stats
+            .iter()
+            .map(|stat| {
+                (
+                    stat.time_bucket.timestamp(),
+                    stat.token_standard.as_str(),
+                    stat.num_token_datas,
+                )
+            })
+            .collect();
+        assert_eq!(counts, vec![(0, "v1", 1), (0, "v2", 2)]);
+        assert!(stats
+            .iter()
+            .all(|stat| stat.start_version == 10 && stat.end_version == 19));
+
+        // Reprocessing the batch produces the same keys, which are not inserted again.
+        let reprocessed = ProcessorTokenStandardStat::from_token_datas(&token_datas, 10, 19);
+        let keys = |stats: &[ProcessorTokenStandardStat]| {
+            stats
+                .iter()
+                .map(|stat| {
+                    (
+                        stat.start_version,
+                        stat.end_version,
+                        stat.time_bucket,
+                        stat.token_standard.clone(),
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(keys(&stats), keys(&reprocessed));
+
+        // Token datas of the next hour are counted separately.
+        let stats = ProcessorTokenStandardStat::from_token_datas(
+            &[token_data(20, TokenStandard::V2, 3600 + 60)],
+            20,
+            29,
+        );
+        assert_eq!(stats[0].time_bucket.timestamp(), 3600);
+    }
+}
@@ -0,0 +1,202 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+// This is required because a diesel macro makes clippy sad
+#![allow(clippy::extra_unused_lifetimes)]
+#![allow(clippy::unused_unit)]
+
+use super::v2_token_datas::{CurrentTokenDataV2, CurrentTokenDataV2PK, TokenDataV2};
+use crate::schema::token_mutation_events;
+use field_count::FieldCount;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A change to the name, uri or description of a token v2, which current_token_datas_v2
+/// overwrites, to keep the history of mutations of evolving tokens.
+#[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
+#[diesel(primary_key(transaction_version, write_set_change_index, mutated_field_name))]
+#[diesel(table_name = token_mutation_events)]
+pub struct TokenMutationEvent {
+    pub transaction_version: i64,
+    pub write_set_change_index: i64,
+    pub token_data_id: String,
+    pub mutated_field_name: String,
+    pub old_value: String,
+    pub new_value: String,
+    pub transaction_timestamp: chrono::NaiveDateTime,
+}
+
+impl TokenMutationEvent {
+    /// Returns a mutation for each of token_name, token_uri and description that differs
+    /// between the prior current row of the token and the one from the write resource at
+    /// the given index. Nothing is returned if the prior row is not older.
+    pub fn get_from_token_data_change(
+        prior: &CurrentTokenDataV2,
+        current: &CurrentTokenDataV2,
+        write_set_change_index: i64,
+    ) -> Vec<Self> {
+        if prior.last_transaction_version >= current.last_transaction_version {
+            return vec![];
+        }
+        [
+            ("token_name", &prior.token_name, &current.token_name),
+            ("token_uri", &prior.token_uri, &current.token_uri),
+            ("description", &prior.description, &current.description),
+        ]
+        .into_iter()
+        .filter(|(_, old_value, new_value)| old_value != new_value)
+        .map(|(field_name, old_value, new_value)| Self {
+            transaction_version: current.last_transaction_version,
+            write_set_change_index,
+            token_data_id: current.token_data_id.clone(),
+            mutated_field_name: field_name.to_string(),
+            old_value: old_value.clone(),
+            new_value: new_value.clone(),
+            transaction_timestamp: current.last_transaction_timestamp,
+        })
+        .collect()
+    }
+
+    /// Returns the mutations of the token data from a write resource against its prior current
+    /// row, from this batch or otherwise from the db. Against a prior row from this batch, this
+    /// also flags whether the description of the token data changed.
+    pub fn get_from_token_data_v2(
+        token_data: &mut TokenDataV2,
+        current_token_data: &CurrentTokenDataV2,
+        current_token_datas_v2: &HashMap<CurrentTokenDataV2PK, CurrentTokenDataV2>,
+        prior_token_datas_v2: &HashMap<CurrentTokenDataV2PK, CurrentTokenDataV2>,
+    ) -> Vec<Self> {
+        match current_token_datas_v2.get(&current_token_data.token_data_id) {
+            Some(prior) => {
+                token_data.detect_description_mutation(prior);
+                Self::get_from_token_data_change(
+                    prior,
+                    current_token_data,
+                    token_data.write_set_change_index,
+                )
+            },
+            None => prior_token_datas_v2
+                .get(&current_token_data.token_data_id)
+                .map(|prior| {
+                    Self::get_from_token_data_change(
+                        prior,
+                        current_token_data,
+                        token_data.write_set_change_index,
+                    )
+                })
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        models::token_models::v2_token_utils::{
+            TokenV2AggregatedData, TokenV2AggregatedDataMapping,
+        },
+        util::standardize_address,
+    };
+    use aptos_api_types::WriteResource;
+
+    fn get_token_write_resource(uri: &str) -> WriteResource {
+        serde_json::from_value(serde_json::json!({
+            "address": "0x123",
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x4::token::Token",
+                "data": {
+                    "collection": { "inner": "0x456" },
+                    "description": "description",
+                    "name": "name",
+                    "uri": uri,
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn get_current_token_data(
+        uri: &str,
+        txn_version: i64,
+        token_v2_metadata: &TokenV2AggregatedDataMapping,
+    ) -> CurrentTokenDataV2 {
+        let txn_timestamp = chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let (_, current_token_data) = TokenDataV2::get_v2_from_write_resource(
+            &get_token_write_resource(uri),
+            txn_version,
+            0,
+            txn_timestamp,
+            token_v2_metadata,
+            None,
+        )
+        .unwrap()
+        .unwrap();
+        current_token_data
+    }
+
+    fn get_token_v2_metadata() -> TokenV2AggregatedDataMapping {
+        HashMap::from([(
+            standardize_address("0x123"),
+            TokenV2AggregatedData::new_for_test("0x789", None, false),
+        )])
+    }
+
+    #[test]
+    fn test_uri_mutation() {
+        let token_v2_metadata = get_token_v2_metadata();
+        let prior = get_current_token_data("uri-1", 1, &token_v2_metadata);
+        let current = get_current_token_data("uri-2", 2, &token_v2_metadata);
+
+        let mutations = TokenMutationEvent::get_from_token_data_change(&prior, &current, 3);
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(mutations[0].transaction_version, 2);
+        assert_eq!(mutations[0].write_set_change_index, 3);
+        assert_eq!(mutations[0].token_data_id, standardize_address("0x123"));
+        assert_eq!(mutations[0].mutated_field_name, "token_uri");
+        assert_eq!(mutations[0].old_value, "uri-1");
+        assert_eq!(mutations[0].new_value, "uri-2");
+
+        // Replaying an older write is not a mutation.
+        assert!(TokenMutationEvent::get_from_token_data_change(&current, &prior, 3).is_empty());
+    }
+
+    #[test]
+    fn test_uri_mutation_of_buffered_token() {
+        let txn_timestamp = chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let mut pending_token_datas = vec![];
+        assert!(TokenDataV2::get_v2_from_write_resource(
+            &get_token_write_resource("uri-2"),
+            2,
+            3,
+            txn_timestamp,
+            &HashMap::new(),
+            Some(&mut pending_token_datas)
+        )
+        .unwrap()
+        .is_none());
+
+        // The metadata shows up later in the batch, and the token had another uri in the db.
+        let token_v2_metadata = get_token_v2_metadata();
+        let prior = get_current_token_data("uri-1", 1, &token_v2_metadata);
+        let prior_token_datas_v2 = HashMap::from([(prior.token_data_id.clone(), prior)]);
+        let (mut token_data, current_token_data) = pending_token_datas[0]
+            .resolve(&token_v2_metadata)
+            .unwrap()
+            .unwrap();
+
+        let mutations = TokenMutationEvent::get_from_token_data_v2(
+            &mut token_data,
+            &current_token_data,
+            &HashMap::new(),
+            &prior_token_datas_v2,
+        );
+        assert_eq!(mutations.len(), 1);
+        assert_eq!(mutations[0].transaction_version, 2);
+        assert_eq!(mutations[0].write_set_change_index, 3);
+        assert_eq!(mutations[0].mutated_field_name, "token_uri");
+        assert_eq!(mutations[0].old_value, "uri-1");
+        assert_eq!(mutations[0].new_value, "uri-2");
+    }
+}
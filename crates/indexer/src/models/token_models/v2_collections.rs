@@ -112,6 +112,13 @@ impl CollectionV2 {
                 // Getting supply data (prefer fixed supply over unlimited supply although they should never appear at the same time anyway)
                 let fixed_supply = metadata.fixed_supply.as_ref();
                 let unlimited_supply = metadata.unlimited_supply.as_ref();
+                if let Some(supply) = metadata.concurrent_supply.as_ref() {
+                    (current_supply, max_supply, total_minted_v2) = (
+                        supply.get_current_supply(),
+                        supply.get_max_supply(),
+                        supply.get_total_minted(),
+                    );
+                }
                 if let Some(supply) = unlimited_supply {
                     (current_supply, max_supply, total_minted_v2) = (
                         supply.current_supply.clone(),
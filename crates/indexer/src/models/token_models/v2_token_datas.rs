@@ -7,16 +7,22 @@
 
 use super::{
     token_utils::TokenWriteSet,
-    v2_token_utils::{TokenStandard, TokenV2, TokenV2AggregatedDataMapping},
+    v2_token_utils::{TokenStandard, TokenV2, TokenV2AggregatedData, TokenV2AggregatedDataMapping},
 };
 use crate::{
+    counters::{MISSING_TOKEN_V2_METADATA, TOKEN_SUPPLY_EXCEEDS_MAXIMUM},
+    database::PgPoolConnection,
     schema::{current_token_datas_v2, token_datas_v2},
     util::standardize_address,
 };
-use aptos_api_types::{WriteResource as APIWriteResource, WriteTableItem as APIWriteTableItem};
+use aptos_api_types::{
+    WriteResource as APIWriteResource, WriteSetChange, WriteTableItem as APIWriteTableItem,
+};
 use bigdecimal::{BigDecimal, Zero};
+use diesel::{prelude::*, ExpressionMethods};
 use field_count::FieldCount;
 use serde::{Deserialize, Serialize};
+use std::collections::{hash_map::Entry, HashMap};
 
 // PK of current_token_datas_v2, i.e. token_data_id
 pub type CurrentTokenDataV2PK = String;
@@ -39,6 +45,10 @@ pub struct TokenDataV2 {
     pub token_standard: String,
     pub is_fungible_v2: Option<bool>,
     pub transaction_timestamp: chrono::NaiveDateTime,
+    // Whether the description differs from the prior row of the token in the same batch
+    pub description_mutated: bool,
+    // Whether the supply is above the maximum, which should be impossible
+    pub supply_exceeds_maximum: bool,
 }
 
 #[derive(Debug, Deserialize, FieldCount, Identifiable, Insertable, Serialize)]
@@ -60,34 +70,158 @@ pub struct CurrentTokenDataV2 {
     pub last_transaction_timestamp: chrono::NaiveDateTime,
 }
 
+/// Need a separate struct for queryable because we don't want to define the inserted_at column (letting DB fill)
+#[derive(Debug, Identifiable, Queryable)]
+#[diesel(primary_key(token_data_id))]
+#[diesel(table_name = current_token_datas_v2)]
+pub struct CurrentTokenDataV2Query {
+    pub token_data_id: String,
+    pub collection_id: String,
+    pub token_name: String,
+    pub maximum: Option<BigDecimal>,
+    pub supply: BigDecimal,
+    pub largest_property_version_v1: Option<BigDecimal>,
+    pub token_uri: String,
+    pub description: String,
+    pub token_properties: serde_json::Value,
+    pub token_standard: String,
+    pub is_fungible_v2: Option<bool>,
+    pub last_transaction_version: i64,
+    pub last_transaction_timestamp: chrono::NaiveDateTime,
+    pub inserted_at: chrono::NaiveDateTime,
+}
+
+/// A v2 token write resource whose ObjectCore was missing from the metadata when it was
+/// processed (e.g. due to out of order processing), buffered for a second pass at the end of
+/// the batch instead of being dropped.
+#[derive(Clone, Debug)]
+pub struct PendingTokenDataV2 {
+    pub write_resource: APIWriteResource,
+    pub txn_version: i64,
+    pub write_set_change_index: i64,
+    pub txn_timestamp: chrono::NaiveDateTime,
+}
+
+impl PendingTokenDataV2 {
+    /// Retries parsing the buffered resource, with the metadata of the entire batch. Only counted
+    /// as missing metadata if it is still missing.
+    pub fn resolve(
+        &self,
+        token_v2_metadata: &TokenV2AggregatedDataMapping,
+    ) -> anyhow::Result<Option<(TokenDataV2, CurrentTokenDataV2)>> {
+        TokenDataV2::get_v2_from_write_resource(
+            &self.write_resource,
+            self.txn_version,
+            self.write_set_change_index,
+            self.txn_timestamp,
+            token_v2_metadata,
+            None,
+        )
+    }
+}
+
+impl CurrentTokenDataV2 {
+    /// Upserts the current token data into the map, unless the map already holds a row from a
+    /// higher transaction version. This keeps out-of-order (re)processing from regressing the
+    /// current state, same as the last_transaction_version guard in the db upsert.
+    ///
+    /// If the row already in the map has identical content (i.e. only the version and timestamp
+    /// moved), it is kept as is, so that frequently touched tokens don't cause write
+    /// amplification. Returns whether the map was updated.
+    pub fn upsert_into(
+        self,
+        current_token_datas: &mut HashMap<CurrentTokenDataV2PK, Self>,
+    ) -> bool {
+        match current_token_datas.entry(self.token_data_id.clone()) {
+            Entry::Occupied(mut entry) => {
+                if entry.get().last_transaction_version <= self.last_transaction_version
+                    && !entry.get().has_same_content(&self)
+                {
+                    entry.insert(self);
+                    true
+                } else {
+                    false
+                }
+            },
+            Entry::Vacant(entry) => {
+                entry.insert(self);
+                true
+            },
+        }
+    }
+
+    /// Compares all substantive fields, ignoring last_transaction_version and
+    /// last_transaction_timestamp.
+    pub fn has_same_content(&self, other: &Self) -> bool {
+        self.token_data_id == other.token_data_id
+            && self.collection_id == other.collection_id
+            && self.token_name == other.token_name
+            && self.maximum == other.maximum
+            && self.supply == other.supply
+            && self.largest_property_version_v1 == other.largest_property_version_v1
+            && self.token_uri == other.token_uri
+            && self.token_properties == other.token_properties
+            && self.description == other.description
+            && self.token_standard == other.token_standard
+            && self.is_fungible_v2 == other.is_fungible_v2
+    }
+}
+
 impl TokenDataV2 {
+    /// Sets description_mutated if the description differs from the prior current row of
+    /// the token, e.g. from earlier in the batch. A prior row that is not older is ignored.
+    pub fn detect_description_mutation(&mut self, prior: &CurrentTokenDataV2) {
+        if prior.last_transaction_version < self.transaction_version {
+            self.description_mutated = prior.description != self.description;
+        }
+    }
+
     pub fn get_v2_from_write_resource(
         write_resource: &APIWriteResource,
         txn_version: i64,
         write_set_change_index: i64,
         txn_timestamp: chrono::NaiveDateTime,
         token_v2_metadata: &TokenV2AggregatedDataMapping,
+        pending_token_datas: Option<&mut Vec<PendingTokenDataV2>>,
     ) -> anyhow::Result<Option<(Self, CurrentTokenDataV2)>> {
         if let Some(inner) = &TokenV2::from_write_resource(write_resource, txn_version)? {
             let token_data_id = standardize_address(&write_resource.address.to_string());
-            // Get maximum, supply, and is fungible from fungible asset if this is a fungible token
-            let (maximum, supply, is_fungible_v2) = (None, BigDecimal::zero(), Some(false));
-            // Get token properties from 0x4::property_map::PropertyMap
-            let mut token_properties = serde_json::Value::Null;
-            if let Some(metadata) = token_v2_metadata.get(&token_data_id) {
-                token_properties = metadata
-                    .property_map
-                    .as_ref()
-                    .map(|m| m.inner.clone())
-                    .unwrap_or(token_properties);
-            } else {
-                // ObjectCore should not be missing, returning from entire function early
-                return Ok(None);
-            }
+            let (is_fungible_v2, maximum, supply, token_properties) =
+                if let Some(metadata) = token_v2_metadata.get(&token_data_id) {
+                    // Get maximum, supply, and is fungible from the supply of a fungible token
+                    let (is_fungible_v2, maximum, supply) = classify_token_standard(metadata);
+                    // Get token properties from 0x4::property_map::PropertyMap
+                    let token_properties = metadata
+                        .property_map
+                        .as_ref()
+                        .map(|m| m.inner.clone())
+                        .unwrap_or(serde_json::Value::Null);
+                    (is_fungible_v2, maximum, supply, token_properties)
+                } else {
+                    // ObjectCore should not be missing, but it can be with out of order processing.
+                    // If requested, the resource is buffered for a second pass (which counts it
+                    // as missing if it still is), otherwise dropped.
+                    match pending_token_datas {
+                        Some(pending_token_datas) => pending_token_datas.push(PendingTokenDataV2 {
+                            write_resource: write_resource.clone(),
+                            txn_version,
+                            write_set_change_index,
+                            txn_timestamp,
+                        }),
+                        None => MISSING_TOKEN_V2_METADATA.inc(),
+                    }
+                    return Ok(None);
+                };
 
             let collection_id = inner.get_collection_address();
             let token_name = inner.get_name_trunc();
             let token_uri = inner.get_uri_trunc();
+            let supply_exceeds_maximum = check_supply_exceeds_maximum(
+                txn_version,
+                &token_data_id,
+                &supply,
+                maximum.as_ref(),
+            );
 
             Ok(Some((
                 Self {
@@ -105,6 +239,8 @@ impl TokenDataV2 {
                     token_standard: TokenStandard::V2.to_string(),
                     is_fungible_v2,
                     transaction_timestamp: txn_timestamp,
+                    description_mutated: false,
+                    supply_exceeds_maximum,
                 },
                 CurrentTokenDataV2 {
                     token_data_id,
@@ -158,6 +294,13 @@ impl TokenDataV2 {
                 let token_data_id = token_data_id_struct.to_id();
                 let token_name = token_data_id_struct.get_name_trunc();
                 let token_uri = token_data.get_uri_trunc();
+                // A maximum of 0 means the supply is unlimited (and not tracked) in v1
+                let supply_exceeds_maximum = check_supply_exceeds_maximum(
+                    txn_version,
+                    &token_data_id,
+                    &token_data.supply,
+                    Some(&token_data.maximum).filter(|maximum| !maximum.is_zero()),
+                );
 
                 return Ok(Some((
                     Self {
@@ -177,6 +320,8 @@ impl TokenDataV2 {
                         token_standard: TokenStandard::V1.to_string(),
                         is_fungible_v2: None,
                         transaction_timestamp: txn_timestamp,
+                        description_mutated: false,
+                        supply_exceeds_maximum,
                     },
                     CurrentTokenDataV2 {
                         token_data_id,
@@ -205,4 +350,478 @@ impl TokenDataV2 {
         }
         Ok(None)
     }
+
+    /// Parses the token datas of both standards from the write set changes of a transaction
+    /// in a single pass: v1 from table items, v2 from resources, with the v2 metadata shared
+    /// across all changes. Results are in write set order; v2 token datas with missing
+    /// metadata are handled as in get_v2_from_write_resource.
+    pub fn process_token_changes(
+        changes: &[WriteSetChange],
+        txn_version: i64,
+        txn_timestamp: chrono::NaiveDateTime,
+        token_v2_metadata: &TokenV2AggregatedDataMapping,
+        mut pending_token_datas: Option<&mut Vec<PendingTokenDataV2>>,
+    ) -> anyhow::Result<Vec<(Self, CurrentTokenDataV2)>> {
+        let mut token_datas = vec![];
+        for (index, wsc) in changes.iter().enumerate() {
+            let wsc_index = index as i64;
+            let maybe_token_data = match wsc {
+                WriteSetChange::WriteTableItem(table_item) => Self::get_v1_from_write_table_item(
+                    table_item,
+                    txn_version,
+                    wsc_index,
+                    txn_timestamp,
+                )?,
+                WriteSetChange::WriteResource(resource) => Self::get_v2_from_write_resource(
+                    resource,
+                    txn_version,
+                    wsc_index,
+                    txn_timestamp,
+                    token_v2_metadata,
+                    pending_token_datas.as_deref_mut(),
+                )?,
+                _ => None,
+            };
+            token_datas.extend(maybe_token_data);
+        }
+        Ok(token_datas)
+    }
+}
+
+/// Returns whether the supply is above the maximum (None meaning unlimited), which should be
+/// impossible, logging and counting such anomalies so they don't go unnoticed.
+fn check_supply_exceeds_maximum(
+    txn_version: i64,
+    token_data_id: &str,
+    supply: &BigDecimal,
+    maximum: Option<&BigDecimal>,
+) -> bool {
+    match maximum {
+        Some(maximum) if supply > maximum => {
+            TOKEN_SUPPLY_EXCEEDS_MAXIMUM.inc();
+            aptos_logger::warn!(
+                transaction_version = txn_version,
+                token_data_id = token_data_id,
+                supply = supply.to_string(),
+                maximum = maximum.to_string(),
+                "Token supply exceeds maximum"
+            );
+            true
+        },
+        _ => false,
+    }
+}
+
+/// Classifies a v2 token from its aggregated object data, returning
+/// (is_fungible_v2, maximum, supply). A token is fungible if its object is also the metadata
+/// of a fungible asset (0x1::fungible_asset::Metadata), in which case maximum and supply are
+/// taken from the supply of the asset (no maximum for an unlimited supply), otherwise it is
+/// an NFT with no maximum and zero supply.
+pub fn classify_token_standard(
+    metadata: &TokenV2AggregatedData,
+) -> (Option<bool>, Option<BigDecimal>, BigDecimal) {
+    if metadata.fungible_asset_metadata.is_none() {
+        return (Some(false), None, BigDecimal::zero());
+    }
+    match metadata.fungible_asset_supply.as_ref() {
+        Some(supply) => (Some(true), supply.get_maximum(), supply.current.clone()),
+        None => (Some(true), None, BigDecimal::zero()),
+    }
+}
+
+impl CurrentTokenDataV2Query {
+    /// Returns the current token datas from the db of the tokens that exist, by token data id.
+    pub fn get_by_token_data_ids(
+        conn: &mut PgPoolConnection,
+        token_data_ids: &[String],
+    ) -> diesel::QueryResult<HashMap<CurrentTokenDataV2PK, CurrentTokenDataV2>> {
+        if token_data_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        current_token_datas_v2::table
+            .filter(current_token_datas_v2::token_data_id.eq_any(token_data_ids))
+            .load::<Self>(conn)
+            .map(|token_datas| {
+                token_datas
+                    .into_iter()
+                    .map(|token_data| (token_data.token_data_id.clone(), token_data.into()))
+                    .collect()
+            })
+    }
+}
+
+impl From<CurrentTokenDataV2Query> for CurrentTokenDataV2 {
+    fn from(query: CurrentTokenDataV2Query) -> Self {
+        Self {
+            token_data_id: query.token_data_id,
+            collection_id: query.collection_id,
+            token_name: query.token_name,
+            maximum: query.maximum,
+            supply: query.supply,
+            largest_property_version_v1: query.largest_property_version_v1,
+            token_uri: query.token_uri,
+            token_properties: query.token_properties,
+            description: query.description,
+            token_standard: query.token_standard,
+            is_fungible_v2: query.is_fungible_v2,
+            last_transaction_version: query.last_transaction_version,
+            last_transaction_timestamp: query.last_transaction_timestamp,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_models::v2_token_utils::FungibleAssetSupply;
+
+    fn current_token_data(txn_version: i64, token_name: &str) -> CurrentTokenDataV2 {
+        CurrentTokenDataV2 {
+            token_data_id: "0x1".to_string(),
+            collection_id: "0x2".to_string(),
+            token_name: token_name.to_string(),
+            maximum: None,
+            supply: BigDecimal::zero(),
+            largest_property_version_v1: None,
+            token_uri: "uri".to_string(),
+            token_properties: serde_json::Value::Null,
+            description: "description".to_string(),
+            token_standard: TokenStandard::V2.to_string(),
+            is_fungible_v2: Some(false),
+            last_transaction_version: txn_version,
+            last_transaction_timestamp: chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    fn token_v2_aggregated_data(
+        fungible_asset_supply: Option<FungibleAssetSupply>,
+    ) -> TokenV2AggregatedData {
+        TokenV2AggregatedData::new_for_test("0x789", fungible_asset_supply, false)
+    }
+
+    fn fungible_asset_supply(current: u64, maximum: Option<u64>) -> FungibleAssetSupply {
+        // Move options are serialized as vectors of zero or one element
+        let maximum = maximum.map(|maximum| maximum.to_string());
+        let write_resource: APIWriteResource = serde_json::from_value(serde_json::json!({
+            "address": "0x1",
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x1::fungible_asset::Supply",
+                "data": {
+                    "current": current.to_string(),
+                    "maximum": { "vec": maximum.into_iter().collect::<Vec<_>>() },
+                },
+            },
+        }))
+        .unwrap();
+        FungibleAssetSupply::from_write_resource(&write_resource, 1)
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_classify_token_standard_nft() {
+        assert_eq!(
+            classify_token_standard(&token_v2_aggregated_data(None)),
+            (Some(false), None, BigDecimal::zero())
+        );
+    }
+
+    #[test]
+    fn test_classify_token_standard_fixed_supply_fungible() {
+        assert_eq!(
+            classify_token_standard(&token_v2_aggregated_data(Some(fungible_asset_supply(
+                10,
+                Some(100)
+            )))),
+            (
+                Some(true),
+                Some(BigDecimal::from(100)),
+                BigDecimal::from(10)
+            )
+        );
+    }
+
+    #[test]
+    fn test_classify_token_standard_unlimited_supply_fungible() {
+        assert_eq!(
+            classify_token_standard(&token_v2_aggregated_data(Some(fungible_asset_supply(
+                10, None
+            )))),
+            (Some(true), None, BigDecimal::from(10))
+        );
+    }
+
+    #[test]
+    fn test_upsert_into_does_not_regress() {
+        let mut current_token_datas = HashMap::new();
+        current_token_data(100, "new").upsert_into(&mut current_token_datas);
+        current_token_data(50, "old").upsert_into(&mut current_token_datas);
+
+        let current = current_token_datas.get("0x1").unwrap();
+        assert_eq!(current.last_transaction_version, 100);
+        assert_eq!(current.token_name, "new");
+
+        // Reprocessing the same version with different content overwrites the row.
+        current_token_data(100, "reprocessed").upsert_into(&mut current_token_datas);
+        assert_eq!(
+            current_token_datas.get("0x1").unwrap().token_name,
+            "reprocessed"
+        );
+    }
+
+    #[test]
+    fn test_upsert_into_skips_unchanged_content() {
+        let mut current_token_datas = HashMap::new();
+        assert!(current_token_data(100, "same").upsert_into(&mut current_token_datas));
+        // Only the version moved, so no new current row is emitted.
+        assert!(!current_token_data(101, "same").upsert_into(&mut current_token_datas));
+        assert_eq!(
+            current_token_datas
+                .get("0x1")
+                .unwrap()
+                .last_transaction_version,
+            100
+        );
+
+        assert!(current_token_data(102, "changed").upsert_into(&mut current_token_datas));
+        assert_eq!(
+            current_token_datas
+                .get("0x1")
+                .unwrap()
+                .last_transaction_version,
+            102
+        );
+    }
+
+    #[test]
+    fn test_description_mutated() {
+        let token_data = |txn_version: i64, description: &str| {
+            let write_resource: APIWriteResource = serde_json::from_value(serde_json::json!({
+                "address": "0x1",
+                "state_key_hash": "0x0",
+                "data": {
+                    "type": "0x4::token::Token",
+                    "data": {
+                        "collection": { "inner": "0x2" },
+                        "description": description,
+                        "name": "name",
+                        "uri": "uri",
+                    },
+                },
+            }))
+            .unwrap();
+            let token_v2_metadata =
+                HashMap::from([(standardize_address("0x1"), token_v2_aggregated_data(None))]);
+            TokenDataV2::get_v2_from_write_resource(
+                &write_resource,
+                txn_version,
+                0,
+                chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+                &token_v2_metadata,
+                None,
+            )
+            .unwrap()
+            .unwrap()
+            .0
+        };
+        // The prior row of the batch has description "description".
+        let prior = current_token_data(100, "name");
+
+        let mut unchanged = token_data(101, "description");
+        unchanged.detect_description_mutation(&prior);
+        assert!(!unchanged.description_mutated);
+
+        let mut changed = token_data(102, "new description");
+        changed.detect_description_mutation(&prior);
+        assert!(changed.description_mutated);
+
+        // A prior row that is not older is ignored.
+        let mut reprocessed = token_data(100, "new description");
+        reprocessed.detect_description_mutation(&prior);
+        assert!(!reprocessed.description_mutated);
+    }
+
+    #[test]
+    fn test_supply_exceeds_maximum() {
+        let write_resource: APIWriteResource = serde_json::from_value(serde_json::json!({
+            "address": "0x1",
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x4::token::Token",
+                "data": {
+                    "collection": { "inner": "0x2" },
+                    "description": "description",
+                    "name": "name",
+                    "uri": "uri",
+                },
+            },
+        }))
+        .unwrap();
+        let token_data = |current_supply: u64| {
+            let token_v2_metadata = HashMap::from([(
+                standardize_address("0x1"),
+                token_v2_aggregated_data(Some(fungible_asset_supply(current_supply, Some(100)))),
+            )]);
+            TokenDataV2::get_v2_from_write_resource(
+                &write_resource,
+                1,
+                0,
+                chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap(),
+                &token_v2_metadata,
+                None,
+            )
+            .unwrap()
+            .unwrap()
+            .0
+        };
+
+        assert!(!token_data(100).supply_exceeds_maximum);
+
+        // The anomaly is still stored, but flagged and counted (besides the warning).
+        let anomalies = TOKEN_SUPPLY_EXCEEDS_MAXIMUM.get();
+        let anomalous = token_data(101);
+        assert!(anomalous.supply_exceeds_maximum);
+        assert_eq!(anomalous.supply, BigDecimal::from(101));
+        assert!(TOKEN_SUPPLY_EXCEEDS_MAXIMUM.get() > anomalies);
+
+        // An unlimited (v1 zero) maximum is never exceeded.
+        assert!(!check_supply_exceeds_maximum(
+            1,
+            "0x1",
+            &BigDecimal::from(101),
+            None
+        ));
+    }
+
+    #[test]
+    fn test_missing_metadata_is_buffered() {
+        let write_resource: APIWriteResource = serde_json::from_value(serde_json::json!({
+            "address": "0x123",
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x4::token::Token",
+                "data": {
+                    "collection": { "inner": "0x456" },
+                    "description": "description",
+                    "name": "name",
+                    "uri": "uri",
+                },
+            },
+        }))
+        .unwrap();
+        let txn_timestamp = chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let mut token_v2_metadata = HashMap::new();
+
+        // Without buffering, the resource is dropped.
+        assert!(TokenDataV2::get_v2_from_write_resource(
+            &write_resource,
+            1,
+            0,
+            txn_timestamp,
+            &token_v2_metadata,
+            None
+        )
+        .unwrap()
+        .is_none());
+
+        let mut pending_token_datas = vec![];
+        assert!(TokenDataV2::get_v2_from_write_resource(
+            &write_resource,
+            1,
+            0,
+            txn_timestamp,
+            &token_v2_metadata,
+            Some(&mut pending_token_datas)
+        )
+        .unwrap()
+        .is_none());
+        assert_eq!(pending_token_datas.len(), 1);
+
+        // Once the ObjectCore is known, the buffered resource resolves.
+        token_v2_metadata.insert(standardize_address("0x123"), token_v2_aggregated_data(None));
+        let (token_data, current_token_data) = pending_token_datas[0]
+            .resolve(&token_v2_metadata)
+            .unwrap()
+            .unwrap();
+        assert_eq!(token_data.token_data_id, standardize_address("0x123"));
+        assert_eq!(current_token_data.token_name, "name");
+    }
+
+    #[test]
+    fn test_process_mixed_token_changes() {
+        let changes: Vec<WriteSetChange> = serde_json::from_value(serde_json::json!([
+            {
+                "type": "write_table_item",
+                "state_key_hash": "0x0",
+                "handle": "0x1",
+                "key": "0x",
+                "value": "0x",
+                "data": {
+                    "key_type": "0x3::token::TokenDataId",
+                    "key": { "creator": "0x1", "collection": "collection", "name": "v1" },
+                    "value_type": "0x3::token::TokenData",
+                    "value": {
+                        "default_properties": { "map": { "data": [] } },
+                        "description": "description",
+                        "largest_property_version": "0",
+                        "maximum": "10",
+                        "mutability_config": {
+                            "description": false,
+                            "maximum": false,
+                            "properties": false,
+                            "royalty": false,
+                            "uri": false,
+                        },
+                        "name": "v1",
+                        "royalty": {
+                            "payee_address": "0x1",
+                            "royalty_points_denominator": "0",
+                            "royalty_points_numerator": "0",
+                        },
+                        "supply": "1",
+                        "uri": "uri",
+                    },
+                },
+            },
+            {
+                "type": "write_resource",
+                "address": "0x123",
+                "state_key_hash": "0x0",
+                "data": {
+                    "type": "0x4::token::Token",
+                    "data": {
+                        "collection": { "inner": "0x456" },
+                        "description": "description",
+                        "name": "v2",
+                        "uri": "uri",
+                    },
+                },
+            },
+        ]))
+        .unwrap();
+        let txn_timestamp = chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let mut token_v2_metadata = HashMap::new();
+        token_v2_metadata.insert(standardize_address("0x123"), token_v2_aggregated_data(None));
+
+        let token_datas = TokenDataV2::process_token_changes(
+            &changes,
+            1,
+            txn_timestamp,
+            &token_v2_metadata,
+            None,
+        )
+        .unwrap();
+        assert_eq!(token_datas.len(), 2);
+        let (v1_token_data, _) = &token_datas[0];
+        assert_eq!(v1_token_data.write_set_change_index, 0);
+        assert_eq!(v1_token_data.token_name, "v1");
+        assert_eq!(v1_token_data.token_standard, TokenStandard::V1.to_string());
+        assert_eq!(v1_token_data.maximum, Some(BigDecimal::from(10)));
+        let (v2_token_data, v2_current_token_data) = &token_datas[1];
+        assert_eq!(v2_token_data.write_set_change_index, 1);
+        assert_eq!(v2_token_data.token_data_id, standardize_address("0x123"));
+        assert_eq!(v2_token_data.token_standard, TokenStandard::V2.to_string());
+        assert_eq!(v2_current_token_data.token_name, "v2");
+    }
 }
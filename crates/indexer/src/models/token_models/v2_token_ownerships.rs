@@ -10,7 +10,9 @@ use super::{
     token_utils::TokenWriteSet,
     tokens::TableHandleToOwner,
     v2_token_datas::TokenDataV2,
-    v2_token_utils::{ObjectCore, TokenStandard, TokenV2AggregatedDataMapping, TokenV2Burned},
+    v2_token_utils::{
+        FungibleStore, ObjectCore, TokenStandard, TokenV2AggregatedDataMapping, TokenV2Burned,
+    },
 };
 use crate::{
     database::PgPoolConnection,
@@ -191,6 +193,70 @@ impl TokenOwnershipV2 {
         }
     }
 
+    /// For fungible tokens, ownership is the balance of a fungible store, owned by the owner of
+    /// the store object. The store object's ObjectCore is taken from the same metadata mapping
+    /// as token datas, and the store is skipped if it's missing. The store is also skipped if
+    /// its metadata object is in the mapping but isn't a token (i.e. a plain fungible asset).
+    /// If the metadata object isn't in the mapping, the caller must check that it's a token.
+    pub fn get_ft_v2_from_write_resource(
+        write_resource: &WriteResource,
+        txn_version: i64,
+        write_set_change_index: i64,
+        txn_timestamp: chrono::NaiveDateTime,
+        token_v2_metadata: &TokenV2AggregatedDataMapping,
+    ) -> anyhow::Result<Option<(Self, CurrentTokenOwnershipV2)>> {
+        let fungible_store = match FungibleStore::from_write_resource(write_resource, txn_version)?
+        {
+            Some(fungible_store) => fungible_store,
+            None => return Ok(None),
+        };
+        let storage_id = standardize_address(&write_resource.address.to_string());
+        let object_core = match token_v2_metadata.get(&storage_id) {
+            Some(metadata) => &metadata.object,
+            None => return Ok(None),
+        };
+        let token_data_id = fungible_store.get_metadata_address();
+        if let Some(metadata) = token_v2_metadata.get(&token_data_id) {
+            if metadata.token.is_none() {
+                return Ok(None);
+            }
+        }
+        let owner_address = object_core.get_owner_address();
+        let is_soulbound = !object_core.allow_ungated_transfer;
+
+        Ok(Some((
+            Self {
+                transaction_version: txn_version,
+                write_set_change_index,
+                token_data_id: token_data_id.clone(),
+                property_version_v1: BigDecimal::zero(),
+                owner_address: Some(owner_address.clone()),
+                storage_id: storage_id.clone(),
+                amount: fungible_store.balance.clone(),
+                table_type_v1: None,
+                token_properties_mutated_v1: None,
+                is_soulbound_v2: Some(is_soulbound),
+                token_standard: TokenStandard::V2.to_string(),
+                is_fungible_v2: Some(true),
+                transaction_timestamp: txn_timestamp,
+            },
+            CurrentTokenOwnershipV2 {
+                token_data_id,
+                property_version_v1: BigDecimal::zero(),
+                owner_address,
+                storage_id,
+                amount: fungible_store.balance,
+                table_type_v1: None,
+                token_properties_mutated_v1: None,
+                is_soulbound_v2: Some(is_soulbound),
+                token_standard: TokenStandard::V2.to_string(),
+                is_fungible_v2: Some(true),
+                last_transaction_version: txn_version,
+                last_transaction_timestamp: txn_timestamp,
+            },
+        )))
+    }
+
     /// This handles the case where token is burned but objectCore is still there
     pub fn get_burned_nft_v2_from_write_resource(
         write_resource: &WriteResource,
@@ -514,3 +580,85 @@ impl CurrentTokenOwnershipV2Query {
             .first::<Self>(conn)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::token_models::v2_token_utils::TokenV2AggregatedData;
+
+    fn token_v2_aggregated_data(owner: &str) -> TokenV2AggregatedData {
+        TokenV2AggregatedData::new_for_test(owner, None, true)
+    }
+
+    #[test]
+    fn test_ft_ownership_from_store_owner() {
+        let write_resource: WriteResource = serde_json::from_value(serde_json::json!({
+            "address": "0x456",
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x1::fungible_asset::FungibleStore",
+                "data": {
+                    "metadata": { "inner": "0x123" },
+                    "balance": "5",
+                    "frozen": false,
+                },
+            },
+        }))
+        .unwrap();
+        let txn_timestamp = chrono::NaiveDateTime::from_timestamp_opt(0, 0).unwrap();
+        let mut token_v2_metadata = HashMap::from([(
+            standardize_address("0x456"),
+            token_v2_aggregated_data("0x789"),
+        )]);
+
+        let (ownership, current_ownership) = TokenOwnershipV2::get_ft_v2_from_write_resource(
+            &write_resource,
+            10,
+            2,
+            txn_timestamp,
+            &token_v2_metadata,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(ownership.owner_address, Some(standardize_address("0x789")));
+        assert_eq!(ownership.amount, BigDecimal::from(5));
+        assert_eq!(ownership.is_fungible_v2, Some(true));
+        assert_eq!(
+            current_ownership.token_data_id,
+            standardize_address("0x123")
+        );
+        assert_eq!(
+            current_ownership.owner_address,
+            standardize_address("0x789")
+        );
+        assert_eq!(current_ownership.storage_id, standardize_address("0x456"));
+        assert_eq!(current_ownership.amount, BigDecimal::from(5));
+
+        // The metadata object has an ObjectCore but no token resource: not a token.
+        token_v2_metadata.insert(
+            standardize_address("0x123"),
+            token_v2_aggregated_data("0xabc"),
+        );
+        assert!(TokenOwnershipV2::get_ft_v2_from_write_resource(
+            &write_resource,
+            10,
+            2,
+            txn_timestamp,
+            &token_v2_metadata,
+        )
+        .unwrap()
+        .is_none());
+
+        // The store object's ObjectCore is unknown, so is its owner.
+        token_v2_metadata.clear();
+        assert!(TokenOwnershipV2::get_ft_v2_from_write_resource(
+            &write_resource,
+            10,
+            2,
+            txn_timestamp,
+            &token_v2_metadata,
+        )
+        .unwrap()
+        .is_none());
+    }
+}
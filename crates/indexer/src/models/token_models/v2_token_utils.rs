@@ -13,11 +13,12 @@ use crate::{
 };
 use anyhow::{Context, Result};
 use aptos_api_types::{deserialize_from_string, Event, WriteResource};
-use bigdecimal::BigDecimal;
+use bigdecimal::{BigDecimal, Zero};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     fmt::{self, Formatter},
+    str::FromStr,
 };
 
 /// Tracks all token related data in a hashmap for quick access (keyed on address of the object core)
@@ -30,7 +31,10 @@ pub type EventIndex = i64;
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TokenV2AggregatedData {
     pub aptos_collection: Option<AptosCollection>,
+    pub concurrent_supply: Option<ConcurrentSupply>,
     pub fixed_supply: Option<FixedSupply>,
+    pub fungible_asset_metadata: Option<FungibleAssetMetadata>,
+    pub fungible_asset_supply: Option<FungibleAssetSupply>,
     pub object: ObjectCore,
     pub unlimited_supply: Option<UnlimitedSupply>,
     pub property_map: Option<PropertyMap>,
@@ -38,6 +42,41 @@ pub struct TokenV2AggregatedData {
     pub token: Option<TokenV2>,
 }
 
+#[cfg(test)]
+impl TokenV2AggregatedData {
+    /// The aggregated data of a token object with the given owner, which is a fungible token
+    /// if it has a fungible asset supply.
+    pub fn new_for_test(
+        owner: &str,
+        fungible_asset_supply: Option<FungibleAssetSupply>,
+        allow_ungated_transfer: bool,
+    ) -> Self {
+        Self {
+            aptos_collection: None,
+            concurrent_supply: None,
+            fixed_supply: None,
+            fungible_asset_metadata: fungible_asset_supply.as_ref().map(|_| {
+                FungibleAssetMetadata {
+                    name: "name".to_string(),
+                    symbol: "SYM".to_string(),
+                    decimals: 8,
+                }
+            }),
+            fungible_asset_supply,
+            object: serde_json::from_value(serde_json::json!({
+                "allow_ungated_transfer": allow_ungated_transfer,
+                "guid_creation_num": "1",
+                "owner": owner,
+            }))
+            .unwrap(),
+            unlimited_supply: None,
+            property_map: None,
+            transfer_event: None,
+            token: None,
+        }
+    }
+}
+
 /// Tracks which token standard a token / collection is built upon
 #[derive(Serialize)]
 pub enum TokenStandard {
@@ -290,6 +329,212 @@ impl UnlimitedSupply {
     }
 }
 
+/// Supply tracked with aggregators (v2), which can be updated concurrently. The aggregators
+/// are kept as raw json, as their values may not always be readable.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConcurrentSupply {
+    pub current_supply: serde_json::Value,
+    pub total_minted: serde_json::Value,
+}
+
+impl ConcurrentSupply {
+    pub fn from_write_resource(
+        write_resource: &WriteResource,
+        txn_version: i64,
+    ) -> anyhow::Result<Option<Self>> {
+        let type_str = format!(
+            "{}::{}::{}",
+            write_resource.data.typ.address,
+            write_resource.data.typ.module,
+            write_resource.data.typ.name
+        );
+        if !V2TokenResource::is_resource_supported(type_str.as_str()) {
+            return Ok(None);
+        }
+        let resource = MoveResource::from_write_resource(
+            write_resource,
+            0, // Placeholder, this isn't used anyway
+            txn_version,
+            0, // Placeholder, this isn't used anyway
+        );
+
+        if let V2TokenResource::ConcurrentSupply(inner) =
+            V2TokenResource::from_resource(&type_str, resource.data.as_ref().unwrap(), txn_version)?
+        {
+            Ok(Some(inner))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Current supply, or zero (with a warning) if the aggregator can't be read.
+    pub fn get_current_supply(&self) -> BigDecimal {
+        Self::get_aggregator_value(&self.current_supply, "value").unwrap_or_else(|| {
+            aptos_logger::warn!(
+                current_supply = self.current_supply.to_string(),
+                "Unable to read concurrent supply, defaulting to 0"
+            );
+            BigDecimal::zero()
+        })
+    }
+
+    /// Maximum supply, i.e. the limit of the current supply aggregator, if readable.
+    pub fn get_max_supply(&self) -> Option<BigDecimal> {
+        Self::get_aggregator_value(&self.current_supply, "max_value")
+    }
+
+    pub fn get_total_minted(&self) -> Option<BigDecimal> {
+        Self::get_aggregator_value(&self.total_minted, "value")
+    }
+
+    /// Aggregators are serialized as {"value": "1", "max_value": "10"}, with u64 fields
+    /// serialized as strings.
+    fn get_aggregator_value(aggregator: &serde_json::Value, field: &str) -> Option<BigDecimal> {
+        aggregator
+            .get(field)
+            .and_then(|value| value.as_str())
+            .and_then(|value| BigDecimal::from_str(value).ok())
+    }
+}
+
+/// Balance of a fungible asset (e.g. a fungible token v2) held in a store object, whose
+/// owner is the owner of the store's ObjectCore.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FungibleStore {
+    metadata: ResourceReference,
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub balance: BigDecimal,
+    pub frozen: bool,
+}
+
+impl FungibleStore {
+    /// Address of the fungible asset metadata object, i.e. the token for fungible tokens.
+    pub fn get_metadata_address(&self) -> String {
+        standardize_address(&self.metadata.inner)
+    }
+
+    pub fn from_write_resource(
+        write_resource: &WriteResource,
+        txn_version: i64,
+    ) -> anyhow::Result<Option<Self>> {
+        let type_str = format!(
+            "{}::{}::{}",
+            write_resource.data.typ.address,
+            write_resource.data.typ.module,
+            write_resource.data.typ.name
+        );
+        if !V2TokenResource::is_resource_supported(type_str.as_str()) {
+            return Ok(None);
+        }
+        let resource = MoveResource::from_write_resource(
+            write_resource,
+            0, // Placeholder, this isn't used anyway
+            txn_version,
+            0, // Placeholder, this isn't used anyway
+        );
+
+        if let V2TokenResource::FungibleStore(inner) =
+            V2TokenResource::from_resource(&type_str, resource.data.as_ref().unwrap(), txn_version)?
+        {
+            Ok(Some(inner))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Metadata of a fungible asset, held by the metadata object of the asset, i.e. the token
+/// object for fungible tokens v2.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FungibleAssetMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: i32,
+}
+
+impl FungibleAssetMetadata {
+    pub fn from_write_resource(
+        write_resource: &WriteResource,
+        txn_version: i64,
+    ) -> anyhow::Result<Option<Self>> {
+        let type_str = format!(
+            "{}::{}::{}",
+            write_resource.data.typ.address,
+            write_resource.data.typ.module,
+            write_resource.data.typ.name
+        );
+        if !V2TokenResource::is_resource_supported(type_str.as_str()) {
+            return Ok(None);
+        }
+        let resource = MoveResource::from_write_resource(
+            write_resource,
+            0, // Placeholder, this isn't used anyway
+            txn_version,
+            0, // Placeholder, this isn't used anyway
+        );
+
+        if let V2TokenResource::FungibleAssetMetadata(inner) =
+            V2TokenResource::from_resource(&type_str, resource.data.as_ref().unwrap(), txn_version)?
+        {
+            Ok(Some(inner))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Supply of a fungible asset, held next to its metadata. The maximum is unset for an
+/// unlimited supply.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FungibleAssetSupply {
+    #[serde(deserialize_with = "deserialize_from_string")]
+    pub current: BigDecimal,
+    maximum: OptionalBigDecimal,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct OptionalBigDecimal {
+    vec: Vec<BigDecimalWrapper>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct BigDecimalWrapper(#[serde(deserialize_with = "deserialize_from_string")] BigDecimal);
+
+impl FungibleAssetSupply {
+    pub fn get_maximum(&self) -> Option<BigDecimal> {
+        self.maximum.vec.first().map(|maximum| maximum.0.clone())
+    }
+
+    pub fn from_write_resource(
+        write_resource: &WriteResource,
+        txn_version: i64,
+    ) -> anyhow::Result<Option<Self>> {
+        let type_str = format!(
+            "{}::{}::{}",
+            write_resource.data.typ.address,
+            write_resource.data.typ.module,
+            write_resource.data.typ.name
+        );
+        if !V2TokenResource::is_resource_supported(type_str.as_str()) {
+            return Ok(None);
+        }
+        let resource = MoveResource::from_write_resource(
+            write_resource,
+            0, // Placeholder, this isn't used anyway
+            txn_version,
+            0, // Placeholder, this isn't used anyway
+        );
+
+        if let V2TokenResource::FungibleAssetSupply(inner) =
+            V2TokenResource::from_resource(&type_str, resource.data.as_ref().unwrap(), txn_version)?
+        {
+            Ok(Some(inner))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct MintEvent {
     #[serde(deserialize_with = "deserialize_from_string")]
@@ -407,7 +652,11 @@ impl PropertyMap {
 pub enum V2TokenResource {
     AptosCollection(AptosCollection),
     Collection(Collection),
+    ConcurrentSupply(ConcurrentSupply),
     FixedSupply(FixedSupply),
+    FungibleAssetMetadata(FungibleAssetMetadata),
+    FungibleAssetSupply(FungibleAssetSupply),
+    FungibleStore(FungibleStore),
     ObjectCore(ObjectCore),
     UnlimitedSupply(UnlimitedSupply),
     TokenV2(TokenV2),
@@ -419,7 +668,11 @@ impl V2TokenResource {
         matches!(
             data_type,
             "0x1::object::ObjectCore"
+                | "0x1::fungible_asset::FungibleStore"
+                | "0x1::fungible_asset::Metadata"
+                | "0x1::fungible_asset::Supply"
                 | "0x4::collection::Collection"
+                | "0x4::collection::ConcurrentSupply"
                 | "0x4::collection::FixedSupply"
                 | "0x4::collection::UnlimitedSupply"
                 | "0x4::aptos_token::AptosCollection"
@@ -437,9 +690,18 @@ impl V2TokenResource {
             "0x1::object::ObjectCore" => {
                 serde_json::from_value(data.clone()).map(|inner| Some(Self::ObjectCore(inner)))
             },
+            "0x1::fungible_asset::FungibleStore" => {
+                serde_json::from_value(data.clone()).map(|inner| Some(Self::FungibleStore(inner)))
+            },
+            "0x1::fungible_asset::Metadata" => serde_json::from_value(data.clone())
+                .map(|inner| Some(Self::FungibleAssetMetadata(inner))),
+            "0x1::fungible_asset::Supply" => serde_json::from_value(data.clone())
+                .map(|inner| Some(Self::FungibleAssetSupply(inner))),
             "0x4::collection::Collection" => {
                 serde_json::from_value(data.clone()).map(|inner| Some(Self::Collection(inner)))
             },
+            "0x4::collection::ConcurrentSupply" => serde_json::from_value(data.clone())
+                .map(|inner| Some(Self::ConcurrentSupply(inner))),
             "0x4::collection::FixedSupply" => {
                 serde_json::from_value(data.clone()).map(|inner| Some(Self::FixedSupply(inner)))
             },
@@ -502,3 +764,44 @@ impl V2TokenEvent {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_concurrent_supply_aggregators() {
+        let write_resource: WriteResource = serde_json::from_value(serde_json::json!({
+            "address": "0x123",
+            "state_key_hash": "0x0",
+            "data": {
+                "type": "0x4::collection::ConcurrentSupply",
+                "data": {
+                    "current_supply": { "max_value": "100", "value": "10" },
+                    "total_minted": { "max_value": "18446744073709551615", "value": "12" },
+                },
+            },
+        }))
+        .unwrap();
+        let concurrent_supply = ConcurrentSupply::from_write_resource(&write_resource, 1)
+            .unwrap()
+            .unwrap();
+        assert_eq!(concurrent_supply.get_current_supply(), BigDecimal::from(10));
+        assert_eq!(
+            concurrent_supply.get_max_supply(),
+            Some(BigDecimal::from(100))
+        );
+        assert_eq!(
+            concurrent_supply.get_total_minted(),
+            Some(BigDecimal::from(12))
+        );
+
+        // An unreadable aggregator falls back to a zero supply.
+        let unreadable = ConcurrentSupply {
+            current_supply: serde_json::json!("0xdeadbeef"),
+            total_minted: serde_json::Value::Null,
+        };
+        assert_eq!(unreadable.get_current_supply(), BigDecimal::zero());
+        assert_eq!(unreadable.get_max_supply(), None);
+    }
+}
@@ -13,11 +13,14 @@ use crate::{
         coin_models::coin_activities::MAX_ENTRY_FUNCTION_LENGTH,
         token_models::{
             ans_lookup::{CurrentAnsLookup, CurrentAnsLookupPK},
+            collection_cap_events::CollectionCapEvent,
             collection_datas::{CollectionData, CurrentCollectionData},
             nft_points::NftPoints,
+            processor_token_standard_stats::ProcessorTokenStandardStat,
             token_activities::TokenActivity,
             token_claims::CurrentTokenPendingClaim,
             token_datas::{CurrentTokenData, TokenData},
+            token_mutation_events::TokenMutationEvent,
             token_ownerships::{CurrentTokenOwnership, TokenOwnership},
             tokens::{
                 CurrentTokenOwnershipPK, CurrentTokenPendingClaimPK, TableHandleToOwner,
@@ -25,13 +28,17 @@ use crate::{
             },
             v2_collections::{CollectionV2, CurrentCollectionV2, CurrentCollectionV2PK},
             v2_token_activities::TokenActivityV2,
-            v2_token_datas::{CurrentTokenDataV2, CurrentTokenDataV2PK, TokenDataV2},
+            v2_token_datas::{
+                CurrentTokenDataV2, CurrentTokenDataV2PK, CurrentTokenDataV2Query,
+                PendingTokenDataV2, TokenDataV2,
+            },
             v2_token_ownerships::{
                 CurrentTokenOwnershipV2, CurrentTokenOwnershipV2PK, NFTOwnershipV2,
                 TokenOwnershipV2,
             },
             v2_token_utils::{
-                AptosCollection, BurnEvent, FixedSupply, ObjectCore, PropertyMap, TokenV2,
+                AptosCollection, BurnEvent, ConcurrentSupply, FixedSupply, FungibleAssetMetadata,
+                FungibleAssetSupply, FungibleStore, ObjectCore, PropertyMap, TokenV2,
                 TokenV2AggregatedData, TokenV2AggregatedDataMapping, TokenV2Burned, TransferEvent,
                 UnlimitedSupply,
             },
@@ -54,6 +61,7 @@ pub struct TokenTransactionProcessor {
     connection_pool: PgDbPool,
     ans_contract_address: Option<String>,
     nft_points_contract: Option<String>,
+    buffer_missing_token_v2_metadata: bool,
 }
 
 impl TokenTransactionProcessor {
@@ -61,6 +69,7 @@ impl TokenTransactionProcessor {
         connection_pool: PgDbPool,
         ans_contract_address: Option<String>,
         nft_points_contract: Option<String>,
+        buffer_missing_token_v2_metadata: bool,
     ) -> Self {
         aptos_logger::info!(
             ans_contract_address = ans_contract_address,
@@ -70,6 +79,7 @@ impl TokenTransactionProcessor {
             connection_pool,
             ans_contract_address,
             nft_points_contract,
+            buffer_missing_token_v2_metadata,
         }
     }
 }
@@ -105,6 +115,9 @@ fn insert_to_db_impl(
         current_token_datas_v2,
         current_token_ownerships_v2,
         token_activities_v2,
+        token_mutation_events,
+        token_standard_stats,
+        collection_cap_events,
     ): (
         &[CollectionV2],
         &[TokenDataV2],
@@ -113,6 +126,9 @@ fn insert_to_db_impl(
         &[CurrentTokenDataV2],
         &[CurrentTokenOwnershipV2],
         &[TokenActivityV2],
+        &[TokenMutationEvent],
+        &[ProcessorTokenStandardStat],
+        &[CollectionCapEvent],
     ),
 ) -> Result<(), diesel::result::Error> {
     let (tokens, token_ownerships, token_datas, collection_datas) = basic_token_transaction_lists;
@@ -136,6 +152,9 @@ fn insert_to_db_impl(
     insert_current_token_datas_v2(conn, current_token_datas_v2)?;
     insert_current_token_ownerships_v2(conn, current_token_ownerships_v2)?;
     insert_token_activities_v2(conn, token_activities_v2)?;
+    insert_token_mutation_events(conn, token_mutation_events)?;
+    insert_token_standard_stats(conn, token_standard_stats)?;
+    insert_collection_cap_events(conn, collection_cap_events)?;
     Ok(())
 }
 
@@ -167,6 +186,9 @@ fn insert_to_db(
         current_token_datas_v2,
         current_token_ownerships_v2,
         token_activities_v2,
+        token_mutation_events,
+        token_standard_stats,
+        collection_cap_events,
     ): (
         Vec<CollectionV2>,
         Vec<TokenDataV2>,
@@ -175,6 +197,9 @@ fn insert_to_db(
         Vec<CurrentTokenDataV2>,
         Vec<CurrentTokenOwnershipV2>,
         Vec<TokenActivityV2>,
+        Vec<TokenMutationEvent>,
+        Vec<ProcessorTokenStandardStat>,
+        Vec<CollectionCapEvent>,
     ),
 ) -> Result<(), diesel::result::Error> {
     aptos_logger::trace!(
@@ -210,6 +235,9 @@ fn insert_to_db(
                     &current_token_datas_v2,
                     &current_token_ownerships_v2,
                     &token_activities_v2,
+                    &token_mutation_events,
+                    &token_standard_stats,
+                    &collection_cap_events,
                 ),
             )
         }) {
@@ -237,6 +265,9 @@ fn insert_to_db(
                 let current_token_ownerships_v2 =
                     clean_data_for_db(current_token_ownerships_v2, true);
                 let token_activities_v2 = clean_data_for_db(token_activities_v2, true);
+                let token_mutation_events = clean_data_for_db(token_mutation_events, true);
+                let token_standard_stats = clean_data_for_db(token_standard_stats, true);
+                let collection_cap_events = clean_data_for_db(collection_cap_events, true);
 
                 insert_to_db_impl(
                     pg_conn,
@@ -258,6 +289,9 @@ fn insert_to_db(
                         &current_token_datas_v2,
                         &current_token_ownerships_v2,
                         &token_activities_v2,
+                        &token_mutation_events,
+                        &token_standard_stats,
+                        &collection_cap_events,
                     ),
                 )
             }),
@@ -786,6 +820,76 @@ fn insert_token_activities_v2(
     Ok(())
 }
 
+fn insert_token_mutation_events(
+    conn: &mut PgConnection,
+    items_to_insert: &[TokenMutationEvent],
+) -> Result<(), diesel::result::Error> {
+    use schema::token_mutation_events::dsl::*;
+
+    let chunks = get_chunks(items_to_insert.len(), TokenMutationEvent::field_count());
+
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::token_mutation_events::table)
+                .values(&items_to_insert[start_ind..end_ind])
+                .on_conflict((
+                    transaction_version,
+                    write_set_change_index,
+                    mutated_field_name,
+                ))
+                .do_nothing(),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_token_standard_stats(
+    conn: &mut PgConnection,
+    items_to_insert: &[ProcessorTokenStandardStat],
+) -> Result<(), diesel::result::Error> {
+    use schema::processor_token_standard_stats::dsl::*;
+
+    let chunks = get_chunks(
+        items_to_insert.len(),
+        ProcessorTokenStandardStat::field_count(),
+    );
+
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::processor_token_standard_stats::table)
+                .values(&items_to_insert[start_ind..end_ind])
+                .on_conflict((start_version, end_version, time_bucket, token_standard))
+                .do_nothing(),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
+fn insert_collection_cap_events(
+    conn: &mut PgConnection,
+    items_to_insert: &[CollectionCapEvent],
+) -> Result<(), diesel::result::Error> {
+    use schema::collection_cap_events::dsl::*;
+
+    let chunks = get_chunks(items_to_insert.len(), CollectionCapEvent::field_count());
+
+    for (start_ind, end_ind) in chunks {
+        execute_with_better_error(
+            conn,
+            diesel::insert_into(schema::collection_cap_events::table)
+                .values(&items_to_insert[start_ind..end_ind])
+                .on_conflict(collection_id)
+                .do_nothing(),
+            None,
+        )?;
+    }
+    Ok(())
+}
+
 #[async_trait]
 impl TransactionProcessor for TokenTransactionProcessor {
     fn name(&self) -> &'static str {
@@ -925,7 +1029,30 @@ impl TransactionProcessor for TokenTransactionProcessor {
             current_token_ownerships_v2,
             current_token_datas_v2,
             token_activities_v2,
-        ) = parse_v2_token(&transactions, &table_handle_to_owner, &mut conn);
+            token_mutation_events,
+        ) = match parse_v2_token(
+            &transactions,
+            &table_handle_to_owner,
+            &mut conn,
+            self.buffer_missing_token_v2_metadata,
+        ) {
+            Ok(token_v2_data) => token_v2_data,
+            Err(err) => {
+                return Err(TransactionProcessingError::TransactionCommitError((
+                    err,
+                    start_version,
+                    end_version,
+                    self.name(),
+                )))
+            },
+        };
+        let token_standard_stats = ProcessorTokenStandardStat::from_token_datas(
+            &token_datas_v2,
+            start_version,
+            end_version,
+        );
+        let collection_cap_events =
+            CollectionCapEvent::from_collections(&collections_v2, &token_datas_v2);
 
         let tx_result = insert_to_db(
             &mut conn,
@@ -956,6 +1083,9 @@ impl TransactionProcessor for TokenTransactionProcessor {
                 current_token_ownerships_v2,
                 current_token_datas_v2,
                 token_activities_v2,
+                token_mutation_events,
+                token_standard_stats,
+                collection_cap_events,
             ),
         );
         match tx_result {
@@ -982,7 +1112,8 @@ fn parse_v2_token(
     transactions: &[Transaction],
     table_handle_to_owner: &TableHandleToOwner,
     conn: &mut PgPoolConnection,
-) -> (
+    buffer_missing_token_v2_metadata: bool,
+) -> anyhow::Result<(
     Vec<CollectionV2>,
     Vec<TokenDataV2>,
     Vec<TokenOwnershipV2>,
@@ -990,12 +1121,14 @@ fn parse_v2_token(
     Vec<CurrentTokenDataV2>,
     Vec<CurrentTokenOwnershipV2>,
     Vec<TokenActivityV2>,
-) {
+    Vec<TokenMutationEvent>,
+)> {
     // Token V2 and V1 combined
     let mut collections_v2 = vec![];
     let mut token_datas_v2 = vec![];
     let mut token_ownerships_v2 = vec![];
     let mut token_activities_v2 = vec![];
+    let mut token_mutation_events = vec![];
     let mut current_collections_v2: HashMap<CurrentCollectionV2PK, CurrentCollectionV2> =
         HashMap::new();
     let mut current_token_datas_v2: HashMap<CurrentTokenDataV2PK, CurrentTokenDataV2> =
@@ -1010,6 +1143,12 @@ fn parse_v2_token(
     // We want to persist this through the entire batch so that even if a token is burned,
     // we can still get the object core metadata for it
     let mut token_v2_metadata: TokenV2AggregatedDataMapping = HashMap::new();
+    // Token v2 resources whose ObjectCore wasn't known yet, to retry at the end of the batch
+    let mut pending_token_datas_v2: Vec<PendingTokenDataV2> = vec![];
+    // Current token datas in the db of the tokens written in the batch (and of the metadata of
+    // fungible stores written in the batch), fetched all at once to detect mutations of tokens
+    // and fungible token ownerships of tokens last written before the batch
+    let prior_token_datas_v2 = get_prior_token_datas_v2(transactions, conn)?;
 
     // Code above is inefficient (multiple passthroughs) so I'm approaching TokenV2 with a cleaner code structure
     for txn in transactions {
@@ -1036,7 +1175,10 @@ fn parse_v2_token(
                             standardize_address(&wr.address.to_string()),
                             TokenV2AggregatedData {
                                 aptos_collection: None,
+                                concurrent_supply: None,
                                 fixed_supply: None,
+                                fungible_asset_metadata: None,
+                                fungible_asset_supply: None,
                                 object: object_core,
                                 unlimited_supply: None,
                                 property_map: None,
@@ -1063,6 +1205,21 @@ fn parse_v2_token(
                         {
                             aggregated_data.unlimited_supply = Some(unlimited_supply);
                         }
+                        if let Some(concurrent_supply) =
+                            ConcurrentSupply::from_write_resource(wr, txn_version).unwrap()
+                        {
+                            aggregated_data.concurrent_supply = Some(concurrent_supply);
+                        }
+                        if let Some(fungible_asset_metadata) =
+                            FungibleAssetMetadata::from_write_resource(wr, txn_version).unwrap()
+                        {
+                            aggregated_data.fungible_asset_metadata = Some(fungible_asset_metadata);
+                        }
+                        if let Some(fungible_asset_supply) =
+                            FungibleAssetSupply::from_write_resource(wr, txn_version).unwrap()
+                        {
+                            aggregated_data.fungible_asset_supply = Some(fungible_asset_supply);
+                        }
                         if let Some(aptos_collection) =
                             AptosCollection::from_write_resource(wr, txn_version).unwrap()
                         {
@@ -1150,7 +1307,7 @@ fn parse_v2_token(
                                 current_collection,
                             );
                         }
-                        if let Some((token_data, current_token_data)) =
+                        if let Some((mut token_data, current_token_data)) =
                             TokenDataV2::get_v1_from_write_table_item(
                                 table_item,
                                 txn_version,
@@ -1159,11 +1316,13 @@ fn parse_v2_token(
                             )
                             .unwrap()
                         {
+                            if let Some(prior) =
+                                current_token_datas_v2.get(&current_token_data.token_data_id)
+                            {
+                                token_data.detect_description_mutation(prior);
+                            }
                             token_datas_v2.push(token_data);
-                            current_token_datas_v2.insert(
-                                current_token_data.token_data_id.clone(),
-                                current_token_data,
-                            );
+                            current_token_data.upsert_into(&mut current_token_datas_v2);
                         }
                         if let Some((token_ownership, current_token_ownership)) =
                             TokenOwnershipV2::get_v1_from_write_table_item(
@@ -1247,13 +1406,15 @@ fn parse_v2_token(
                                 current_collection,
                             );
                         }
-                        if let Some((token_data, current_token_data)) =
+                        if let Some((mut token_data, current_token_data)) =
                             TokenDataV2::get_v2_from_write_resource(
                                 resource,
                                 txn_version,
                                 wsc_index,
                                 txn_timestamp,
                                 &token_v2_metadata,
+                                buffer_missing_token_v2_metadata
+                                    .then_some(&mut pending_token_datas_v2),
                             )
                             .unwrap()
                         {
@@ -1268,11 +1429,18 @@ fn parse_v2_token(
                                 &token_v2_metadata,
                             )
                             .unwrap();
-                            token_datas_v2.push(token_data);
-                            current_token_datas_v2.insert(
-                                current_token_data.token_data_id.clone(),
-                                current_token_data,
+                            // Track name / uri / description changes against the prior current row,
+                            // from this batch or otherwise from the db
+                            token_mutation_events.extend(
+                                TokenMutationEvent::get_from_token_data_v2(
+                                    &mut token_data,
+                                    &current_token_data,
+                                    &current_token_datas_v2,
+                                    &prior_token_datas_v2,
+                                ),
                             );
+                            token_datas_v2.push(token_data);
+                            current_token_data.upsert_into(&mut current_token_datas_v2);
                             token_ownerships_v2.push(nft_ownership);
                             // this is used to persist latest owner for burn event handling
                             prior_nft_ownership.insert(
@@ -1339,6 +1507,34 @@ fn parse_v2_token(
                                 );
                             }
                         }
+                        // Add fungible token ownership, if the store holds a known token
+                        if let Some((ft_ownership, current_ft_ownership)) =
+                            TokenOwnershipV2::get_ft_v2_from_write_resource(
+                                resource,
+                                txn_version,
+                                wsc_index,
+                                txn_timestamp,
+                                &token_v2_metadata,
+                            )
+                            .unwrap()
+                        {
+                            let token_data_id = &current_ft_ownership.token_data_id;
+                            let is_token = token_v2_metadata.contains_key(token_data_id)
+                                || current_token_datas_v2.contains_key(token_data_id)
+                                || prior_token_datas_v2.contains_key(token_data_id);
+                            if is_token {
+                                token_ownerships_v2.push(ft_ownership);
+                                current_token_ownerships_v2.insert(
+                                    (
+                                        current_ft_ownership.token_data_id.clone(),
+                                        current_ft_ownership.property_version_v1.clone(),
+                                        current_ft_ownership.owner_address.clone(),
+                                        current_ft_ownership.storage_id.clone(),
+                                    ),
+                                    current_ft_ownership,
+                                );
+                            }
+                        }
                     },
                     WriteSetChange::DeleteResource(resource) => {
                         // Add burned NFT handling
@@ -1380,6 +1576,49 @@ fn parse_v2_token(
         }
     }
 
+    // Second pass over the token v2 resources that were missing metadata, now that the metadata
+    // of the entire batch is known. The token data and its NFT ownership are recovered, but
+    // current ownerships don't override those from later transactions in the batch.
+    for pending_token_data in pending_token_datas_v2 {
+        if let Some((mut token_data, current_token_data)) =
+            pending_token_data.resolve(&token_v2_metadata)?
+        {
+            token_mutation_events.extend(TokenMutationEvent::get_from_token_data_v2(
+                &mut token_data,
+                &current_token_data,
+                &current_token_datas_v2,
+                &prior_token_datas_v2,
+            ));
+            let (
+                nft_ownership,
+                current_nft_ownership,
+                from_nft_ownership,
+                from_current_nft_ownership,
+            ) = TokenOwnershipV2::get_nft_v2_from_token_data(&token_data, &token_v2_metadata)?;
+            token_datas_v2.push(token_data);
+            current_token_data.upsert_into(&mut current_token_datas_v2);
+            token_ownerships_v2.push(nft_ownership);
+            if let Some(from_nft_ownership) = from_nft_ownership {
+                token_ownerships_v2.push(from_nft_ownership);
+            }
+            for cto in std::iter::once(current_nft_ownership).chain(from_current_nft_ownership) {
+                let pk = (
+                    cto.token_data_id.clone(),
+                    cto.property_version_v1.clone(),
+                    cto.owner_address.clone(),
+                    cto.storage_id.clone(),
+                );
+                let is_superseded = matches!(
+                    current_token_ownerships_v2.get(&pk),
+                    Some(current) if current.last_transaction_version > cto.last_transaction_version
+                );
+                if !is_superseded {
+                    current_token_ownerships_v2.insert(pk, cto);
+                }
+            }
+        }
+    }
+
     // Getting list of values and sorting by pk in order to avoid postgres deadlock since we're doing multi threaded db writes
     let mut current_collections_v2 = current_collections_v2
         .into_values()
@@ -1409,7 +1648,7 @@ fn parse_v2_token(
             ))
     });
 
-    (
+    Ok((
         collections_v2,
         token_datas_v2,
         token_ownerships_v2,
@@ -1417,5 +1656,38 @@ fn parse_v2_token(
         current_token_datas_v2,
         current_token_ownerships_v2,
         token_activities_v2,
-    )
+        token_mutation_events,
+    ))
+}
+
+/// Returns the current token datas in the db of the v2 tokens written in the batch, and of the
+/// metadata of the fungible stores written in the batch (if they are tokens), with a single
+/// query for the entire batch.
+fn get_prior_token_datas_v2(
+    transactions: &[Transaction],
+    conn: &mut PgPoolConnection,
+) -> anyhow::Result<HashMap<CurrentTokenDataV2PK, CurrentTokenDataV2>> {
+    let mut token_data_ids = HashSet::new();
+    for txn in transactions {
+        if let Transaction::UserTransaction(user_txn) = txn {
+            let txn_version = user_txn.info.version.0 as i64;
+            for wsc in user_txn.info.changes.iter() {
+                if let WriteSetChange::WriteResource(wr) = wsc {
+                    if TokenV2::from_write_resource(wr, txn_version)?.is_some() {
+                        token_data_ids.insert(standardize_address(&wr.address.to_string()));
+                    }
+                    if let Some(fungible_store) =
+                        FungibleStore::from_write_resource(wr, txn_version)?
+                    {
+                        token_data_ids.insert(fungible_store.get_metadata_address());
+                    }
+                }
+            }
+        }
+    }
+    let token_data_ids = token_data_ids.into_iter().collect::<Vec<_>>();
+    Ok(CurrentTokenDataV2Query::get_by_token_data_ids(
+        conn,
+        &token_data_ids,
+    )?)
 }
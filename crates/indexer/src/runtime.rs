@@ -132,6 +132,7 @@ pub async fn run_forever(config: IndexerConfig, context: Arc<Context>) {
             conn_pool.clone(),
             config.ans_contract_address,
             config.nft_points_contract,
+            config.buffer_missing_token_v2_metadata.unwrap_or(false),
         )),
         Processor::CoinProcessor => Arc::new(CoinTransactionProcessor::new(conn_pool.clone())),
         Processor::StakeProcessor => Arc::new(StakeTransactionProcessor::new(conn_pool.clone())),
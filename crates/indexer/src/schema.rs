@@ -77,6 +77,18 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    collection_cap_events (collection_id) {
+        collection_id -> Varchar,
+        token_data_id -> Nullable<Varchar>,
+        transaction_version -> Int8,
+        maximum -> Numeric,
+        supply -> Numeric,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     collection_datas (collection_data_id_hash, transaction_version) {
         collection_data_id_hash -> Varchar,
@@ -477,6 +489,17 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    processor_token_standard_stats (start_version, end_version, time_bucket, token_standard) {
+        start_version -> Int8,
+        end_version -> Int8,
+        time_bucket -> Timestamp,
+        token_standard -> Varchar,
+        num_token_datas -> Int8,
+        inserted_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     proposal_votes (transaction_version, proposal_id, voter_address) {
         transaction_version -> Int8,
@@ -621,6 +644,21 @@ diesel::table! {
         is_fungible_v2 -> Nullable<Bool>,
         transaction_timestamp -> Timestamp,
         inserted_at -> Timestamp,
+        description_mutated -> Bool,
+        supply_exceeds_maximum -> Bool,
+    }
+}
+
+diesel::table! {
+    token_mutation_events (transaction_version, write_set_change_index, mutated_field_name) {
+        transaction_version -> Int8,
+        write_set_change_index -> Int8,
+        token_data_id -> Varchar,
+        mutated_field_name -> Varchar,
+        old_value -> Text,
+        new_value -> Text,
+        transaction_timestamp -> Timestamp,
+        inserted_at -> Timestamp,
     }
 }
 
@@ -734,6 +772,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     coin_balances,
     coin_infos,
     coin_supply,
+    collection_cap_events,
     collection_datas,
     collections_v2,
     current_ans_lookup,
@@ -762,6 +801,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     objects,
     processor_status,
     processor_statuses,
+    processor_token_standard_stats,
     proposal_votes,
     signatures,
     table_items,
@@ -770,6 +810,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     token_activities_v2,
     token_datas,
     token_datas_v2,
+    token_mutation_events,
     token_ownerships,
     token_ownerships_v2,
     tokens,
@@ -8,7 +8,9 @@ use aptos_rest_client::{aptos_api_types::TransactionInfo, error::RestError, Clie
 use aptos_sdk::{
     move_types::account_address::AccountAddress, types::transaction::SignedTransaction,
 };
-use aptos_transaction_generator_lib::{CounterState, ReliableTransactionSubmitter};
+use aptos_transaction_generator_lib::{
+    CounterState, ExecutionResult, ReliableTransactionSubmitter,
+};
 use async_trait::async_trait;
 use futures::future::join_all;
 use rand::{rngs::StdRng, seq::SliceRandom, thread_rng, Rng, SeedableRng};
@@ -284,6 +286,21 @@ impl ReliableTransactionSubmitter for RestApiReliableTransactionSubmitter {
         Ok(())
     }
 
+    async fn execute_transactions_with_hashes(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<ExecutionResult> {
+        let outcome = self.execute_transactions(txns).await?;
+        // Executions only succeed once all transactions are committed.
+        Ok(ExecutionResult {
+            outcome,
+            committed_hashes: txns
+                .iter()
+                .map(|txn| txn.clone().committed_hash())
+                .collect(),
+        })
+    }
+
     fn create_counter_state(&self) -> CounterState {
         CounterState {
             submit_failures: std::iter::repeat_with(|| AtomicUsize::new(0))
@@ -0,0 +1,225 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GeneratorMetrics, GeneratorReport, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+/// Wrapper that caps the total number of transactions produced by the inner generator.
+/// The count is shared across all generators created by the same creator, so once
+/// `max_total` transactions are produced (across all calls and all workers),
+/// subsequent calls return no transactions. With no cap, it is a pass-through.
+pub struct BoundedGenerator {
+    generator: Box<dyn TransactionGenerator>,
+    max_total: Option<usize>,
+    total_created: Arc<AtomicUsize>,
+}
+
+impl BoundedGenerator {
+    pub fn new(
+        generator: Box<dyn TransactionGenerator>,
+        max_total: Option<usize>,
+        total_created: Arc<AtomicUsize>,
+    ) -> Self {
+        Self {
+            generator,
+            max_total,
+            total_created,
+        }
+    }
+
+    // Returns the transactions, and the number of requested transactions discarded by the cap.
+    fn generate_bounded(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        mut generate: impl FnMut(
+            &mut dyn TransactionGenerator,
+            &mut LocalAccount,
+            usize,
+        ) -> Vec<SignedTransaction>,
+    ) -> (Vec<SignedTransaction>, usize) {
+        let max_total = match self.max_total {
+            Some(max_total) => max_total,
+            None => return (generate(self.generator.as_mut(), account, num_to_create), 0),
+        };
+
+        // Reserve the transactions to create upfront, so concurrent workers can't exceed the cap.
+        let reserved =
+            match self
+                .total_created
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |created| {
+                    if created >= max_total {
+                        None
+                    } else {
+                        Some(std::cmp::min(created + num_to_create, max_total))
+                    }
+                }) {
+                Ok(prev_created) => std::cmp::min(num_to_create, max_total - prev_created),
+                Err(_) => return (Vec::new(), num_to_create),
+            };
+
+        let txns = generate(self.generator.as_mut(), account, reserved);
+        // Return the unused reservation, if the inner generator created fewer transactions.
+        if txns.len() < reserved {
+            self.total_created
+                .fetch_sub(reserved - txns.len(), Ordering::Relaxed);
+        }
+        (txns, num_to_create - reserved)
+    }
+}
+
+impl TransactionGenerator for BoundedGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.generate_bounded(account, num_to_create, |generator, account, num| {
+            generator.generate_transactions(account, num)
+        })
+        .0
+    }
+
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        let (txns, discarded) =
+            self.generate_bounded(account, num_to_create, |generator, account, num| {
+                generator.generate_transactions_with_metrics(account, num, metrics)
+            });
+        // The inner generator reports its own transactions, only the capped-off ones are
+        // added here.
+        if discarded > 0 {
+            metrics.report(GeneratorReport {
+                discarded,
+                ..GeneratorReport::default()
+            });
+        }
+        txns
+    }
+}
+
+pub struct BoundedGeneratorCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    max_total: Option<usize>,
+    total_created: Arc<AtomicUsize>,
+}
+
+impl BoundedGeneratorCreator {
+    pub fn new(creator: Box<dyn TransactionGeneratorCreator>, max_total: Option<usize>) -> Self {
+        Self {
+            creator,
+            max_total,
+            total_created: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for BoundedGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(BoundedGenerator::new(
+            self.creator.create_transaction_generator(),
+            self.max_total,
+            self.total_created.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_utils::{FakeMetrics, TransferGeneratorCreator},
+        RateLimitedGeneratorCreator,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_bounded_generator_caps_total() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator =
+            BoundedGeneratorCreator::new(Box::new(TransferGeneratorCreator::default()), Some(10));
+        let mut generator = creator.create_transaction_generator();
+
+        assert_eq!(generator.generate_transactions(&mut account, 7).len(), 7);
+        assert_eq!(generator.generate_transactions(&mut account, 7).len(), 3);
+        assert!(generator.generate_transactions(&mut account, 7).is_empty());
+        // The cap is shared with other generators from the same creator.
+        let mut other_generator = creator.create_transaction_generator();
+        assert!(other_generator
+            .generate_transactions(&mut account, 7)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_metrics_of_rate_limited_inner_generator() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator = BoundedGeneratorCreator::new(
+            Box::new(RateLimitedGeneratorCreator::new(
+                Box::new(TransferGeneratorCreator::default()),
+                Some(3),
+            )),
+            Some(4),
+        );
+        let mut generator = creator.create_transaction_generator();
+        let metrics = FakeMetrics::default();
+
+        // The rate limiter reports what it generated, the cap what it discarded.
+        assert_eq!(
+            generator
+                .generate_transactions_with_metrics(&mut account, 3, &metrics)
+                .len(),
+            3
+        );
+        assert_eq!(
+            generator
+                .generate_transactions_with_metrics(&mut account, 3, &metrics)
+                .len(),
+            1
+        );
+        assert!(generator
+            .generate_transactions_with_metrics(&mut account, 3, &metrics)
+            .is_empty());
+        assert_eq!(*metrics.reports.lock(), vec![
+            GeneratorReport {
+                generated: 3,
+                discarded: 0,
+                deferred: 0,
+            },
+            GeneratorReport {
+                generated: 1,
+                discarded: 0,
+                deferred: 0,
+            },
+            GeneratorReport {
+                discarded: 2,
+                ..GeneratorReport::default()
+            },
+            GeneratorReport {
+                discarded: 3,
+                ..GeneratorReport::default()
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_unbounded_generator() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator =
+            BoundedGeneratorCreator::new(Box::new(TransferGeneratorCreator::default()), None);
+        let mut generator = creator.create_transaction_generator();
+
+        assert_eq!(generator.generate_transactions(&mut account, 7).len(), 7);
+        assert_eq!(generator.generate_transactions(&mut account, 7).len(), 7);
+    }
+}
@@ -0,0 +1,157 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GeneratorMetrics, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::types::{
+    transaction::{RawTransaction, SignedTransaction},
+    LocalAccount,
+};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+/// Wrapper that assigns to each transaction of the inner generator a gas unit price picked
+/// from weighted tiers, e.g. to observe prioritization in fee-market tests. As the gas unit
+/// price is part of the signed transaction, each transaction is rebuilt and re-signed by the
+/// sender (so multi-agent transactions are not supported).
+pub struct GasPriceTiersGenerator {
+    rng: StdRng,
+    generator: Box<dyn TransactionGenerator>,
+    // (gas unit price, weight) of each tier. Tiers with zero weight are never picked.
+    gas_price_tiers: Vec<(u64, usize)>,
+    total_weight: usize,
+}
+
+impl GasPriceTiersGenerator {
+    pub fn new(
+        rng: StdRng,
+        generator: Box<dyn TransactionGenerator>,
+        gas_price_tiers: Vec<(u64, usize)>,
+    ) -> Self {
+        let total_weight = gas_price_tiers.iter().map(|(_, weight)| weight).sum();
+        assert!(
+            total_weight > 0,
+            "At least one gas price tier must have a non-zero weight"
+        );
+        Self {
+            rng,
+            generator,
+            gas_price_tiers,
+            total_weight,
+        }
+    }
+
+    fn pick_gas_unit_price(&mut self) -> u64 {
+        let mut picked = self.rng.gen_range(0, self.total_weight);
+        for (gas_unit_price, weight) in &self.gas_price_tiers {
+            if picked < *weight {
+                return *gas_unit_price;
+            }
+            picked -= *weight;
+        }
+        unreachable!("Picked weight must be lower than the total weight");
+    }
+
+    /// Re-signs the transactions of the inner generator with a picked gas unit price.
+    fn reprice(
+        &mut self,
+        account: &LocalAccount,
+        txns: Vec<SignedTransaction>,
+    ) -> Vec<SignedTransaction> {
+        txns.into_iter()
+            .map(|txn| {
+                let raw_txn = RawTransaction::new(
+                    txn.sender(),
+                    txn.sequence_number(),
+                    txn.payload().clone(),
+                    txn.max_gas_amount(),
+                    self.pick_gas_unit_price(),
+                    txn.expiration_timestamp_secs(),
+                    txn.chain_id(),
+                );
+                account.sign_transaction(raw_txn)
+            })
+            .collect()
+    }
+}
+
+impl TransactionGenerator for GasPriceTiersGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let txns = self.generator.generate_transactions(account, num_to_create);
+        self.reprice(account, txns)
+    }
+
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        let txns =
+            self.generator
+                .generate_transactions_with_metrics(account, num_to_create, metrics);
+        self.reprice(account, txns)
+    }
+}
+
+pub struct GasPriceTiersGeneratorCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    gas_price_tiers: Vec<(u64, usize)>,
+}
+
+impl GasPriceTiersGeneratorCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        gas_price_tiers: Vec<(u64, usize)>,
+    ) -> Self {
+        Self {
+            creator,
+            gas_price_tiers,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for GasPriceTiersGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(GasPriceTiersGenerator::new(
+            StdRng::from_entropy(),
+            self.creator.create_transaction_generator(),
+            self.gas_price_tiers.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransferGenerator;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_gas_price_tiers() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut generator =
+            GasPriceTiersGenerator::new(rng, Box::new(TransferGenerator::default()), vec![
+                (100, 3),
+                (200, 1),
+                (300, 0),
+            ]);
+
+        let txns = generator.generate_transactions(&mut account, 1000);
+        assert_eq!(txns.len(), 1000);
+        let mut counts: HashMap<u64, usize> = HashMap::new();
+        for (i, txn) in txns.into_iter().enumerate() {
+            assert_eq!(txn.sequence_number(), i as u64);
+            *counts.entry(txn.gas_unit_price()).or_default() += 1;
+            // The transaction is re-signed with the new gas unit price.
+            assert!(txn.check_signature().is_ok());
+        }
+
+        assert_eq!(counts.len(), 2);
+        assert!((650..850).contains(&counts[&100]), "{:?}", counts);
+        assert!((150..350).contains(&counts[&200]), "{:?}", counts);
+    }
+}
@@ -0,0 +1,232 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GeneratorMetrics, GeneratorReport, TransactionGenerator, TransactionGeneratorCreator};
+use aptos::common::types::RotationProofChallenge;
+use aptos_crypto::{SigningKey, ValidCryptoMaterial};
+use aptos_sdk::{
+    bcs,
+    move_types::{account_address::AccountAddress, language_storage::CORE_CODE_ADDRESS},
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{transaction::SignedTransaction, AccountKey, LocalAccount},
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::HashMap;
+
+/// Wrapper that rotates the authentication key of the accounts it generates transactions
+/// for, to stress the key rotation path under load. After every `rotation_interval`
+/// transactions of the inner generator for an account, an `account::rotate_authentication_key`
+/// transaction (in addition to the requested ones) is signed with the current key, and the
+/// key of the LocalAccount is replaced, so the following transactions are signed with the
+/// new key.
+pub struct KeyRotationGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    generator: Box<dyn TransactionGenerator>,
+    rotation_interval: usize,
+    // Number of transactions generated for each account since its last rotation.
+    since_rotation: HashMap<AccountAddress, usize>,
+}
+
+impl KeyRotationGenerator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        generator: Box<dyn TransactionGenerator>,
+        rotation_interval: usize,
+    ) -> Self {
+        assert!(rotation_interval > 0, "Rotation interval must be positive");
+        Self {
+            rng,
+            txn_factory,
+            generator,
+            rotation_interval,
+            since_rotation: HashMap::new(),
+        }
+    }
+
+    /// Signs the rotation to a new key with the current key of the account, and replaces
+    /// the key of the account.
+    fn rotate_key(&mut self, account: &mut LocalAccount) -> SignedTransaction {
+        let new_key = AccountKey::generate(&mut self.rng);
+        let rotation_proof = RotationProofChallenge {
+            account_address: CORE_CODE_ADDRESS,
+            module_name: "account".to_string(),
+            struct_name: "RotationProofChallenge".to_string(),
+            sequence_number: account.sequence_number(),
+            originator: account.address(),
+            current_auth_key: account.authentication_key().derived_address(),
+            new_public_key: new_key.public_key().to_bytes().to_vec(),
+        };
+        let rotation_msg = bcs::to_bytes(&rotation_proof).expect("Rotation proof must serialize");
+        let signed_by_current_key = account.private_key().sign_arbitrary_message(&rotation_msg);
+        let signed_by_new_key = new_key.private_key().sign_arbitrary_message(&rotation_msg);
+
+        let txn = account.sign_with_transaction_builder(self.txn_factory.payload(
+            aptos_stdlib::account_rotate_authentication_key(
+                0,
+                account.public_key().to_bytes().to_vec(),
+                0,
+                new_key.public_key().to_bytes().to_vec(),
+                signed_by_current_key.to_bytes().to_vec(),
+                signed_by_new_key.to_bytes().to_vec(),
+            ),
+        ));
+        account.rotate_key(new_key);
+        txn
+    }
+
+    // Returns the transactions, including the rotations, and the number of rotations.
+    fn generate_with_rotations(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        mut generate: impl FnMut(
+            &mut dyn TransactionGenerator,
+            &mut LocalAccount,
+            usize,
+        ) -> Vec<SignedTransaction>,
+    ) -> (Vec<SignedTransaction>, usize) {
+        let mut txns = Vec::with_capacity(num_to_create);
+        let mut num_rotations = 0;
+        let mut remaining = num_to_create;
+        while remaining > 0 {
+            let since_rotation = self.since_rotation.entry(account.address()).or_default();
+            let num_before_rotation =
+                std::cmp::min(remaining, self.rotation_interval - *since_rotation);
+            *since_rotation += num_before_rotation;
+            let rotate = *since_rotation == self.rotation_interval;
+            if rotate {
+                *since_rotation = 0;
+            }
+
+            txns.extend(generate(
+                self.generator.as_mut(),
+                account,
+                num_before_rotation,
+            ));
+            if rotate {
+                txns.push(self.rotate_key(account));
+                num_rotations += 1;
+            }
+            remaining -= num_before_rotation;
+        }
+        (txns, num_rotations)
+    }
+}
+
+impl TransactionGenerator for KeyRotationGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.generate_with_rotations(account, num_to_create, |generator, account, num| {
+            generator.generate_transactions(account, num)
+        })
+        .0
+    }
+
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        let (txns, num_rotations) =
+            self.generate_with_rotations(account, num_to_create, |generator, account, num| {
+                generator.generate_transactions_with_metrics(account, num, metrics)
+            });
+        // The inner generator reports its own transactions, only the rotations are added here.
+        if num_rotations > 0 {
+            metrics.report(GeneratorReport {
+                generated: num_rotations,
+                ..GeneratorReport::default()
+            });
+        }
+        txns
+    }
+}
+
+pub struct KeyRotationGeneratorCreator {
+    txn_factory: TransactionFactory,
+    creator: Box<dyn TransactionGeneratorCreator>,
+    rotation_interval: usize,
+}
+
+impl KeyRotationGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        creator: Box<dyn TransactionGeneratorCreator>,
+        rotation_interval: usize,
+    ) -> Self {
+        Self {
+            txn_factory,
+            creator,
+            rotation_interval,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for KeyRotationGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(KeyRotationGenerator::new(
+            StdRng::from_entropy(),
+            self.txn_factory.clone(),
+            self.creator.create_transaction_generator(),
+            self.rotation_interval,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransferGenerator;
+    use aptos_sdk::types::{chain_id::ChainId, transaction::TransactionPayload};
+
+    fn is_rotation(txn: &SignedTransaction) -> bool {
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                entry_function.function().as_str() == "rotate_authentication_key"
+            },
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_signed_with_rotated_key() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let original_key = account.public_key().clone();
+        let txn_factory = TransactionFactory::new(ChainId::test());
+        let mut generator =
+            KeyRotationGenerator::new(rng, txn_factory, Box::new(TransferGenerator::default()), 2);
+
+        let txns = generator.generate_transactions(&mut account, 3);
+        assert_eq!(txns.len(), 4);
+        assert_eq!(txns.iter().map(is_rotation).collect::<Vec<_>>(), vec![
+            false, false, true, false
+        ]);
+        for (i, txn) in txns.iter().enumerate() {
+            assert_eq!(txn.sequence_number(), i as u64);
+            assert!(txn.clone().check_signature().is_ok());
+        }
+
+        // The rotation is signed with the original key, and the following transactions with
+        // the rotated key of the account.
+        let signer_key = |txn: &SignedTransaction| txn.authenticator().sender().public_key_bytes();
+        assert_eq!(signer_key(&txns[2]), original_key.to_bytes().to_vec());
+        assert_ne!(account.public_key(), &original_key);
+        assert_eq!(
+            signer_key(&txns[3]),
+            account.public_key().to_bytes().to_vec()
+        );
+
+        // The next rotation happens after 2 more transactions (one was already generated).
+        let txns = generator.generate_transactions(&mut account, 2);
+        assert_eq!(txns.iter().map(is_rotation).collect::<Vec<_>>(), vec![
+            false, true, false
+        ]);
+    }
+}
@@ -7,6 +7,7 @@ use anyhow::Result;
 use aptos_infallible::RwLock;
 use aptos_logger::{sample, sample::SampleRate, warn};
 use aptos_sdk::{
+    crypto::HashValue,
     move_types::account_address::AccountAddress,
     transaction_builder::{aptos_stdlib, TransactionFactory},
     types::{transaction::SignedTransaction, LocalAccount},
@@ -26,16 +27,25 @@ mod account_generator;
 mod accounts_pool_wrapper;
 pub mod args;
 mod batch_transfer;
+mod bounded_generator;
 mod call_custom_modules;
 mod entry_points;
+mod gas_price_tiers;
+mod key_rotation;
 mod p2p_transaction_generator;
 pub mod publish_modules;
 mod publishing;
+mod rate_limited_generator;
+mod replay_generator;
+mod resource_account_generator;
+mod script_generator;
+mod sequence_number_sync;
+mod sequential_generator;
+mod token_v2_mint_generator;
 mod transaction_mix_generator;
 use self::{
     account_generator::AccountGeneratorCreator,
     call_custom_modules::CustomModulesDelegationGeneratorCreator,
-    p2p_transaction_generator::P2PTransactionGeneratorCreator,
     publish_modules::PublishPackageCreator,
     transaction_mix_generator::PhasedTxnMixGeneratorCreator,
 };
@@ -44,7 +54,20 @@ use crate::{
     batch_transfer::BatchTransferTransactionGeneratorCreator,
     entry_points::EntryPointTransactionGenerator,
 };
+pub use bounded_generator::BoundedGeneratorCreator;
+pub use gas_price_tiers::GasPriceTiersGeneratorCreator;
+pub use key_rotation::KeyRotationGeneratorCreator;
+pub use p2p_transaction_generator::{AmountDistribution, P2PTransactionGeneratorCreator};
 pub use publishing::module_simple::EntryPoints;
+pub use rate_limited_generator::RateLimitedGeneratorCreator;
+pub use replay_generator::{write_transactions, ReplayGeneratorCreator};
+pub use resource_account_generator::ResourceAccountGeneratorCreator;
+pub use script_generator::{ScriptArgBuilder, ScriptGeneratorCreator};
+pub use sequence_number_sync::{
+    SequenceNumberFetcher, SequenceNumberSyncGenerator, SequenceNumberSyncGeneratorCreator,
+};
+pub use sequential_generator::SequentialCreator;
+pub use token_v2_mint_generator::TokenV2MintGeneratorCreator;
 
 pub const SEND_AMOUNT: u64 = 1;
 
@@ -84,6 +107,56 @@ pub trait TransactionGenerator: Sync + Send {
         account: &mut LocalAccount,
         num_to_create: usize,
     ) -> Vec<SignedTransaction>;
+
+    /// Same as generate_transactions, but transactions are created (and signed) lazily, as
+    /// they are pulled from the returned iterator, to reduce peak memory when creating many
+    /// transactions at once. By default, all transactions are created upfront.
+    fn generate_transactions_lazily<'a>(
+        &'a mut self,
+        account: &'a mut LocalAccount,
+        num_to_create: usize,
+    ) -> Box<dyn Iterator<Item = SignedTransaction> + 'a> {
+        Box::new(
+            self.generate_transactions(account, num_to_create)
+                .into_iter(),
+        )
+    }
+
+    /// Same as generate_transactions, but reports to 'metrics' how many transactions were
+    /// generated, discarded or deferred (e.g. due to rate limits) by the call, to surface why
+    /// a generator underproduces. By default, only the generated transactions are reported.
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        let txns = self.generate_transactions(account, num_to_create);
+        metrics.report(GeneratorReport {
+            generated: txns.len(),
+            ..GeneratorReport::default()
+        });
+        txns
+    }
+}
+
+/// Counts reported by a generator for a single call, see generate_transactions_with_metrics.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct GeneratorReport {
+    pub generated: usize,
+    pub discarded: usize,
+    pub deferred: usize,
+}
+
+/// Sink for the reports of generators, e.g. for the emitter to aggregate them.
+pub trait GeneratorMetrics: Sync + Send {
+    fn report(&self, report: GeneratorReport);
+}
+
+pub struct NoopGeneratorMetrics;
+
+impl GeneratorMetrics for NoopGeneratorMetrics {
+    fn report(&self, _report: GeneratorReport) {}
 }
 
 #[async_trait]
@@ -99,14 +172,49 @@ pub struct CounterState {
     pub by_client: HashMap<String, (AtomicUsize, AtomicUsize, AtomicUsize)>,
 }
 
+/// Outcome of executing a batch of transactions, which lets the caller apply backpressure
+/// (i.e. slow down) when the target reports being overloaded.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SubmissionOutcome {
+    /// Transactions accepted by the target.
+    pub accepted: usize,
+    /// Transactions rejected by the target (e.g. failed validation).
+    pub rejected: usize,
+    /// Transactions the target refused to take because it is overloaded (e.g. mempool full).
+    pub throttled: usize,
+}
+
+impl SubmissionOutcome {
+    pub fn all_accepted(num_txns: usize) -> Self {
+        Self {
+            accepted: num_txns,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_throttled(&self) -> bool {
+        self.throttled > 0
+    }
+}
+
+/// Result of executing a batch of transactions, with the hashes of the transactions that got
+/// committed, so that the caller can correlate them with their on-chain outcomes (e.g. poll
+/// for inclusion, or measure confirmation latency).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionResult {
+    pub outcome: SubmissionOutcome,
+    /// Hashes of the committed transactions, empty if the submitter doesn't track them.
+    pub committed_hashes: Vec<HashValue>,
+}
+
 #[async_trait]
 pub trait ReliableTransactionSubmitter: Sync + Send {
     async fn get_account_balance(&self, account_address: AccountAddress) -> Result<u64>;
 
     async fn query_sequence_number(&self, account_address: AccountAddress) -> Result<u64>;
 
-    async fn execute_transactions(&self, txns: &[SignedTransaction]) -> Result<()> {
-        self.execute_transactions_with_counter(txns, &CounterState {
+    async fn execute_transactions(&self, txns: &[SignedTransaction]) -> Result<SubmissionOutcome> {
+        self.execute_transactions_with_outcome(txns, &CounterState {
             submit_failures: vec![AtomicUsize::new(0)],
             wait_failures: vec![AtomicUsize::new(0)],
             successes: AtomicUsize::new(0),
@@ -115,12 +223,36 @@ pub trait ReliableTransactionSubmitter: Sync + Send {
         .await
     }
 
+    /// Same as execute_transactions, but also returns the hashes of the committed
+    /// transactions. Submitters that don't track them return no hashes (the default).
+    async fn execute_transactions_with_hashes(
+        &self,
+        txns: &[SignedTransaction],
+    ) -> Result<ExecutionResult> {
+        Ok(ExecutionResult {
+            outcome: self.execute_transactions(txns).await?,
+            committed_hashes: vec![],
+        })
+    }
+
     async fn execute_transactions_with_counter(
         &self,
         txns: &[SignedTransaction],
         state: &CounterState,
     ) -> Result<()>;
 
+    /// Same as execute_transactions_with_counter, but also reports how many transactions
+    /// were accepted, rejected or throttled by the target. Submitters that can't tell
+    /// report all transactions as accepted once they are executed (the default).
+    async fn execute_transactions_with_outcome(
+        &self,
+        txns: &[SignedTransaction],
+        state: &CounterState,
+    ) -> Result<SubmissionOutcome> {
+        self.execute_transactions_with_counter(txns, state).await?;
+        Ok(SubmissionOutcome::all_accepted(txns.len()))
+    }
+
     fn create_counter_state(&self) -> CounterState;
 }
 
@@ -319,3 +451,237 @@ pub fn create_account_transaction(
         },
     ))
 }
+
+/// Generators shared by the unit tests of the wrapping generators in this crate.
+#[cfg(test)]
+pub(crate) mod test_utils {
+    use crate::{
+        GeneratorMetrics, GeneratorReport, TransactionGenerator, TransactionGeneratorCreator,
+    };
+    use aptos_infallible::Mutex;
+    use aptos_sdk::{
+        move_types::account_address::AccountAddress,
+        transaction_builder::{aptos_stdlib, TransactionFactory},
+        types::{chain_id::ChainId, transaction::SignedTransaction, LocalAccount},
+    };
+
+    /// Generates transfers of 1 coin to `to`, to be wrapped by the generator under test.
+    pub(crate) struct TransferGenerator {
+        txn_factory: TransactionFactory,
+        to: AccountAddress,
+    }
+
+    impl TransferGenerator {
+        pub(crate) fn new(to: AccountAddress) -> Self {
+            Self {
+                txn_factory: TransactionFactory::new(ChainId::test()),
+                to,
+            }
+        }
+    }
+
+    impl Default for TransferGenerator {
+        fn default() -> Self {
+            Self::new(AccountAddress::ONE)
+        }
+    }
+
+    impl TransactionGenerator for TransferGenerator {
+        fn generate_transactions(
+            &mut self,
+            account: &mut LocalAccount,
+            num_to_create: usize,
+        ) -> Vec<SignedTransaction> {
+            (0..num_to_create)
+                .map(|_| {
+                    account.sign_with_transaction_builder(
+                        self.txn_factory
+                            .payload(aptos_stdlib::aptos_coin_transfer(self.to, 1)),
+                    )
+                })
+                .collect()
+        }
+    }
+
+    pub(crate) struct TransferGeneratorCreator {
+        to: AccountAddress,
+    }
+
+    impl TransferGeneratorCreator {
+        pub(crate) fn new(to: AccountAddress) -> Self {
+            Self { to }
+        }
+    }
+
+    impl Default for TransferGeneratorCreator {
+        fn default() -> Self {
+            Self::new(AccountAddress::ONE)
+        }
+    }
+
+    impl TransactionGeneratorCreator for TransferGeneratorCreator {
+        fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+            Box::new(TransferGenerator::new(self.to))
+        }
+    }
+
+    /// Records the reports of the generator under test.
+    #[derive(Default)]
+    pub(crate) struct FakeMetrics {
+        pub(crate) reports: Mutex<Vec<GeneratorReport>>,
+    }
+
+    impl GeneratorMetrics for FakeMetrics {
+        fn report(&self, report: GeneratorReport) {
+            self.reports.lock().push(report);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransferGenerator;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    /// Submitter accepting up to `max_accepted` transactions of each call (all of them if
+    /// unset) and throttling the rest. If `track_hashes` is set, the hashes of the accepted
+    /// transactions are returned as committed.
+    #[derive(Default)]
+    struct MockSubmitter {
+        max_accepted: Option<usize>,
+        track_hashes: bool,
+    }
+
+    #[async_trait]
+    impl ReliableTransactionSubmitter for MockSubmitter {
+        async fn get_account_balance(&self, _account_address: AccountAddress) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn query_sequence_number(&self, _account_address: AccountAddress) -> Result<u64> {
+            Ok(0)
+        }
+
+        async fn execute_transactions_with_counter(
+            &self,
+            _txns: &[SignedTransaction],
+            _state: &CounterState,
+        ) -> Result<()> {
+            Ok(())
+        }
+
+        async fn execute_transactions_with_hashes(
+            &self,
+            txns: &[SignedTransaction],
+        ) -> Result<ExecutionResult> {
+            let outcome = self.execute_transactions(txns).await?;
+            let committed_hashes = if self.track_hashes {
+                txns[..outcome.accepted]
+                    .iter()
+                    .map(|txn| txn.clone().committed_hash())
+                    .collect()
+            } else {
+                vec![]
+            };
+            Ok(ExecutionResult {
+                outcome,
+                committed_hashes,
+            })
+        }
+
+        async fn execute_transactions_with_outcome(
+            &self,
+            txns: &[SignedTransaction],
+            _state: &CounterState,
+        ) -> Result<SubmissionOutcome> {
+            let accepted = txns.len().min(self.max_accepted.unwrap_or(txns.len()));
+            Ok(SubmissionOutcome {
+                accepted,
+                rejected: 0,
+                throttled: txns.len() - accepted,
+            })
+        }
+
+        fn create_counter_state(&self) -> CounterState {
+            CounterState {
+                submit_failures: vec![AtomicUsize::new(0)],
+                wait_failures: vec![AtomicUsize::new(0)],
+                successes: AtomicUsize::new(0),
+                by_client: HashMap::new(),
+            }
+        }
+    }
+
+    fn transfers(num_txns: usize) -> Vec<SignedTransaction> {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        TransferGenerator::default().generate_transactions(&mut account, num_txns)
+    }
+
+    #[tokio::test]
+    async fn test_submission_outcome() {
+        let txns = transfers(5);
+
+        let outcome = MockSubmitter {
+            max_accepted: Some(3),
+            ..Default::default()
+        }
+        .execute_transactions(&txns)
+        .await
+        .unwrap();
+        assert_eq!(outcome, SubmissionOutcome {
+            accepted: 3,
+            rejected: 0,
+            throttled: 2,
+        });
+        assert!(outcome.is_throttled());
+
+        let outcome = MockSubmitter::default()
+            .execute_transactions(&txns)
+            .await
+            .unwrap();
+        assert_eq!(outcome, SubmissionOutcome::all_accepted(5));
+        assert!(!outcome.is_throttled());
+    }
+
+    #[tokio::test]
+    async fn test_committed_hashes() {
+        let txns = transfers(3);
+
+        let outcome = SubmissionOutcome {
+            accepted: 2,
+            rejected: 0,
+            throttled: 1,
+        };
+
+        // The hashes of the committed transactions are returned along with the outcome.
+        let result = MockSubmitter {
+            max_accepted: Some(2),
+            track_hashes: true,
+        }
+        .execute_transactions_with_hashes(&txns)
+        .await
+        .unwrap();
+        assert_eq!(result, ExecutionResult {
+            outcome,
+            committed_hashes: vec![
+                txns[0].clone().committed_hash(),
+                txns[1].clone().committed_hash(),
+            ],
+        });
+
+        // Submitters that don't track hashes return none, along with the outcome.
+        let result = MockSubmitter {
+            max_accepted: Some(2),
+            track_hashes: false,
+        }
+        .execute_transactions_with_hashes(&txns)
+        .await
+        .unwrap();
+        assert_eq!(result, ExecutionResult {
+            outcome,
+            committed_hashes: vec![],
+        });
+    }
+}
@@ -15,9 +15,44 @@ use rand::{
 };
 use std::{cmp::max, sync::Arc};
 
+/// Distribution of the amount of coins transferred by each transaction.
+#[derive(Clone, Debug)]
+pub enum AmountDistribution {
+    /// Every transfer sends the same amount.
+    Fixed(u64),
+    /// Amounts are drawn uniformly from the (inclusive) range.
+    Uniform { min: u64, max: u64 },
+    /// Amounts are drawn from the list, each with probability proportional to its weight
+    /// (e.g. mostly dust transfers, with occasional whale transfers).
+    Weighted(Vec<(u64, u64)>),
+}
+
+impl AmountDistribution {
+    pub fn sample(&self, rng: &mut impl Rng) -> u64 {
+        match self {
+            AmountDistribution::Fixed(amount) => *amount,
+            AmountDistribution::Uniform { min, max } => {
+                rand::distributions::Uniform::new_inclusive(min, max).sample(rng)
+            },
+            AmountDistribution::Weighted(amounts) => {
+                let total_weight: u64 = amounts.iter().map(|(_, weight)| weight).sum();
+                assert!(total_weight > 0, "Amount weights must not all be 0");
+                let mut picked = rng.gen_range(0, total_weight);
+                for (amount, weight) in amounts {
+                    if picked < *weight {
+                        return *amount;
+                    }
+                    picked -= weight;
+                }
+                unreachable!("Picked weight is below the total weight")
+            },
+        }
+    }
+}
+
 pub struct P2PTransactionGenerator {
     rng: StdRng,
-    send_amount: u64,
+    amount_distribution: AmountDistribution,
     txn_factory: TransactionFactory,
     all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
     invalid_transaction_ratio: usize,
@@ -26,20 +61,36 @@ pub struct P2PTransactionGenerator {
 impl P2PTransactionGenerator {
     pub fn new(
         rng: StdRng,
-        send_amount: u64,
+        amount_distribution: AmountDistribution,
         txn_factory: TransactionFactory,
         all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
         invalid_transaction_ratio: usize,
     ) -> Self {
         Self {
             rng,
-            send_amount,
+            amount_distribution,
             txn_factory,
             all_addresses,
             invalid_transaction_ratio,
         }
     }
 
+    fn choose_receivers(&mut self, num_to_create: usize) -> Vec<AccountAddress> {
+        let receivers = self
+            .all_addresses
+            .read()
+            .choose_multiple(&mut self.rng, num_to_create)
+            .cloned()
+            .collect::<Vec<_>>();
+        assert!(
+            receivers.len() >= num_to_create,
+            "failed: {} >= {}",
+            receivers.len(),
+            num_to_create
+        );
+        receivers
+    }
+
     fn gen_single_txn(
         &self,
         from: &mut LocalAccount,
@@ -61,29 +112,27 @@ impl P2PTransactionGenerator {
     ) -> SignedTransaction {
         let mut invalid_account = LocalAccount::generate(rng);
         let invalid_address = invalid_account.address();
+        let send_amount = self.amount_distribution.sample(rng);
         match Standard.sample(rng) {
             InvalidTransactionType::ChainId => {
                 let txn_factory = &self.txn_factory.clone().with_chain_id(ChainId::new(255));
-                self.gen_single_txn(sender, receiver, self.send_amount, txn_factory)
+                self.gen_single_txn(sender, receiver, send_amount, txn_factory)
             },
             InvalidTransactionType::Sender => self.gen_single_txn(
                 &mut invalid_account,
                 receiver,
-                self.send_amount,
-                &self.txn_factory,
-            ),
-            InvalidTransactionType::Receiver => self.gen_single_txn(
-                sender,
-                &invalid_address,
-                self.send_amount,
+                send_amount,
                 &self.txn_factory,
             ),
+            InvalidTransactionType::Receiver => {
+                self.gen_single_txn(sender, &invalid_address, send_amount, &self.txn_factory)
+            },
             InvalidTransactionType::Duplication => {
                 // if this is the first tx, default to generate invalid tx with wrong chain id
                 // otherwise, make a duplication of an exist valid tx
                 if reqs.is_empty() {
                     let txn_factory = &self.txn_factory.clone().with_chain_id(ChainId::new(255));
-                    self.gen_single_txn(sender, receiver, self.send_amount, txn_factory)
+                    self.gen_single_txn(sender, receiver, send_amount, txn_factory)
                 } else {
                     let random_index = rng.gen_range(0, reqs.len());
                     reqs[random_index].clone()
@@ -131,23 +180,13 @@ impl TransactionGenerator for P2PTransactionGenerator {
         };
         let mut num_valid_tx = num_to_create * (1 - invalid_size);
 
-        let receivers = self
-            .all_addresses
-            .read()
-            .choose_multiple(&mut self.rng, num_to_create)
-            .cloned()
-            .collect::<Vec<_>>();
-        assert!(
-            receivers.len() >= num_to_create,
-            "failed: {} >= {}",
-            receivers.len(),
-            num_to_create
-        );
+        let receivers = self.choose_receivers(num_to_create);
         for i in 0..num_to_create {
             let receiver = receivers.get(i).expect("all_addresses can't be empty");
             let request = if num_valid_tx > 0 {
                 num_valid_tx -= 1;
-                self.gen_single_txn(account, receiver, self.send_amount, &self.txn_factory)
+                let send_amount = self.amount_distribution.sample(&mut self.rng);
+                self.gen_single_txn(account, receiver, send_amount, &self.txn_factory)
             } else {
                 self.generate_invalid_transaction(
                     &mut self.rng.clone(),
@@ -160,11 +199,32 @@ impl TransactionGenerator for P2PTransactionGenerator {
         }
         requests
     }
+
+    fn generate_transactions_lazily<'a>(
+        &'a mut self,
+        account: &'a mut LocalAccount,
+        num_to_create: usize,
+    ) -> Box<dyn Iterator<Item = SignedTransaction> + 'a> {
+        if self.invalid_transaction_ratio != 0 {
+            // Invalid transactions can duplicate the previously created ones,
+            // so they are all created upfront.
+            return Box::new(
+                self.generate_transactions(account, num_to_create)
+                    .into_iter(),
+            );
+        }
+
+        let receivers = self.choose_receivers(num_to_create);
+        Box::new(receivers.into_iter().map(move |receiver| {
+            let send_amount = self.amount_distribution.sample(&mut self.rng);
+            self.gen_single_txn(account, &receiver, send_amount, &self.txn_factory)
+        }))
+    }
 }
 
 pub struct P2PTransactionGeneratorCreator {
     txn_factory: TransactionFactory,
-    amount: u64,
+    amount_distribution: AmountDistribution,
     all_addresses: Arc<RwLock<Vec<AccountAddress>>>,
     invalid_transaction_ratio: usize,
 }
@@ -178,21 +238,103 @@ impl P2PTransactionGeneratorCreator {
     ) -> Self {
         Self {
             txn_factory,
-            amount,
+            amount_distribution: AmountDistribution::Fixed(amount),
             all_addresses,
             invalid_transaction_ratio,
         }
     }
+
+    /// Draws the amount of each transfer from the given distribution, instead of always
+    /// sending the same amount.
+    pub fn amount_distribution(mut self, amount_distribution: AmountDistribution) -> Self {
+        self.amount_distribution = amount_distribution;
+        self
+    }
 }
 
 impl TransactionGeneratorCreator for P2PTransactionGeneratorCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
         Box::new(P2PTransactionGenerator::new(
             StdRng::from_entropy(),
-            self.amount,
+            self.amount_distribution.clone(),
             self.txn_factory.clone(),
             self.all_addresses.clone(),
             self.invalid_transaction_ratio,
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::{bcs, types::transaction::TransactionPayload};
+
+    #[test]
+    fn test_generate_transactions_lazily() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let all_addresses = (0..10).map(|_| AccountAddress::random()).collect();
+        let mut generator = P2PTransactionGenerator::new(
+            rng,
+            AmountDistribution::Fixed(1),
+            TransactionFactory::new(ChainId::test()),
+            Arc::new(RwLock::new(all_addresses)),
+            0,
+        );
+
+        // Every signed transaction increments the sequence number of the account.
+        let mut txns = generator.generate_transactions_lazily(&mut account, 5);
+        assert_eq!(txns.next().unwrap().sequence_number(), 0);
+        assert_eq!(txns.next().unwrap().sequence_number(), 1);
+        drop(txns);
+        assert_eq!(account.sequence_number(), 2);
+
+        let txns: Vec<_> = generator
+            .generate_transactions_lazily(&mut account, 5)
+            .collect();
+        assert_eq!(txns.len(), 5);
+        assert_eq!(account.sequence_number(), 7);
+    }
+
+    fn transfer_amount(txn: &SignedTransaction) -> u64 {
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                bcs::from_bytes(&entry_function.args()[1]).unwrap()
+            },
+            _ => panic!("Transfer must be an entry function"),
+        }
+    }
+
+    #[test]
+    fn test_amount_distribution() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let all_addresses = (0..100).map(|_| AccountAddress::random()).collect();
+        let mut creator = P2PTransactionGeneratorCreator::new(
+            TransactionFactory::new(ChainId::test()),
+            1,
+            Arc::new(RwLock::new(all_addresses)),
+            0,
+        )
+        .amount_distribution(AmountDistribution::Uniform { min: 10, max: 20 });
+        let mut generator = creator.create_transaction_generator();
+
+        let amounts: Vec<u64> = generator
+            .generate_transactions(&mut account, 100)
+            .iter()
+            .map(transfer_amount)
+            .collect();
+        assert!(amounts.iter().all(|amount| (10..=20).contains(amount)));
+        assert!(amounts.iter().any(|amount| *amount != amounts[0]));
+
+        let weighted = AmountDistribution::Weighted(vec![(1, 1), (1_000_000, 0), (5, 3)]);
+        for _ in 0..100 {
+            assert!([1, 5].contains(&weighted.sample(&mut rng)));
+        }
+        let full_range = AmountDistribution::Uniform {
+            min: u64::MAX - 1,
+            max: u64::MAX,
+        };
+        assert!(full_range.sample(&mut rng) >= u64::MAX - 1);
+    }
+}
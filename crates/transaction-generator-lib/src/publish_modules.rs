@@ -1,20 +1,30 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
-    publishing::publish_util::PackageHandler, TransactionGenerator, TransactionGeneratorCreator,
+    publishing::publish_util::{Package, PackageHandler},
+    TransactionGenerator, TransactionGeneratorCreator,
 };
 use aptos_infallible::RwLock;
 use aptos_sdk::{
+    bcs,
     transaction_builder::TransactionFactory,
-    types::{transaction::SignedTransaction, LocalAccount},
+    types::{
+        transaction::{Script, SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
 };
-use rand::{rngs::StdRng, SeedableRng};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use std::sync::Arc;
 
+/// Number of attempts at adjusting the padding of a package to reach the target transaction
+/// size (length prefixes grow with the padding, so the first guess can be a few bytes off).
+const MAX_PADDING_ROUNDS: usize = 4;
+
 pub struct PublishPackageGenerator {
     rng: StdRng,
     package_handler: Arc<RwLock<PackageHandler>>,
     txn_factory: TransactionFactory,
+    target_txn_size_bytes: Option<(usize, usize)>,
 }
 
 impl PublishPackageGenerator {
@@ -27,7 +37,43 @@ impl PublishPackageGenerator {
             rng,
             package_handler,
             txn_factory,
+            target_txn_size_bytes: None,
+        }
+    }
+
+    /// Pads the published packages so that the serialized size of each publish transaction
+    /// is drawn uniformly from the given (inclusive) range. Packages that are already larger
+    /// than the drawn size are not padded.
+    pub fn target_txn_size_bytes(mut self, target_txn_size_bytes: (usize, usize)) -> Self {
+        self.target_txn_size_bytes = Some(target_txn_size_bytes);
+        self
+    }
+
+    // Size of a signed transaction, excluding its payload (it doesn't depend on the sender
+    // nor on the payload, as all the other fields have a fixed size).
+    fn signed_txn_overhead(&self) -> usize {
+        let mut account = LocalAccount::generate(&mut StdRng::seed_from_u64(0));
+        let payload = TransactionPayload::Script(Script::new(vec![], vec![], vec![]));
+        let txn = account.sign_with_transaction_builder(self.txn_factory.payload(payload.clone()));
+        bcs::serialized_size(&txn).expect("Transaction must serialize")
+            - bcs::serialized_size(&payload).expect("Payload must serialize")
+    }
+
+    fn pad_package(&mut self, package: &mut Package, (min_size, max_size): (usize, usize)) {
+        let target_size = self.rng.gen_range(min_size, max_size + 1);
+        let overhead = self.signed_txn_overhead();
+        let mut padding = 0;
+        for _ in 0..MAX_PADDING_ROUNDS {
+            let mut padded = package.clone();
+            padded.pad(padding);
+            let size = overhead
+                + bcs::serialized_size(&padded.publish_payload()).expect("Payload must serialize");
+            if size == target_size || (size > target_size && padding == 0) {
+                break;
+            }
+            padding = (padding + target_size).saturating_sub(size);
         }
+        package.pad(padding);
     }
 }
 
@@ -40,10 +86,13 @@ impl TransactionGenerator for PublishPackageGenerator {
         let mut requests = Vec::with_capacity(num_to_create);
 
         // First publish the module and then use it
-        let package = self
+        let mut package = self
             .package_handler
             .write()
             .pick_package(&mut self.rng, account);
+        if let Some(target_txn_size_bytes) = self.target_txn_size_bytes {
+            self.pad_package(&mut package, target_txn_size_bytes);
+        }
         let txn = package.publish_transaction(account, &self.txn_factory);
         requests.push(txn);
         // use module published
@@ -66,6 +115,7 @@ impl TransactionGenerator for PublishPackageGenerator {
 pub struct PublishPackageCreator {
     txn_factory: TransactionFactory,
     package_handler: Arc<RwLock<PackageHandler>>,
+    target_txn_size_bytes: Option<(usize, usize)>,
 }
 
 impl PublishPackageCreator {
@@ -73,16 +123,73 @@ impl PublishPackageCreator {
         Self {
             txn_factory,
             package_handler: Arc::new(RwLock::new(PackageHandler::new("simple"))),
+            target_txn_size_bytes: None,
+        }
+    }
+
+    /// Publishes synthetically large modules, with `module_size_functions` additional
+    /// functions each, for benchmarking publishing gas and bytecode verification.
+    pub fn new_with_large_modules(
+        txn_factory: TransactionFactory,
+        module_size_functions: usize,
+    ) -> Self {
+        Self {
+            txn_factory,
+            package_handler: Arc::new(RwLock::new(PackageHandler::new_with_module_size(
+                "simple",
+                module_size_functions,
+            ))),
+            target_txn_size_bytes: None,
         }
     }
+
+    /// Pads the published packages with unused data, so that publish transactions have a
+    /// serialized size within the given (inclusive) range, e.g. to stress size dependent
+    /// behavior of mempool and consensus (see PublishPackageGenerator::target_txn_size_bytes).
+    pub fn target_txn_size_bytes(mut self, target_txn_size_bytes: (usize, usize)) -> Self {
+        self.target_txn_size_bytes = Some(target_txn_size_bytes);
+        self
+    }
 }
 
 impl TransactionGeneratorCreator for PublishPackageCreator {
     fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
-        Box::new(PublishPackageGenerator::new(
+        let generator = PublishPackageGenerator::new(
             StdRng::from_entropy(),
             self.package_handler.clone(),
             self.txn_factory.clone(),
-        ))
+        );
+        Box::new(match self.target_txn_size_bytes {
+            Some(target_txn_size_bytes) => generator.target_txn_size_bytes(target_txn_size_bytes),
+            None => generator,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::types::chain_id::ChainId;
+
+    #[test]
+    fn test_target_txn_size() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let (min_size, max_size) = (100_000, 101_000);
+        let mut creator = PublishPackageCreator::new(TransactionFactory::new(ChainId::test()))
+            .target_txn_size_bytes((min_size, max_size));
+        let mut generator = creator.create_transaction_generator();
+
+        for _ in 0..5 {
+            // Every account publishes a new (randomly scrambled) package.
+            let mut account = LocalAccount::generate(&mut rng);
+            let txns = generator.generate_transactions(&mut account, 1);
+            assert_eq!(txns.len(), 1);
+            let size = bcs::to_bytes(&txns[0]).unwrap().len();
+            assert!(
+                (min_size..=max_size).contains(&size),
+                "size {} out of bounds",
+                size
+            );
+        }
     }
 }
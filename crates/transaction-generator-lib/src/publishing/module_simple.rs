@@ -12,12 +12,15 @@ use aptos_sdk::{
     types::transaction::{EntryFunction, TransactionPayload},
 };
 use move_binary_format::{
-    file_format::{FunctionHandleIndex, IdentifierIndex, SignatureToken},
+    file_format::{Constant, FunctionHandleIndex, IdentifierIndex, SignatureToken},
     CompiledModule,
 };
 use rand::{distributions::Alphanumeric, prelude::StdRng, seq::SliceRandom, Rng};
 use rand_core::RngCore;
 
+// Maximum length of a padding constant, below CONSTANT_SIZE_MAX (including the length prefix).
+const MAX_PADDING_CONSTANT_LEN: usize = 60_000;
+
 //
 // Contains all the code to work on the Simple package
 //
@@ -55,6 +58,12 @@ pub fn scramble(module: &mut CompiledModule, fn_count: usize, rng: &mut StdRng)
         }
     }
 
+    duplicate_copy_pasta(module, fn_count);
+}
+
+// Deterministically grows the module by adding `fn_count` copies of the
+// copy_pasta* function in Simple.move.
+pub fn duplicate_copy_pasta(module: &mut CompiledModule, fn_count: usize) {
     // find the copy_pasta* function in Simple.move
     let mut def = None;
     let mut handle = None;
@@ -86,6 +95,21 @@ pub fn scramble(module: &mut CompiledModule, fn_count: usize, rng: &mut StdRng)
     }
 }
 
+// Grows the module by `padding` bytes (plus a few bytes of length prefixes), by adding unused
+// vector<u8> constants to the constant pool. The behavior of the module is unchanged.
+pub fn add_padding_constants(module: &mut CompiledModule, padding: usize) {
+    // Constants are limited to CONSTANT_SIZE_MAX bytes, so large paddings are split.
+    let mut remaining = padding;
+    while remaining > 0 {
+        let len = remaining.min(MAX_PADDING_CONSTANT_LEN);
+        module.constant_pool.push(Constant {
+            type_: SignatureToken::Vector(Box::new(SignatureToken::U8)),
+            data: bcs::to_bytes(&vec![0u8; len]).expect("U8 vector must serialize"),
+        });
+        remaining -= len;
+    }
+}
+
 pub enum MultiSigConfig {
     None,
     Random(usize),
@@ -116,10 +140,18 @@ pub enum EntryPoints {
     /// Half the size of `Resource`
     Half,
     // 1 arg
+    /// Always abort with the given code
+    Abort {
+        code: u64,
+    },
     /// run a for loop
     Loopy {
         loop_count: Option<u64>,
     },
+    /// Run a tight arithmetic loop, with no storage access
+    Loop {
+        iterations: u64,
+    },
     /// Return value from constant array (RANDOM)
     GetFromConst {
         const_idx: Option<u64>,
@@ -181,7 +213,9 @@ impl EntryPoints {
             | EntryPoints::ResetData
             | EntryPoints::Double
             | EntryPoints::Half
+            | EntryPoints::Abort { .. }
             | EntryPoints::Loopy { .. }
+            | EntryPoints::Loop { .. }
             | EntryPoints::GetFromConst { .. }
             | EntryPoints::SetId
             | EntryPoints::SetName
@@ -214,7 +248,9 @@ impl EntryPoints {
             | EntryPoints::ResetData
             | EntryPoints::Double
             | EntryPoints::Half
+            | EntryPoints::Abort { .. }
             | EntryPoints::Loopy { .. }
+            | EntryPoints::Loop { .. }
             | EntryPoints::GetFromConst { .. }
             | EntryPoints::SetId
             | EntryPoints::SetName
@@ -262,11 +298,13 @@ impl EntryPoints {
             EntryPoints::Double => get_payload_void(module_id, ident_str!("double").to_owned()),
             EntryPoints::Half => get_payload_void(module_id, ident_str!("half").to_owned()),
             // 1 arg
+            EntryPoints::Abort { code } => abort_with_code(module_id, *code),
             EntryPoints::Loopy { loop_count } => loopy(
                 module_id,
                 loop_count
                     .unwrap_or_else(|| rng.expect("Must provide RNG").gen_range(0u64, 1000u64)),
             ),
+            EntryPoints::Loop { iterations } => arithmetic_loop(module_id, *iterations),
             EntryPoints::GetFromConst { const_idx } => get_from_random_const(
                 module_id,
                 const_idx.unwrap_or_else(
@@ -468,12 +506,24 @@ pub fn rand_gen_function(rng: &mut StdRng, module_id: ModuleId) -> TransactionPa
 // Entry points payload
 //
 
+fn abort_with_code(module_id: ModuleId, code: u64) -> TransactionPayload {
+    get_payload(module_id, ident_str!("abort_with_code").to_owned(), vec![
+        bcs::to_bytes(&code).unwrap(),
+    ])
+}
+
 fn loopy(module_id: ModuleId, count: u64) -> TransactionPayload {
     get_payload(module_id, ident_str!("loopy").to_owned(), vec![
         bcs::to_bytes(&count).unwrap(),
     ])
 }
 
+fn arithmetic_loop(module_id: ModuleId, iterations: u64) -> TransactionPayload {
+    get_payload(module_id, ident_str!("arithmetic_loop").to_owned(), vec![
+        bcs::to_bytes(&iterations).unwrap(),
+    ])
+}
+
 fn get_from_random_const(module_id: ModuleId, idx: u64) -> TransactionPayload {
     get_payload(
         module_id,
@@ -566,3 +616,35 @@ fn get_payload_void(module_id: ModuleId, func: Identifier) -> TransactionPayload
 fn get_payload(module_id: ModuleId, func: Identifier, args: Vec<Vec<u8>>) -> TransactionPayload {
     TransactionPayload::EntryFunction(EntryFunction::new(module_id, func, vec![], args))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_abort_payload() {
+        let module_id = ModuleId::new(AccountAddress::ONE, ident_str!("simple").to_owned());
+        let payload = EntryPoints::Abort { code: 42 }.create_payload(module_id.clone(), None, None);
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(entry_function.module(), &module_id);
+                assert_eq!(entry_function.function().as_str(), "abort_with_code");
+                assert_eq!(entry_function.args(), &[bcs::to_bytes(&42u64).unwrap()]);
+            },
+            _ => panic!("Expected an entry function payload"),
+        }
+    }
+
+    #[test]
+    fn test_loop_payload() {
+        let module_id = ModuleId::new(AccountAddress::ONE, ident_str!("simple").to_owned());
+        let payload = EntryPoints::Loop { iterations: 1000 }.create_payload(module_id, None, None);
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(entry_function.function().as_str(), "arithmetic_loop");
+                assert_eq!(entry_function.args(), &[bcs::to_bytes(&1000u64).unwrap()]);
+            },
+            _ => panic!("Expected an entry function payload"),
+        }
+    }
+}
@@ -7,7 +7,11 @@ use aptos_sdk::{
     bcs,
     move_types::{identifier::Identifier, language_storage::ModuleId},
     transaction_builder::{aptos_stdlib, TransactionFactory},
-    types::{account_address::AccountAddress, transaction::SignedTransaction, LocalAccount},
+    types::{
+        account_address::AccountAddress,
+        transaction::{SignedTransaction, TransactionPayload},
+        LocalAccount,
+    },
 };
 use move_binary_format::{access::ModuleAccess, CompiledModule};
 use rand::{rngs::StdRng, Rng};
@@ -45,6 +49,9 @@ impl PackageTracker {
 pub struct PackageHandler {
     packages: Vec<PackageTracker>,
     is_simple: bool,
+    // If set, the package is grown to the given number of (additional) functions,
+    // instead of being randomly scrambled.
+    module_size_functions: Option<usize>,
 }
 
 impl Default for PackageHandler {
@@ -63,6 +70,16 @@ impl PackageHandler {
         PackageHandler {
             packages,
             is_simple: name == "simple",
+            module_size_functions: None,
+        }
+    }
+
+    // Same as `new`, but every published package is deterministically grown to
+    // `module_size_functions` additional functions, to stress the bytecode verifier.
+    pub fn new_with_module_size(name: &str, module_size_functions: usize) -> Self {
+        Self {
+            module_size_functions: Some(module_size_functions),
+            ..Self::new(name)
         }
     }
 
@@ -100,7 +117,10 @@ impl PackageHandler {
             if version {
                 package.version(rng);
             }
-            package.scramble(tracker.publishers[idx].fn_count, rng);
+            match self.module_size_functions {
+                Some(fn_count) => package.inflate(fn_count),
+                None => package.scramble(tracker.publishers[idx].fn_count, rng),
+            }
         }
         // info!("PACKAGE: {:#?}", package);
         package
@@ -158,16 +178,29 @@ impl Package {
         module_simple::scramble(self.get_mut_module("simple"), fn_count, rng)
     }
 
+    // Deterministically grows the package by `fn_count` functions
+    pub fn inflate(&mut self, fn_count: usize) {
+        module_simple::duplicate_copy_pasta(self.get_mut_module("simple"), fn_count)
+    }
+
+    // Grows the package by `padding` bytes, with an unused constant
+    pub fn pad(&mut self, padding: usize) {
+        module_simple::add_padding_constants(self.get_mut_module("simple"), padding)
+    }
+
     // Return a transaction to publish the current package
     pub fn publish_transaction(
         &self,
         publisher: &mut LocalAccount,
         txn_factory: &TransactionFactory,
     ) -> SignedTransaction {
+        publisher.sign_with_transaction_builder(txn_factory.payload(self.publish_payload()))
+    }
+
+    // Return the payload to publish the current package
+    pub fn publish_payload(&self) -> TransactionPayload {
         match self {
-            Self::Simple(modules, metadata) => {
-                publish_transaction(txn_factory, publisher, modules, metadata)
-            },
+            Self::Simple(modules, metadata) => publish_payload(modules, metadata),
         }
     }
 
@@ -257,12 +290,10 @@ fn update(
     (new_modules, metadata)
 }
 
-fn publish_transaction(
-    txn_factory: &TransactionFactory,
-    publisher: &mut LocalAccount,
+fn publish_payload(
     modules: &[(String, CompiledModule)],
     metadata: &PackageMetadata,
-) -> SignedTransaction {
+) -> TransactionPayload {
     let metadata = bcs::to_bytes(metadata).expect("PackageMetadata must serialize");
     let mut code: Vec<Vec<u8>> = vec![];
     for (_, module) in modules {
@@ -272,6 +303,37 @@ fn publish_transaction(
             .expect("Module must serialize");
         code.push(module_code);
     }
-    let payload = aptos_stdlib::code_publish_package_txn(metadata, code);
-    publisher.sign_with_transaction_builder(txn_factory.payload(payload))
+    aptos_stdlib::code_publish_package_txn(metadata, code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::types::chain_id::ChainId;
+    use rand::SeedableRng;
+
+    fn publish_payload_size(module_size_functions: usize) -> usize {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut publisher = LocalAccount::generate(&mut rng);
+        let mut package_handler =
+            PackageHandler::new_with_module_size("simple", module_size_functions);
+        let package = package_handler.pick_package(&mut rng, &mut publisher);
+        let txn =
+            package.publish_transaction(&mut publisher, &TransactionFactory::new(ChainId::test()));
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                entry_function.args().iter().map(|arg| arg.len()).sum()
+            },
+            _ => panic!("Publishing must be an entry function"),
+        }
+    }
+
+    #[test]
+    fn test_large_module_size() {
+        let small = publish_payload_size(10);
+        let large = publish_payload_size(100);
+        assert!(large > small);
+        // Module bytes only depend on the size parameter.
+        assert_eq!(publish_payload_size(100), large);
+    }
 }
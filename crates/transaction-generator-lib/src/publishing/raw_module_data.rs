@@ -19,10 +19,10 @@ use std::collections::HashMap;
 pub static PACKAGE_SIMPLE_METADATA: Lazy<Vec<u8>> = Lazy::new(|| {
 	vec![
 		13, 71, 101, 110, 101, 114, 105, 99, 77, 111, 100, 117, 108, 101, 1, 0, 0, 0,
-		0, 0, 0, 0, 0, 64, 66, 51, 69, 68, 51, 54, 54, 50, 70, 54, 65, 65,
-		66, 52, 70, 65, 68, 49, 51, 51, 56, 70, 68, 48, 56, 54, 65, 68, 68, 52,
-		52, 67, 51, 55, 51, 70, 67, 65, 52, 48, 49, 52, 52, 52, 55, 57, 70, 66,
-		51, 50, 50, 70, 67, 68, 57, 48, 70, 65, 51, 57, 51, 49, 48, 69, 132, 1,
+		0, 0, 0, 0, 0, 64, 55, 51, 55, 48, 55, 48, 65, 69, 49, 70, 54, 65,
+		48, 53, 52, 50, 65, 65, 55, 56, 56, 48, 65, 70, 66, 68, 69, 50, 55, 55,
+		65, 67, 54, 70, 68, 49, 66, 56, 49, 67, 49, 68, 54, 57, 54, 49, 53, 52,
+		56, 53, 53, 55, 70, 70, 57, 67, 65, 67, 55, 51, 52, 70, 51, 65, 132, 1,
 		31, 139, 8, 0, 0, 0, 0, 0, 2, 255, 77, 139, 59, 14, 194, 48, 16, 68,
 		251, 61, 133, 229, 30, 135, 11, 80, 208, 64, 197, 9, 162, 20, 43, 123, 64, 86,
 		156, 93, 203, 134, 80, 32, 238, 142, 45, 1, 138, 102, 154, 249, 188, 49, 179, 159,
@@ -45,174 +45,182 @@ pub static PACKAGE_SIMPLE_METADATA: Lazy<Vec<u8>> = Lazy::new(|| {
 #[rustfmt::skip]
 pub static MODULE_SIMPLE_SIMPLE: Lazy<Vec<u8>> = Lazy::new(|| {
 	vec![
-		161, 28, 235, 11, 6, 0, 0, 0, 12, 1, 0, 14, 2, 14, 46, 3, 60, 151,
-		1, 4, 211, 1, 8, 5, 219, 1, 240, 1, 7, 203, 3, 168, 4, 8, 243, 7,
-		64, 6, 179, 8, 115, 16, 166, 9, 62, 10, 228, 9, 51, 12, 151, 10, 220, 12,
-		13, 243, 22, 16, 0, 0, 1, 1, 1, 2, 1, 3, 1, 4, 1, 5, 1, 6,
+		161, 28, 235, 11, 6, 0, 0, 0, 12, 1, 0, 14, 2, 14, 46, 3, 60, 161,
+		1, 4, 221, 1, 8, 5, 229, 1, 240, 1, 7, 213, 3, 200, 4, 8, 157, 8,
+		64, 6, 221, 8, 115, 16, 208, 9, 62, 10, 142, 10, 51, 12, 193, 10, 192, 13,
+		13, 129, 24, 16, 0, 0, 1, 1, 1, 2, 1, 3, 1, 4, 1, 5, 1, 6,
 		0, 7, 8, 0, 0, 8, 8, 0, 0, 9, 7, 0, 0, 10, 8, 0, 0, 11,
-		8, 0, 0, 12, 6, 0, 0, 13, 8, 0, 5, 24, 7, 0, 3, 40, 4, 1,
-		6, 1, 6, 45, 4, 2, 3, 1, 0, 1, 0, 14, 0, 1, 0, 0, 15, 2,
-		1, 0, 0, 16, 3, 4, 0, 0, 17, 5, 1, 0, 0, 18, 6, 1, 0, 0,
-		19, 5, 1, 0, 0, 20, 6, 1, 0, 0, 21, 5, 1, 0, 0, 22, 5, 1,
-		0, 0, 23, 6, 1, 0, 0, 25, 7, 1, 0, 0, 26, 8, 1, 0, 0, 27,
-		9, 1, 0, 0, 28, 9, 1, 0, 0, 29, 5, 1, 0, 0, 30, 10, 1, 0,
-		0, 31, 11, 1, 0, 0, 32, 5, 1, 0, 0, 33, 6, 1, 0, 0, 34, 12,
-		1, 0, 0, 35, 9, 1, 0, 0, 36, 5, 1, 0, 4, 46, 5, 16, 0, 5,
-		47, 19, 20, 0, 1, 48, 5, 23, 1, 6, 3, 49, 24, 1, 1, 6, 6, 50,
-		1, 29, 2, 3, 4, 6, 51, 30, 31, 2, 3, 2, 2, 52, 13, 13, 0, 24,
-		22, 25, 22, 26, 28, 27, 28, 2, 7, 10, 2, 6, 10, 2, 0, 2, 6, 12,
-		10, 2, 4, 6, 8, 4, 6, 8, 4, 6, 8, 1, 6, 8, 1, 1, 6, 3,
-		1, 6, 12, 2, 6, 12, 3, 4, 6, 12, 3, 8, 7, 10, 2, 3, 6, 12,
-		3, 3, 2, 6, 12, 5, 2, 6, 12, 6, 12, 5, 6, 12, 6, 12, 6, 12,
-		6, 12, 6, 12, 2, 6, 12, 8, 7, 1, 3, 1, 2, 2, 7, 8, 0, 8,
-		0, 1, 5, 3, 6, 3, 6, 3, 6, 3, 3, 3, 8, 4, 7, 8, 4, 1,
-		10, 2, 1, 8, 7, 2, 7, 8, 3, 5, 1, 8, 5, 1, 11, 8, 1, 9,
-		0, 2, 7, 11, 8, 1, 9, 0, 9, 0, 3, 10, 3, 10, 3, 3, 3, 8,
-		2, 7, 8, 4, 8, 4, 3, 5, 7, 11, 9, 2, 3, 3, 7, 3, 2, 3,
-		3, 1, 11, 9, 2, 9, 0, 9, 1, 3, 7, 11, 9, 2, 9, 0, 9, 1,
-		9, 0, 9, 1, 1, 7, 9, 1, 8, 1, 10, 2, 7, 8, 4, 10, 2, 3,
-		3, 8, 4, 7, 8, 4, 9, 3, 7, 8, 4, 3, 3, 3, 8, 4, 7, 8,
-		4, 6, 8, 4, 6, 8, 4, 2, 7, 8, 4, 8, 4, 2, 8, 4, 7, 8,
-		4, 3, 7, 8, 1, 7, 8, 1, 3, 1, 7, 8, 1, 6, 115, 105, 109, 112,
-		108, 101, 7, 97, 99, 99, 111, 117, 110, 116, 5, 101, 114, 114, 111, 114, 5, 101,
-		118, 101, 110, 116, 6, 115, 105, 103, 110, 101, 114, 6, 115, 116, 114, 105, 110, 103,
-		5, 116, 97, 98, 108, 101, 12, 66, 121, 116, 101, 82, 101, 115, 111, 117, 114, 99,
-		101, 7, 67, 111, 117, 110, 116, 101, 114, 4, 68, 97, 116, 97, 10, 69, 118, 101,
-		110, 116, 83, 116, 111, 114, 101, 8, 82, 101, 115, 111, 117, 114, 99, 101, 11, 83,
-		105, 109, 112, 108, 101, 69, 118, 101, 110, 116, 10, 84, 97, 98, 108, 101, 83, 116,
-		111, 114, 101, 11, 97, 112, 112, 101, 110, 100, 95, 100, 97, 116, 97, 20, 98, 121,
-		116, 101, 115, 95, 109, 97, 107, 101, 95, 111, 114, 95, 99, 104, 97, 110, 103, 101,
-		14, 99, 111, 112, 121, 95, 112, 97, 115, 116, 97, 95, 114, 101, 102, 6, 100, 111,
-		117, 98, 108, 101, 11, 101, 109, 105, 116, 95, 101, 118, 101, 110, 116, 115, 11, 103,
-		101, 116, 95, 99, 111, 117, 110, 116, 101, 114, 21, 103, 101, 116, 95, 102, 114, 111,
-		109, 95, 114, 97, 110, 100, 111, 109, 95, 99, 111, 110, 115, 116, 4, 104, 97, 108,
-		102, 11, 105, 110, 105, 116, 95, 109, 111, 100, 117, 108, 101, 5, 108, 111, 111, 112,
-		121, 6, 83, 116, 114, 105, 110, 103, 14, 109, 97, 107, 101, 95, 111, 114, 95, 99,
-		104, 97, 110, 103, 101, 20, 109, 97, 107, 101, 95, 111, 114, 95, 99, 104, 97, 110,
-		103, 101, 95, 116, 97, 98, 108, 101, 8, 109, 97, 120, 105, 109, 105, 122, 101, 8,
-		109, 105, 110, 105, 109, 105, 122, 101, 3, 110, 111, 112, 13, 110, 111, 112, 95, 50,
-		95, 115, 105, 103, 110, 101, 114, 115, 13, 110, 111, 112, 95, 53, 95, 115, 105, 103,
-		110, 101, 114, 115, 10, 114, 101, 115, 101, 116, 95, 100, 97, 116, 97, 6, 115, 101,
-		116, 95, 105, 100, 8, 115, 101, 116, 95, 110, 97, 109, 101, 16, 115, 116, 101, 112,
-		95, 100, 101, 115, 116, 105, 110, 97, 116, 105, 111, 110, 11, 115, 116, 101, 112, 95,
-		115, 105, 103, 110, 101, 114, 4, 100, 97, 116, 97, 5, 99, 111, 117, 110, 116, 13,
-		115, 105, 109, 112, 108, 101, 95, 101, 118, 101, 110, 116, 115, 11, 69, 118, 101, 110,
-		116, 72, 97, 110, 100, 108, 101, 2, 105, 100, 4, 110, 97, 109, 101, 8, 101, 118,
-		101, 110, 116, 95, 105, 100, 13, 116, 97, 98, 108, 101, 95, 101, 110, 116, 114, 105,
-		101, 115, 5, 84, 97, 98, 108, 101, 10, 97, 100, 100, 114, 101, 115, 115, 95, 111,
-		102, 4, 117, 116, 102, 56, 16, 110, 101, 119, 95, 101, 118, 101, 110, 116, 95, 104,
-		97, 110, 100, 108, 101, 10, 101, 109, 105, 116, 95, 101, 118, 101, 110, 116, 3, 110,
-		101, 119, 23, 98, 111, 114, 114, 111, 119, 95, 109, 117, 116, 95, 119, 105, 116, 104,
-		95, 100, 101, 102, 97, 117, 108, 116, 16, 105, 110, 118, 97, 108, 105, 100, 95, 97,
-		114, 103, 117, 109, 101, 110, 116, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		8, 0, 0, 12, 6, 0, 0, 13, 8, 0, 5, 26, 7, 0, 3, 42, 4, 1,
+		6, 1, 6, 47, 4, 2, 3, 1, 0, 1, 0, 14, 0, 1, 0, 0, 15, 2,
+		1, 0, 0, 16, 0, 1, 0, 0, 17, 3, 1, 0, 0, 18, 4, 5, 0, 0,
+		19, 6, 1, 0, 0, 20, 0, 1, 0, 0, 21, 6, 1, 0, 0, 22, 0, 1,
+		0, 0, 23, 6, 1, 0, 0, 24, 6, 1, 0, 0, 25, 0, 1, 0, 0, 27,
+		7, 1, 0, 0, 28, 8, 1, 0, 0, 29, 9, 1, 0, 0, 30, 9, 1, 0,
+		0, 31, 6, 1, 0, 0, 32, 10, 1, 0, 0, 33, 11, 1, 0, 0, 34, 6,
+		1, 0, 0, 35, 0, 1, 0, 0, 36, 12, 1, 0, 0, 37, 9, 1, 0, 0,
+		38, 6, 1, 0, 4, 48, 6, 17, 0, 5, 49, 20, 21, 0, 1, 50, 6, 24,
+		1, 6, 3, 51, 25, 1, 1, 6, 6, 52, 1, 29, 2, 3, 4, 6, 53, 30,
+		31, 2, 3, 2, 2, 54, 13, 13, 0, 26, 23, 27, 23, 28, 15, 29, 15, 2,
+		6, 12, 3, 0, 2, 7, 10, 2, 6, 10, 2, 2, 6, 12, 10, 2, 4, 6,
+		8, 4, 6, 8, 4, 6, 8, 1, 6, 8, 1, 1, 6, 3, 1, 6, 12, 4,
+		6, 12, 3, 8, 7, 10, 2, 3, 6, 12, 3, 3, 2, 6, 12, 5, 2, 6,
+		12, 6, 12, 5, 6, 12, 6, 12, 6, 12, 6, 12, 6, 12, 2, 6, 12, 8,
+		7, 1, 3, 1, 2, 2, 3, 3, 2, 7, 8, 0, 8, 0, 1, 5, 3, 6,
+		3, 6, 3, 6, 3, 3, 3, 8, 4, 7, 8, 4, 1, 10, 2, 1, 8, 7,
+		2, 7, 8, 3, 5, 1, 8, 5, 1, 11, 8, 1, 9, 0, 2, 7, 11, 8,
+		1, 9, 0, 9, 0, 3, 10, 3, 10, 3, 3, 3, 8, 2, 7, 8, 4, 8,
+		4, 3, 5, 7, 11, 9, 2, 3, 3, 7, 3, 1, 11, 9, 2, 9, 0, 9,
+		1, 3, 7, 11, 9, 2, 9, 0, 9, 1, 9, 0, 9, 1, 1, 7, 9, 1,
+		8, 1, 10, 2, 7, 8, 4, 10, 2, 3, 3, 8, 4, 7, 8, 4, 9, 3,
+		7, 8, 4, 3, 3, 3, 8, 4, 7, 8, 4, 6, 8, 4, 6, 8, 4, 2,
+		7, 8, 4, 8, 4, 2, 8, 4, 7, 8, 4, 3, 7, 8, 1, 7, 8, 1,
+		3, 1, 7, 8, 1, 6, 115, 105, 109, 112, 108, 101, 7, 97, 99, 99, 111, 117,
+		110, 116, 5, 101, 114, 114, 111, 114, 5, 101, 118, 101, 110, 116, 6, 115, 105, 103,
+		110, 101, 114, 6, 115, 116, 114, 105, 110, 103, 5, 116, 97, 98, 108, 101, 12, 66,
+		121, 116, 101, 82, 101, 115, 111, 117, 114, 99, 101, 7, 67, 111, 117, 110, 116, 101,
+		114, 4, 68, 97, 116, 97, 10, 69, 118, 101, 110, 116, 83, 116, 111, 114, 101, 8,
+		82, 101, 115, 111, 117, 114, 99, 101, 11, 83, 105, 109, 112, 108, 101, 69, 118, 101,
+		110, 116, 10, 84, 97, 98, 108, 101, 83, 116, 111, 114, 101, 15, 97, 98, 111, 114,
+		116, 95, 119, 105, 116, 104, 95, 99, 111, 100, 101, 11, 97, 112, 112, 101, 110, 100,
+		95, 100, 97, 116, 97, 15, 97, 114, 105, 116, 104, 109, 101, 116, 105, 99, 95, 108,
+		111, 111, 112, 20, 98, 121, 116, 101, 115, 95, 109, 97, 107, 101, 95, 111, 114, 95,
+		99, 104, 97, 110, 103, 101, 14, 99, 111, 112, 121, 95, 112, 97, 115, 116, 97, 95,
+		114, 101, 102, 6, 100, 111, 117, 98, 108, 101, 11, 101, 109, 105, 116, 95, 101, 118,
+		101, 110, 116, 115, 11, 103, 101, 116, 95, 99, 111, 117, 110, 116, 101, 114, 21, 103,
+		101, 116, 95, 102, 114, 111, 109, 95, 114, 97, 110, 100, 111, 109, 95, 99, 111, 110,
+		115, 116, 4, 104, 97, 108, 102, 11, 105, 110, 105, 116, 95, 109, 111, 100, 117, 108,
+		101, 5, 108, 111, 111, 112, 121, 6, 83, 116, 114, 105, 110, 103, 14, 109, 97, 107,
+		101, 95, 111, 114, 95, 99, 104, 97, 110, 103, 101, 20, 109, 97, 107, 101, 95, 111,
+		114, 95, 99, 104, 97, 110, 103, 101, 95, 116, 97, 98, 108, 101, 8, 109, 97, 120,
+		105, 109, 105, 122, 101, 8, 109, 105, 110, 105, 109, 105, 122, 101, 3, 110, 111, 112,
+		13, 110, 111, 112, 95, 50, 95, 115, 105, 103, 110, 101, 114, 115, 13, 110, 111, 112,
+		95, 53, 95, 115, 105, 103, 110, 101, 114, 115, 10, 114, 101, 115, 101, 116, 95, 100,
+		97, 116, 97, 6, 115, 101, 116, 95, 105, 100, 8, 115, 101, 116, 95, 110, 97, 109,
+		101, 16, 115, 116, 101, 112, 95, 100, 101, 115, 116, 105, 110, 97, 116, 105, 111, 110,
+		11, 115, 116, 101, 112, 95, 115, 105, 103, 110, 101, 114, 4, 100, 97, 116, 97, 5,
+		99, 111, 117, 110, 116, 13, 115, 105, 109, 112, 108, 101, 95, 101, 118, 101, 110, 116,
+		115, 11, 69, 118, 101, 110, 116, 72, 97, 110, 100, 108, 101, 2, 105, 100, 4, 110,
+		97, 109, 101, 8, 101, 118, 101, 110, 116, 95, 105, 100, 13, 116, 97, 98, 108, 101,
+		95, 101, 110, 116, 114, 105, 101, 115, 5, 84, 97, 98, 108, 101, 10, 97, 100, 100,
+		114, 101, 115, 115, 95, 111, 102, 4, 117, 116, 102, 56, 16, 110, 101, 119, 95, 101,
+		118, 101, 110, 116, 95, 104, 97, 110, 100, 108, 101, 10, 101, 109, 105, 116, 95, 101,
+		118, 101, 110, 116, 3, 110, 101, 119, 23, 98, 111, 114, 114, 111, 119, 95, 109, 117,
+		116, 95, 119, 105, 116, 104, 95, 100, 101, 102, 97, 117, 108, 116, 16, 105, 110, 118,
+		97, 108, 105, 100, 95, 97, 114, 103, 117, 109, 101, 110, 116, 0, 0, 0, 0, 0,
 		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-		0, 171, 205, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 3,
-		8, 1, 0, 0, 0, 0, 0, 0, 0, 10, 2, 9, 8, 1, 35, 69, 103, 137,
-		171, 205, 239, 10, 2, 6, 5, 104, 101, 108, 108, 111, 10, 3, 81, 10, 0, 0,
-		0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 2, 0, 0, 0,
-		0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 4, 0, 0, 0, 0, 0,
-		0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 6, 0, 0, 0, 0, 0, 0, 0,
-		7, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 9, 0,
-		0, 0, 0, 0, 0, 0, 18, 97, 112, 116, 111, 115, 58, 58, 109, 101, 116, 97,
-		100, 97, 116, 97, 95, 118, 49, 42, 1, 1, 0, 0, 0, 0, 0, 0, 0, 29,
-		69, 67, 79, 85, 78, 84, 69, 82, 95, 82, 69, 83, 79, 85, 82, 67, 69, 95,
-		78, 79, 84, 95, 80, 82, 69, 83, 69, 78, 84, 0, 0, 0, 0, 2, 1, 37,
-		10, 2, 1, 2, 1, 38, 3, 2, 2, 1, 37, 10, 2, 3, 2, 1, 39, 11,
-		8, 1, 8, 5, 4, 2, 3, 41, 3, 42, 8, 7, 37, 8, 2, 5, 2, 1,
-		43, 3, 6, 2, 1, 44, 11, 9, 2, 3, 3, 0, 0, 0, 0, 13, 26, 10,
-		1, 65, 14, 12, 2, 10, 2, 6, 0, 0, 0, 0, 0, 0, 0, 0, 36, 4,
-		21, 5, 8, 10, 0, 10, 1, 10, 2, 6, 1, 0, 0, 0, 0, 0, 0, 0,
-		23, 66, 14, 20, 68, 14, 11, 2, 6, 1, 0, 0, 0, 0, 0, 0, 0, 23,
-		12, 2, 5, 3, 11, 1, 1, 11, 0, 1, 2, 1, 1, 4, 1, 0, 15, 20,
-		10, 0, 17, 22, 41, 0, 4, 13, 11, 0, 17, 22, 42, 0, 12, 2, 11, 1,
-		11, 2, 15, 0, 21, 5, 19, 11, 1, 18, 0, 12, 3, 11, 0, 11, 3, 45,
-		0, 2, 2, 0, 0, 0, 17, 103, 10, 0, 16, 1, 12, 5, 10, 1, 16, 1,
-		12, 6, 11, 5, 20, 10, 6, 20, 35, 4, 18, 11, 6, 12, 5, 10, 2, 16,
-		2, 12, 6, 5, 26, 11, 6, 1, 10, 1, 16, 1, 12, 5, 10, 3, 16, 2,
-		12, 6, 10, 6, 20, 10, 1, 16, 1, 20, 35, 4, 47, 11, 5, 1, 11, 1,
-		1, 11, 0, 1, 11, 2, 1, 11, 6, 12, 5, 11, 3, 16, 2, 12, 6, 5,
-		69, 11, 3, 1, 10, 5, 11, 0, 16, 1, 34, 4, 65, 11, 6, 1, 11, 5,
-		1, 11, 2, 16, 2, 12, 5, 11, 1, 16, 1, 12, 6, 5, 69, 11, 1, 1,
-		11, 2, 1, 10, 5, 20, 10, 6, 20, 35, 4, 82, 11, 6, 1, 10, 5, 12,
-		6, 10, 5, 1, 5, 88, 11, 5, 1, 10, 6, 12, 5, 10, 6, 1, 10, 5,
-		10, 6, 33, 4, 97, 11, 6, 1, 11, 5, 12, 4, 5, 101, 11, 5, 1, 11,
-		6, 12, 4, 11, 4, 2, 3, 1, 4, 1, 4, 18, 44, 10, 0, 17, 22, 41,
-		4, 32, 4, 16, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 23, 7,
-		1, 18, 2, 18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 43, 11, 0, 17,
-		22, 42, 4, 12, 3, 10, 3, 16, 3, 16, 4, 65, 14, 6, 2, 0, 0, 0,
-		0, 0, 0, 0, 24, 12, 1, 10, 3, 16, 3, 16, 4, 65, 14, 10, 1, 35,
-		4, 41, 5, 35, 10, 3, 15, 3, 15, 4, 49, 255, 68, 14, 5, 27, 11, 3,
-		1, 2, 4, 0, 0, 1, 3, 21, 36, 10, 0, 17, 22, 12, 3, 10, 3, 41,
-		3, 32, 4, 13, 10, 0, 11, 0, 56, 0, 18, 3, 45, 3, 5, 15, 11, 0,
-		1, 11, 3, 42, 3, 12, 2, 10, 1, 6, 0, 0, 0, 0, 0, 0, 0, 0,
-		36, 4, 33, 5, 23, 11, 1, 6, 1, 0, 0, 0, 0, 0, 0, 0, 23, 12,
-		1, 10, 2, 15, 5, 10, 1, 18, 5, 56, 1, 5, 18, 11, 2, 1, 2, 5,
-		1, 4, 1, 1, 1, 7, 11, 0, 17, 22, 43, 1, 16, 2, 20, 1, 2, 6,
-		1, 4, 0, 25, 25, 7, 3, 12, 2, 14, 2, 65, 13, 12, 4, 10, 4, 6,
-		0, 0, 0, 0, 0, 0, 0, 0, 34, 4, 24, 10, 1, 10, 4, 38, 4, 17,
-		11, 4, 6, 1, 0, 0, 0, 0, 0, 0, 0, 23, 12, 1, 7, 3, 12, 3,
-		14, 3, 11, 1, 66, 13, 20, 1, 2, 7, 1, 4, 1, 4, 18, 44, 10, 0,
-		17, 22, 41, 4, 32, 4, 16, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2,
-		17, 23, 7, 1, 18, 2, 18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 43,
-		11, 0, 17, 22, 42, 4, 12, 3, 10, 3, 16, 3, 16, 4, 65, 14, 6, 2,
-		0, 0, 0, 0, 0, 0, 0, 26, 12, 1, 10, 3, 16, 3, 16, 4, 65, 14,
-		10, 1, 36, 4, 41, 5, 35, 10, 3, 15, 3, 15, 4, 69, 14, 1, 5, 27,
-		11, 3, 1, 2, 8, 0, 0, 0, 1, 5, 11, 0, 6, 0, 0, 0, 0, 0,
-		0, 0, 0, 18, 1, 45, 1, 2, 9, 1, 4, 0, 1, 11, 10, 1, 6, 0,
-		0, 0, 0, 0, 0, 0, 0, 36, 4, 10, 5, 5, 11, 1, 6, 1, 0, 0,
-		0, 0, 0, 0, 0, 23, 12, 1, 5, 0, 2, 10, 1, 4, 1, 4, 26, 34,
-		10, 0, 17, 22, 41, 4, 4, 22, 11, 0, 17, 22, 42, 4, 12, 5, 11, 1,
-		10, 5, 15, 1, 21, 11, 2, 10, 5, 15, 6, 21, 11, 3, 11, 5, 15, 3,
-		15, 4, 21, 5, 33, 11, 3, 18, 2, 12, 4, 11, 1, 11, 2, 11, 4, 18,
-		4, 12, 6, 11, 0, 11, 6, 45, 4, 2, 11, 0, 0, 1, 6, 27, 44, 10,
-		0, 17, 22, 12, 3, 10, 3, 41, 6, 32, 4, 12, 11, 0, 56, 2, 18, 6,
-		45, 6, 5, 14, 11, 0, 1, 11, 3, 42, 6, 15, 7, 12, 4, 10, 2, 6,
-		0, 0, 0, 0, 0, 0, 0, 0, 36, 4, 41, 5, 23, 11, 2, 6, 1, 0,
-		0, 0, 0, 0, 0, 0, 23, 12, 2, 10, 4, 10, 1, 10, 2, 22, 6, 0,
-		0, 0, 0, 0, 0, 0, 0, 56, 3, 12, 5, 10, 5, 20, 6, 1, 0, 0,
-		0, 0, 0, 0, 0, 22, 11, 5, 21, 5, 18, 11, 4, 1, 2, 12, 1, 4,
-		1, 4, 32, 93, 10, 1, 41, 4, 4, 6, 11, 0, 1, 2, 10, 0, 17, 22,
-		41, 4, 32, 4, 21, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 23,
-		7, 1, 18, 2, 18, 4, 12, 8, 10, 0, 11, 8, 45, 4, 10, 0, 17, 22,
-		43, 4, 16, 3, 16, 4, 65, 14, 12, 6, 10, 1, 43, 4, 16, 3, 16, 4,
-		65, 14, 12, 7, 11, 6, 11, 7, 36, 4, 49, 11, 0, 17, 22, 43, 4, 16,
-		3, 16, 4, 20, 11, 1, 42, 4, 12, 4, 12, 3, 5, 59, 11, 1, 43, 4,
-		16, 3, 16, 4, 20, 11, 0, 17, 22, 42, 4, 12, 4, 12, 3, 11, 3, 11,
-		4, 12, 9, 12, 5, 14, 5, 65, 14, 10, 9, 16, 3, 16, 4, 65, 14, 36,
-		4, 75, 5, 72, 8, 12, 2, 5, 82, 10, 9, 16, 3, 16, 4, 65, 14, 6,
-		16, 39, 0, 0, 0, 0, 0, 0, 35, 12, 2, 11, 2, 4, 90, 10, 9, 15,
-		3, 15, 4, 14, 5, 17, 0, 5, 63, 11, 9, 1, 2, 13, 1, 4, 1, 4,
-		33, 81, 10, 1, 41, 4, 4, 6, 11, 0, 1, 2, 10, 0, 17, 22, 41, 4,
-		32, 4, 21, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 23, 7, 1,
-		18, 2, 18, 4, 12, 7, 10, 0, 11, 7, 45, 4, 10, 0, 17, 22, 43, 4,
-		12, 9, 10, 1, 43, 4, 12, 10, 11, 9, 16, 3, 16, 4, 65, 14, 11, 10,
-		16, 3, 16, 4, 65, 14, 12, 5, 12, 4, 10, 4, 10, 5, 36, 4, 51, 11,
-		5, 6, 2, 0, 0, 0, 0, 0, 0, 0, 26, 11, 0, 17, 22, 42, 4, 12,
-		3, 12, 2, 5, 60, 11, 0, 1, 11, 4, 6, 2, 0, 0, 0, 0, 0, 0,
-		0, 26, 11, 1, 42, 4, 12, 3, 12, 2, 11, 2, 11, 3, 12, 8, 12, 6,
-		10, 8, 16, 3, 16, 4, 65, 14, 10, 6, 36, 4, 78, 5, 72, 10, 8, 15,
-		3, 15, 4, 69, 14, 1, 5, 64, 11, 8, 1, 2, 14, 1, 4, 0, 1, 1,
-		2, 15, 1, 4, 0, 1, 1, 2, 16, 1, 4, 0, 1, 1, 2, 17, 1, 4,
-		1, 4, 34, 34, 10, 0, 17, 22, 41, 4, 4, 23, 11, 0, 17, 22, 42, 4,
-		12, 1, 6, 0, 0, 0, 0, 0, 0, 0, 0, 10, 1, 15, 1, 21, 7, 2,
-		17, 23, 10, 1, 15, 6, 21, 7, 1, 11, 1, 15, 3, 15, 4, 21, 5, 33,
-		6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 23, 7, 1, 18, 2, 18,
-		4, 12, 2, 11, 0, 11, 2, 45, 4, 2, 18, 1, 4, 1, 4, 35, 25, 10,
-		0, 17, 22, 41, 4, 32, 4, 16, 11, 1, 7, 2, 17, 23, 7, 1, 18, 2,
-		18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 24, 11, 0, 17, 22, 42, 4,
-		12, 3, 11, 1, 11, 3, 15, 1, 21, 2, 19, 1, 4, 1, 4, 35, 24, 10,
-		0, 17, 22, 41, 4, 32, 4, 15, 6, 0, 0, 0, 0, 0, 0, 0, 0, 11,
-		1, 7, 1, 18, 2, 18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 23, 11,
-		0, 17, 22, 42, 4, 12, 3, 11, 1, 11, 3, 15, 6, 21, 2, 20, 1, 4,
-		1, 1, 36, 42, 10, 1, 41, 1, 4, 4, 5, 9, 11, 0, 1, 7, 0, 17,
-		28, 39, 11, 1, 42, 1, 12, 2, 10, 2, 16, 2, 20, 7, 0, 22, 10, 2,
-		15, 2, 21, 11, 2, 16, 2, 20, 12, 4, 10, 0, 17, 22, 41, 1, 4, 37,
-		11, 0, 17, 22, 42, 1, 12, 3, 11, 4, 11, 3, 15, 2, 21, 5, 41, 11,
-		0, 11, 4, 18, 1, 45, 1, 2, 21, 1, 4, 1, 1, 37, 13, 11, 0, 17,
-		22, 42, 1, 12, 1, 10, 1, 16, 2, 20, 7, 0, 22, 11, 1, 15, 2, 21,
-		2, 0, 0, 4, 0, 1, 0, 4, 2, 2, 0, 3, 0, 4, 1, 6, 0, 0,
-			]
+		0, 0, 0, 0, 0, 0, 0, 171, 205, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+		0, 0, 0, 0, 1, 3, 8, 1, 0, 0, 0, 0, 0, 0, 0, 10, 2, 9,
+		8, 1, 35, 69, 103, 137, 171, 205, 239, 10, 2, 6, 5, 104, 101, 108, 108, 111,
+		10, 3, 81, 10, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0,
+		0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0,
+		4, 0, 0, 0, 0, 0, 0, 0, 5, 0, 0, 0, 0, 0, 0, 0, 6, 0,
+		0, 0, 0, 0, 0, 0, 7, 0, 0, 0, 0, 0, 0, 0, 8, 0, 0, 0,
+		0, 0, 0, 0, 9, 0, 0, 0, 0, 0, 0, 0, 18, 97, 112, 116, 111, 115,
+		58, 58, 109, 101, 116, 97, 100, 97, 116, 97, 95, 118, 49, 42, 1, 1, 0, 0,
+		0, 0, 0, 0, 0, 29, 69, 67, 79, 85, 78, 84, 69, 82, 95, 82, 69, 83,
+		79, 85, 82, 67, 69, 95, 78, 79, 84, 95, 80, 82, 69, 83, 69, 78, 84, 0,
+		0, 0, 0, 2, 1, 39, 10, 2, 1, 2, 1, 40, 3, 2, 2, 1, 39, 10,
+		2, 3, 2, 1, 41, 11, 8, 1, 8, 5, 4, 2, 3, 43, 3, 44, 8, 7,
+		39, 8, 2, 5, 2, 1, 45, 3, 6, 2, 1, 46, 11, 9, 2, 3, 3, 0,
+		1, 4, 0, 1, 2, 11, 1, 39, 1, 0, 0, 0, 13, 26, 10, 1, 65, 14,
+		12, 2, 10, 2, 6, 0, 0, 0, 0, 0, 0, 0, 0, 36, 4, 21, 5, 8,
+		10, 0, 10, 1, 10, 2, 6, 1, 0, 0, 0, 0, 0, 0, 0, 23, 66, 14,
+		20, 68, 14, 11, 2, 6, 1, 0, 0, 0, 0, 0, 0, 0, 23, 12, 2, 5,
+		3, 11, 1, 1, 11, 0, 1, 2, 2, 1, 4, 0, 15, 25, 6, 0, 0, 0,
+		0, 0, 0, 0, 0, 12, 2, 6, 0, 0, 0, 0, 0, 0, 0, 0, 12, 3,
+		10, 3, 10, 1, 35, 4, 24, 5, 9, 11, 2, 6, 31, 0, 0, 0, 0, 0,
+		0, 0, 24, 10, 3, 6, 71, 66, 15, 0, 0, 0, 0, 0, 25, 22, 6, 71,
+		66, 15, 0, 0, 0, 0, 0, 25, 12, 2, 11, 3, 6, 1, 0, 0, 0, 0,
+		0, 0, 0, 22, 12, 3, 5, 4, 2, 3, 1, 4, 1, 0, 16, 20, 10, 0,
+		17, 24, 41, 0, 4, 13, 11, 0, 17, 24, 42, 0, 12, 2, 11, 1, 11, 2,
+		15, 0, 21, 5, 19, 11, 1, 18, 0, 12, 3, 11, 0, 11, 3, 45, 0, 2,
+		4, 0, 0, 0, 18, 103, 10, 0, 16, 1, 12, 5, 10, 1, 16, 1, 12, 6,
+		11, 5, 20, 10, 6, 20, 35, 4, 18, 11, 6, 12, 5, 10, 2, 16, 2, 12,
+		6, 5, 26, 11, 6, 1, 10, 1, 16, 1, 12, 5, 10, 3, 16, 2, 12, 6,
+		10, 6, 20, 10, 1, 16, 1, 20, 35, 4, 47, 11, 5, 1, 11, 1, 1, 11,
+		0, 1, 11, 2, 1, 11, 6, 12, 5, 11, 3, 16, 2, 12, 6, 5, 69, 11,
+		3, 1, 10, 5, 11, 0, 16, 1, 34, 4, 65, 11, 6, 1, 11, 5, 1, 11,
+		2, 16, 2, 12, 5, 11, 1, 16, 1, 12, 6, 5, 69, 11, 1, 1, 11, 2,
+		1, 10, 5, 20, 10, 6, 20, 35, 4, 82, 11, 6, 1, 10, 5, 12, 6, 10,
+		5, 1, 5, 88, 11, 5, 1, 10, 6, 12, 5, 10, 6, 1, 10, 5, 10, 6,
+		33, 4, 97, 11, 6, 1, 11, 5, 12, 4, 5, 101, 11, 5, 1, 11, 6, 12,
+		4, 11, 4, 2, 5, 1, 4, 1, 4, 19, 44, 10, 0, 17, 24, 41, 4, 32,
+		4, 16, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 25, 7, 1, 18,
+		2, 18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 43, 11, 0, 17, 24, 42,
+		4, 12, 3, 10, 3, 16, 3, 16, 4, 65, 14, 6, 2, 0, 0, 0, 0, 0,
+		0, 0, 24, 12, 1, 10, 3, 16, 3, 16, 4, 65, 14, 10, 1, 35, 4, 41,
+		5, 35, 10, 3, 15, 3, 15, 4, 49, 255, 68, 14, 5, 27, 11, 3, 1, 2,
+		6, 0, 0, 1, 3, 22, 36, 10, 0, 17, 24, 12, 3, 10, 3, 41, 3, 32,
+		4, 13, 10, 0, 11, 0, 56, 0, 18, 3, 45, 3, 5, 15, 11, 0, 1, 11,
+		3, 42, 3, 12, 2, 10, 1, 6, 0, 0, 0, 0, 0, 0, 0, 0, 36, 4,
+		33, 5, 23, 11, 1, 6, 1, 0, 0, 0, 0, 0, 0, 0, 23, 12, 1, 10,
+		2, 15, 5, 10, 1, 18, 5, 56, 1, 5, 18, 11, 2, 1, 2, 7, 1, 4,
+		1, 1, 1, 7, 11, 0, 17, 24, 43, 1, 16, 2, 20, 1, 2, 8, 1, 4,
+		0, 26, 25, 7, 3, 12, 2, 14, 2, 65, 13, 12, 4, 10, 4, 6, 0, 0,
+		0, 0, 0, 0, 0, 0, 34, 4, 24, 10, 1, 10, 4, 38, 4, 17, 11, 4,
+		6, 1, 0, 0, 0, 0, 0, 0, 0, 23, 12, 1, 7, 3, 12, 3, 14, 3,
+		11, 1, 66, 13, 20, 1, 2, 9, 1, 4, 1, 4, 19, 44, 10, 0, 17, 24,
+		41, 4, 32, 4, 16, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 25,
+		7, 1, 18, 2, 18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 43, 11, 0,
+		17, 24, 42, 4, 12, 3, 10, 3, 16, 3, 16, 4, 65, 14, 6, 2, 0, 0,
+		0, 0, 0, 0, 0, 26, 12, 1, 10, 3, 16, 3, 16, 4, 65, 14, 10, 1,
+		36, 4, 41, 5, 35, 10, 3, 15, 3, 15, 4, 69, 14, 1, 5, 27, 11, 3,
+		1, 2, 10, 0, 0, 0, 1, 5, 11, 0, 6, 0, 0, 0, 0, 0, 0, 0,
+		0, 18, 1, 45, 1, 2, 11, 1, 4, 0, 1, 11, 10, 1, 6, 0, 0, 0,
+		0, 0, 0, 0, 0, 36, 4, 10, 5, 5, 11, 1, 6, 1, 0, 0, 0, 0,
+		0, 0, 0, 23, 12, 1, 5, 0, 2, 12, 1, 4, 1, 4, 27, 34, 10, 0,
+		17, 24, 41, 4, 4, 22, 11, 0, 17, 24, 42, 4, 12, 5, 11, 1, 10, 5,
+		15, 1, 21, 11, 2, 10, 5, 15, 6, 21, 11, 3, 11, 5, 15, 3, 15, 4,
+		21, 5, 33, 11, 3, 18, 2, 12, 4, 11, 1, 11, 2, 11, 4, 18, 4, 12,
+		6, 11, 0, 11, 6, 45, 4, 2, 13, 0, 0, 1, 6, 28, 44, 10, 0, 17,
+		24, 12, 3, 10, 3, 41, 6, 32, 4, 12, 11, 0, 56, 2, 18, 6, 45, 6,
+		5, 14, 11, 0, 1, 11, 3, 42, 6, 15, 7, 12, 4, 10, 2, 6, 0, 0,
+		0, 0, 0, 0, 0, 0, 36, 4, 41, 5, 23, 11, 2, 6, 1, 0, 0, 0,
+		0, 0, 0, 0, 23, 12, 2, 10, 4, 10, 1, 10, 2, 22, 6, 0, 0, 0,
+		0, 0, 0, 0, 0, 56, 3, 12, 5, 10, 5, 20, 6, 1, 0, 0, 0, 0,
+		0, 0, 0, 22, 11, 5, 21, 5, 18, 11, 4, 1, 2, 14, 1, 4, 1, 4,
+		32, 93, 10, 1, 41, 4, 4, 6, 11, 0, 1, 2, 10, 0, 17, 24, 41, 4,
+		32, 4, 21, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 25, 7, 1,
+		18, 2, 18, 4, 12, 8, 10, 0, 11, 8, 45, 4, 10, 0, 17, 24, 43, 4,
+		16, 3, 16, 4, 65, 14, 12, 6, 10, 1, 43, 4, 16, 3, 16, 4, 65, 14,
+		12, 7, 11, 6, 11, 7, 36, 4, 49, 11, 0, 17, 24, 43, 4, 16, 3, 16,
+		4, 20, 11, 1, 42, 4, 12, 4, 12, 3, 5, 59, 11, 1, 43, 4, 16, 3,
+		16, 4, 20, 11, 0, 17, 24, 42, 4, 12, 4, 12, 3, 11, 3, 11, 4, 12,
+		9, 12, 5, 14, 5, 65, 14, 10, 9, 16, 3, 16, 4, 65, 14, 36, 4, 75,
+		5, 72, 8, 12, 2, 5, 82, 10, 9, 16, 3, 16, 4, 65, 14, 6, 16, 39,
+		0, 0, 0, 0, 0, 0, 35, 12, 2, 11, 2, 4, 90, 10, 9, 15, 3, 15,
+		4, 14, 5, 17, 1, 5, 63, 11, 9, 1, 2, 15, 1, 4, 1, 4, 33, 81,
+		10, 1, 41, 4, 4, 6, 11, 0, 1, 2, 10, 0, 17, 24, 41, 4, 32, 4,
+		21, 6, 0, 0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 25, 7, 1, 18, 2,
+		18, 4, 12, 7, 10, 0, 11, 7, 45, 4, 10, 0, 17, 24, 43, 4, 12, 9,
+		10, 1, 43, 4, 12, 10, 11, 9, 16, 3, 16, 4, 65, 14, 11, 10, 16, 3,
+		16, 4, 65, 14, 12, 5, 12, 4, 10, 4, 10, 5, 36, 4, 51, 11, 5, 6,
+		2, 0, 0, 0, 0, 0, 0, 0, 26, 11, 0, 17, 24, 42, 4, 12, 3, 12,
+		2, 5, 60, 11, 0, 1, 11, 4, 6, 2, 0, 0, 0, 0, 0, 0, 0, 26,
+		11, 1, 42, 4, 12, 3, 12, 2, 11, 2, 11, 3, 12, 8, 12, 6, 10, 8,
+		16, 3, 16, 4, 65, 14, 10, 6, 36, 4, 78, 5, 72, 10, 8, 15, 3, 15,
+		4, 69, 14, 1, 5, 64, 11, 8, 1, 2, 16, 1, 4, 0, 1, 1, 2, 17,
+		1, 4, 0, 1, 1, 2, 18, 1, 4, 0, 1, 1, 2, 19, 1, 4, 1, 4,
+		34, 34, 10, 0, 17, 24, 41, 4, 4, 23, 11, 0, 17, 24, 42, 4, 12, 1,
+		6, 0, 0, 0, 0, 0, 0, 0, 0, 10, 1, 15, 1, 21, 7, 2, 17, 25,
+		10, 1, 15, 6, 21, 7, 1, 11, 1, 15, 3, 15, 4, 21, 5, 33, 6, 0,
+		0, 0, 0, 0, 0, 0, 0, 7, 2, 17, 25, 7, 1, 18, 2, 18, 4, 12,
+		2, 11, 0, 11, 2, 45, 4, 2, 20, 1, 4, 1, 4, 35, 25, 10, 0, 17,
+		24, 41, 4, 32, 4, 16, 11, 1, 7, 2, 17, 25, 7, 1, 18, 2, 18, 4,
+		12, 2, 11, 0, 11, 2, 45, 4, 5, 24, 11, 0, 17, 24, 42, 4, 12, 3,
+		11, 1, 11, 3, 15, 1, 21, 2, 21, 1, 4, 1, 4, 35, 24, 10, 0, 17,
+		24, 41, 4, 32, 4, 15, 6, 0, 0, 0, 0, 0, 0, 0, 0, 11, 1, 7,
+		1, 18, 2, 18, 4, 12, 2, 11, 0, 11, 2, 45, 4, 5, 23, 11, 0, 17,
+		24, 42, 4, 12, 3, 11, 1, 11, 3, 15, 6, 21, 2, 22, 1, 4, 1, 1,
+		36, 42, 10, 1, 41, 1, 4, 4, 5, 9, 11, 0, 1, 7, 0, 17, 30, 39,
+		11, 1, 42, 1, 12, 2, 10, 2, 16, 2, 20, 7, 0, 22, 10, 2, 15, 2,
+		21, 11, 2, 16, 2, 20, 12, 4, 10, 0, 17, 24, 41, 1, 4, 37, 11, 0,
+		17, 24, 42, 1, 12, 3, 11, 4, 11, 3, 15, 2, 21, 5, 41, 11, 0, 11,
+		4, 18, 1, 45, 1, 2, 23, 1, 4, 1, 1, 37, 13, 11, 0, 17, 24, 42,
+		1, 12, 1, 10, 1, 16, 2, 20, 7, 0, 22, 11, 1, 15, 2, 21, 2, 0,
+		0, 4, 0, 1, 0, 4, 2, 2, 0, 3, 0, 4, 1, 6, 0, 0,
+	]
 });
 
 #[rustfmt::skip]
@@ -0,0 +1,196 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GeneratorMetrics, GeneratorReport, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use std::collections::HashMap;
+
+/// Wrapper that caps the number of transactions the inner generator produces per sender in
+/// a single call, to model a client-side rate limit of an account. Transactions requested
+/// above the cap are deferred, i.e. added to what is requested for the same sender in the
+/// next call. With no cap, it is a pass-through.
+pub struct RateLimitedGenerator {
+    generator: Box<dyn TransactionGenerator>,
+    per_sender_rate: Option<usize>,
+    deferred: HashMap<AccountAddress, usize>,
+}
+
+impl RateLimitedGenerator {
+    pub fn new(generator: Box<dyn TransactionGenerator>, per_sender_rate: Option<usize>) -> Self {
+        Self {
+            generator,
+            per_sender_rate,
+            deferred: HashMap::new(),
+        }
+    }
+
+    // Returns the generated transactions, and the number of transactions deferred for the
+    // sender (in total, including those deferred by earlier calls).
+    fn generate_rate_limited(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> (Vec<SignedTransaction>, usize) {
+        let per_sender_rate = match self.per_sender_rate {
+            Some(per_sender_rate) => per_sender_rate,
+            None => {
+                return (
+                    self.generator.generate_transactions(account, num_to_create),
+                    0,
+                )
+            },
+        };
+
+        let requested =
+            num_to_create + self.deferred.remove(&account.address()).unwrap_or_default();
+        let num_to_create = std::cmp::min(requested, per_sender_rate);
+        let deferred = requested - num_to_create;
+        if deferred > 0 {
+            self.deferred.insert(account.address(), deferred);
+        }
+        (
+            self.generator.generate_transactions(account, num_to_create),
+            deferred,
+        )
+    }
+}
+
+impl TransactionGenerator for RateLimitedGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.generate_rate_limited(account, num_to_create).0
+    }
+
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        let (txns, deferred) = self.generate_rate_limited(account, num_to_create);
+        metrics.report(GeneratorReport {
+            generated: txns.len(),
+            discarded: 0,
+            deferred,
+        });
+        txns
+    }
+}
+
+pub struct RateLimitedGeneratorCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    per_sender_rate: Option<usize>,
+}
+
+impl RateLimitedGeneratorCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        per_sender_rate: Option<usize>,
+    ) -> Self {
+        Self {
+            creator,
+            per_sender_rate,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for RateLimitedGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(RateLimitedGenerator::new(
+            self.creator.create_transaction_generator(),
+            self.per_sender_rate,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{FakeMetrics, TransferGeneratorCreator};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_per_sender_rate() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut other_account = LocalAccount::generate(&mut rng);
+        let transactions_per_account = 10;
+        let mut creator = RateLimitedGeneratorCreator::new(
+            Box::new(TransferGeneratorCreator::default()),
+            Some(3),
+        );
+        let mut generator = creator.create_transaction_generator();
+
+        assert_eq!(
+            generator
+                .generate_transactions(&mut account, transactions_per_account)
+                .len(),
+            3
+        );
+        assert_eq!(
+            generator
+                .generate_transactions(&mut other_account, transactions_per_account)
+                .len(),
+            3
+        );
+        // The excess is deferred to the next calls, still at most 3 per call.
+        assert_eq!(generator.generate_transactions(&mut account, 0).len(), 3);
+        assert_eq!(generator.generate_transactions(&mut account, 0).len(), 3);
+        assert_eq!(generator.generate_transactions(&mut account, 0).len(), 1);
+        assert!(generator.generate_transactions(&mut account, 0).is_empty());
+        assert_eq!(account.sequence_number(), 10);
+    }
+
+    #[test]
+    fn test_deferred_transactions_are_reported() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator = RateLimitedGeneratorCreator::new(
+            Box::new(TransferGeneratorCreator::default()),
+            Some(3),
+        );
+        let mut generator = creator.create_transaction_generator();
+        let metrics = FakeMetrics::default();
+
+        generator.generate_transactions_with_metrics(&mut account, 5, &metrics);
+        generator.generate_transactions_with_metrics(&mut account, 0, &metrics);
+        assert_eq!(*metrics.reports.lock(), vec![
+            GeneratorReport {
+                generated: 3,
+                discarded: 0,
+                deferred: 2,
+            },
+            GeneratorReport {
+                generated: 2,
+                discarded: 0,
+                deferred: 0,
+            },
+        ]);
+
+        // Generators without deferrals only report the generated transactions.
+        let mut generator = TransferGeneratorCreator::default().create_transaction_generator();
+        let metrics = FakeMetrics::default();
+        generator.generate_transactions_with_metrics(&mut account, 4, &metrics);
+        assert_eq!(*metrics.reports.lock(), vec![GeneratorReport {
+            generated: 4,
+            ..GeneratorReport::default()
+        }]);
+    }
+
+    #[test]
+    fn test_no_per_sender_rate() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator =
+            RateLimitedGeneratorCreator::new(Box::new(TransferGeneratorCreator::default()), None);
+        let mut generator = creator.create_transaction_generator();
+
+        assert_eq!(generator.generate_transactions(&mut account, 10).len(), 10);
+    }
+}
@@ -0,0 +1,103 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use anyhow::Context;
+use aptos_infallible::Mutex;
+use aptos_sdk::{
+    bcs,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use std::{collections::VecDeque, path::Path, sync::Arc};
+
+/// Writes the transactions to the given file, in the format read by `ReplayGeneratorCreator`
+/// (BCS serialized `Vec<SignedTransaction>`).
+pub fn write_transactions(path: &Path, transactions: &[SignedTransaction]) -> anyhow::Result<()> {
+    let bytes = bcs::to_bytes(transactions)?;
+    std::fs::write(path, bytes)
+        .with_context(|| format!("Failed to write transactions to {:?}", path))
+}
+
+/// Replays a recorded stream of (already signed) transactions, in order. The stream is
+/// shared across all generators created by the same creator, so each transaction is
+/// replayed exactly once. Once the stream is exhausted, no transactions are returned.
+pub struct ReplayGenerator {
+    transactions: Arc<Mutex<VecDeque<SignedTransaction>>>,
+}
+
+impl TransactionGenerator for ReplayGenerator {
+    fn generate_transactions(
+        &mut self,
+        _account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut transactions = self.transactions.lock();
+        let num_to_create = std::cmp::min(num_to_create, transactions.len());
+        transactions.drain(..num_to_create).collect()
+    }
+}
+
+pub struct ReplayGeneratorCreator {
+    transactions: Arc<Mutex<VecDeque<SignedTransaction>>>,
+}
+
+impl ReplayGeneratorCreator {
+    /// Reads the transactions recorded (with `write_transactions`) in the given file.
+    pub fn new(path: &Path) -> anyhow::Result<Self> {
+        let bytes = std::fs::read(path)
+            .with_context(|| format!("Failed to read transactions from {:?}", path))?;
+        let transactions: Vec<SignedTransaction> = bcs::from_bytes(&bytes)
+            .with_context(|| format!("Failed to deserialize transactions from {:?}", path))?;
+        Ok(Self {
+            transactions: Arc::new(Mutex::new(transactions.into())),
+        })
+    }
+}
+
+impl TransactionGeneratorCreator for ReplayGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(ReplayGenerator {
+            transactions: self.transactions.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::{
+        move_types::account_address::AccountAddress,
+        transaction_builder::{aptos_stdlib, TransactionFactory},
+        types::chain_id::ChainId,
+    };
+    use aptos_temppath::TempPath;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_replay_generator() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let txn_factory = TransactionFactory::new(ChainId::test());
+        let transactions: Vec<SignedTransaction> = (0..5)
+            .map(|i| {
+                account.sign_with_transaction_builder(
+                    txn_factory.payload(aptos_stdlib::aptos_coin_transfer(AccountAddress::ONE, i)),
+                )
+            })
+            .collect();
+
+        let path = TempPath::new();
+        write_transactions(path.path(), &transactions).unwrap();
+
+        let mut creator = ReplayGeneratorCreator::new(path.path()).unwrap();
+        let mut generator = creator.create_transaction_generator();
+        let mut replayed = generator.generate_transactions(&mut account, 3);
+        replayed.extend(generator.generate_transactions(&mut account, 3));
+        assert!(generator.generate_transactions(&mut account, 3).is_empty());
+
+        assert_eq!(
+            bcs::to_bytes(&replayed).unwrap(),
+            bcs::to_bytes(&transactions).unwrap()
+        );
+    }
+}
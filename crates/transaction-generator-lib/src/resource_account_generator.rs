@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_infallible::RwLock;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib, TransactionFactory},
+    types::{
+        account_address::create_resource_address, transaction::SignedTransaction, LocalAccount,
+    },
+};
+use std::sync::Arc;
+
+/// Generates transactions creating a resource account from each sponsor account it is called
+/// with, to load the resource account creation path. The sequence number of the sponsor
+/// transaction is used as the seed, so the derived addresses are unique. With a non-zero
+/// fund amount, `resource_account::create_resource_account_and_fund` is called (transferring
+/// it to the resource account), and `resource_account::create_resource_account` otherwise.
+/// Derived addresses are appended to `resource_addresses`, for optional downstream use.
+pub struct ResourceAccountGenerator {
+    txn_factory: TransactionFactory,
+    fund_amount: u64,
+    resource_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+}
+
+impl ResourceAccountGenerator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        fund_amount: u64,
+        resource_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    ) -> Self {
+        Self {
+            txn_factory,
+            fund_amount,
+            resource_addresses,
+        }
+    }
+}
+
+impl TransactionGenerator for ResourceAccountGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(num_to_create);
+        let mut new_addresses = Vec::with_capacity(num_to_create);
+        for _ in 0..num_to_create {
+            let seed = account.sequence_number().to_le_bytes().to_vec();
+            new_addresses.push(create_resource_address(account.address(), &seed));
+            let payload = if self.fund_amount > 0 {
+                aptos_stdlib::resource_account_create_resource_account_and_fund(
+                    seed,
+                    vec![],
+                    self.fund_amount,
+                )
+            } else {
+                aptos_stdlib::resource_account_create_resource_account(seed, vec![])
+            };
+            requests.push(account.sign_with_transaction_builder(self.txn_factory.payload(payload)));
+        }
+
+        self.resource_addresses.write().append(&mut new_addresses);
+        requests
+    }
+}
+
+pub struct ResourceAccountGeneratorCreator {
+    txn_factory: TransactionFactory,
+    fund_amount: u64,
+    resource_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+}
+
+impl ResourceAccountGeneratorCreator {
+    pub fn new(
+        txn_factory: TransactionFactory,
+        fund_amount: u64,
+        resource_addresses: Arc<RwLock<Vec<AccountAddress>>>,
+    ) -> Self {
+        Self {
+            txn_factory,
+            fund_amount,
+            resource_addresses,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for ResourceAccountGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(ResourceAccountGenerator::new(
+            self.txn_factory.clone(),
+            self.fund_amount,
+            self.resource_addresses.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::types::{chain_id::ChainId, transaction::TransactionPayload};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn entry_function_name(txn: &SignedTransaction) -> String {
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(entry_function.module().name().as_str(), "resource_account");
+                entry_function.function().to_string()
+            },
+            _ => panic!("Expected an entry function payload"),
+        }
+    }
+
+    #[test]
+    fn test_resource_account_creation() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let resource_addresses = Arc::new(RwLock::new(Vec::new()));
+        let mut creator = ResourceAccountGeneratorCreator::new(
+            TransactionFactory::new(ChainId::test()),
+            100,
+            resource_addresses.clone(),
+        );
+        let mut generator = creator.create_transaction_generator();
+
+        let txns = generator.generate_transactions(&mut account, 3);
+        assert_eq!(txns.len(), 3);
+        for txn in &txns {
+            assert_eq!(entry_function_name(txn), "create_resource_account_and_fund");
+        }
+        assert_eq!(*resource_addresses.read(), vec![
+            create_resource_address(account.address(), &0u64.to_le_bytes()),
+            create_resource_address(account.address(), &1u64.to_le_bytes()),
+            create_resource_address(account.address(), &2u64.to_le_bytes()),
+        ]);
+
+        // Without funding, plain resource accounts are created.
+        let mut generator = ResourceAccountGenerator::new(
+            TransactionFactory::new(ChainId::test()),
+            0,
+            Arc::new(RwLock::new(Vec::new())),
+        );
+        let txns = generator.generate_transactions(&mut account, 1);
+        assert_eq!(entry_function_name(&txns[0]), "create_resource_account");
+    }
+}
@@ -0,0 +1,161 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    move_types::language_storage::TypeTag,
+    transaction_builder::TransactionFactory,
+    types::{
+        transaction::{Script, SignedTransaction, TransactionArgument},
+        LocalAccount,
+    },
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::sync::Arc;
+
+/// Builds one argument of the script, for the given sender.
+pub type ScriptArgBuilder =
+    Arc<dyn Fn(&mut StdRng, &LocalAccount) -> TransactionArgument + Send + Sync>;
+
+/// Generator of transactions carrying a compiled Move script (instead of calling an entry
+/// function), to exercise the script execution path of the VM. Each transaction runs the
+/// same script, with its arguments built by the argument builders.
+pub struct ScriptGenerator {
+    rng: StdRng,
+    txn_factory: TransactionFactory,
+    code: Vec<u8>,
+    ty_args: Vec<TypeTag>,
+    arg_builders: Vec<ScriptArgBuilder>,
+}
+
+impl ScriptGenerator {
+    pub fn new(
+        rng: StdRng,
+        txn_factory: TransactionFactory,
+        code: Vec<u8>,
+        ty_args: Vec<TypeTag>,
+        arg_builders: Vec<ScriptArgBuilder>,
+    ) -> Self {
+        Self {
+            rng,
+            txn_factory,
+            code,
+            ty_args,
+            arg_builders,
+        }
+    }
+
+    fn script(&mut self, account: &LocalAccount) -> Script {
+        let args = self
+            .arg_builders
+            .iter()
+            .map(|arg_builder| arg_builder(&mut self.rng, account))
+            .collect();
+        Script::new(self.code.clone(), self.ty_args.clone(), args)
+    }
+}
+
+impl TransactionGenerator for ScriptGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        (0..num_to_create)
+            .map(|_| {
+                let script = self.script(account);
+                account.sign_with_transaction_builder(self.txn_factory.script(script))
+            })
+            .collect()
+    }
+}
+
+pub struct ScriptGeneratorCreator {
+    txn_factory: TransactionFactory,
+    code: Vec<u8>,
+    ty_args: Vec<TypeTag>,
+    arg_builders: Vec<ScriptArgBuilder>,
+}
+
+impl ScriptGeneratorCreator {
+    /// Creates generators running the given compiled script, without type arguments or
+    /// arguments.
+    pub fn new(txn_factory: TransactionFactory, code: Vec<u8>) -> Self {
+        Self {
+            txn_factory,
+            code,
+            ty_args: Vec::new(),
+            arg_builders: Vec::new(),
+        }
+    }
+
+    pub fn ty_args(mut self, ty_args: Vec<TypeTag>) -> Self {
+        self.ty_args = ty_args;
+        self
+    }
+
+    /// Appends an argument to the script, built for each transaction.
+    pub fn arg(mut self, arg_builder: ScriptArgBuilder) -> Self {
+        self.arg_builders.push(arg_builder);
+        self
+    }
+}
+
+impl TransactionGeneratorCreator for ScriptGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(ScriptGenerator::new(
+            StdRng::from_entropy(),
+            self.txn_factory.clone(),
+            self.code.clone(),
+            self.ty_args.clone(),
+            self.arg_builders.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::{
+        bcs,
+        types::{chain_id::ChainId, transaction::TransactionPayload},
+    };
+    use rand::Rng;
+
+    #[test]
+    fn test_script_payload() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let code = vec![0xA1, 0x1C, 0xEB, 0x0B];
+        let mut creator =
+            ScriptGeneratorCreator::new(TransactionFactory::new(ChainId::test()), code.clone())
+                .ty_args(vec![TypeTag::U64])
+                .arg(Arc::new(|_, account| {
+                    TransactionArgument::Address(account.address())
+                }))
+                .arg(Arc::new(|rng, _| {
+                    TransactionArgument::U64(rng.gen_range(1, 100))
+                }));
+        let mut generator = creator.create_transaction_generator();
+
+        let txns = generator.generate_transactions(&mut account, 3);
+        assert_eq!(txns.len(), 3);
+        for txn in txns {
+            let decoded: SignedTransaction =
+                bcs::from_bytes(&bcs::to_bytes(&txn).unwrap()).unwrap();
+            match decoded.payload() {
+                TransactionPayload::Script(script) => {
+                    assert_eq!(script.code(), code.as_slice());
+                    assert_eq!(script.ty_args(), &[TypeTag::U64]);
+                    assert_eq!(script.args().len(), 2);
+                    assert_eq!(
+                        script.args()[0],
+                        TransactionArgument::Address(account.address())
+                    );
+                    assert!(matches!(script.args()[1], TransactionArgument::U64(1..=99)));
+                },
+                _ => panic!("Payload must be a script"),
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GeneratorMetrics, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_infallible::Mutex;
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use std::{collections::HashMap, sync::Arc};
+
+/// Returns the sequence number of the given account on chain, if it can be fetched.
+pub type SequenceNumberFetcher = Arc<dyn Fn(AccountAddress) -> Option<u64> + Send + Sync>;
+
+/// Wrapper that synchronizes the sequence number of the account before the inner generator
+/// creates transactions for it, so that generators recover from rejected transactions
+/// (which otherwise leave a gap in sequence numbers, stalling the account).
+/// An explicitly set reset point (e.g. after a reported rejection) takes precedence, and is
+/// used once. Otherwise the sequence number is fetched from chain state, if a fetcher is
+/// provided, and only used to move the account forward: under load the local sequence number
+/// is ahead of the committed one, so rewinding to it would resubmit in-flight sequence numbers.
+/// Reset points are shared across all generators created by the same creator.
+pub struct SequenceNumberSyncGenerator {
+    generator: Box<dyn TransactionGenerator>,
+    fetcher: Option<SequenceNumberFetcher>,
+    reset_points: Arc<Mutex<HashMap<AccountAddress, u64>>>,
+}
+
+impl SequenceNumberSyncGenerator {
+    pub fn new(
+        generator: Box<dyn TransactionGenerator>,
+        fetcher: Option<SequenceNumberFetcher>,
+        reset_points: Arc<Mutex<HashMap<AccountAddress, u64>>>,
+    ) -> Self {
+        Self {
+            generator,
+            fetcher,
+            reset_points,
+        }
+    }
+
+    /// Sets the sequence number the next transaction generated for the account will use.
+    pub fn reset_sequence_number(&self, address: AccountAddress, sequence_number: u64) {
+        self.reset_points.lock().insert(address, sequence_number);
+    }
+
+    fn sync_sequence_number(&self, account: &mut LocalAccount) {
+        let reset_point = self.reset_points.lock().remove(&account.address());
+        if let Some(reset_point) = reset_point {
+            *account.sequence_number_mut() = reset_point;
+        } else if let Some(on_chain) = self
+            .fetcher
+            .as_ref()
+            .and_then(|fetcher| fetcher(account.address()))
+        {
+            if on_chain > account.sequence_number() {
+                *account.sequence_number_mut() = on_chain;
+            }
+        }
+    }
+}
+
+impl TransactionGenerator for SequenceNumberSyncGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.sync_sequence_number(account);
+        self.generator.generate_transactions(account, num_to_create)
+    }
+
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        self.sync_sequence_number(account);
+        self.generator
+            .generate_transactions_with_metrics(account, num_to_create, metrics)
+    }
+}
+
+pub struct SequenceNumberSyncGeneratorCreator {
+    creator: Box<dyn TransactionGeneratorCreator>,
+    fetcher: Option<SequenceNumberFetcher>,
+    reset_points: Arc<Mutex<HashMap<AccountAddress, u64>>>,
+}
+
+impl SequenceNumberSyncGeneratorCreator {
+    pub fn new(
+        creator: Box<dyn TransactionGeneratorCreator>,
+        fetcher: Option<SequenceNumberFetcher>,
+    ) -> Self {
+        Self {
+            creator,
+            fetcher,
+            reset_points: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Sets the sequence number the next transaction generated for the account (by any of
+    /// the generators created) will use.
+    pub fn reset_sequence_number(&self, address: AccountAddress, sequence_number: u64) {
+        self.reset_points.lock().insert(address, sequence_number);
+    }
+}
+
+impl TransactionGeneratorCreator for SequenceNumberSyncGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(SequenceNumberSyncGenerator::new(
+            self.creator.create_transaction_generator(),
+            self.fetcher.clone(),
+            self.reset_points.clone(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransferGenerator;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn transfer_generator() -> Box<dyn TransactionGenerator> {
+        Box::new(TransferGenerator::default())
+    }
+
+    #[test]
+    fn test_reset_sequence_number() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut generator =
+            SequenceNumberSyncGenerator::new(transfer_generator(), None, Default::default());
+
+        let txns = generator.generate_transactions(&mut account, 5);
+        assert_eq!(txns.last().unwrap().sequence_number(), 4);
+
+        // e.g. transactions 2..5 were rejected.
+        generator.reset_sequence_number(account.address(), 2);
+        let txns = generator.generate_transactions(&mut account, 1);
+        assert_eq!(txns[0].sequence_number(), 2);
+        // The reset point is only used once.
+        let txns = generator.generate_transactions(&mut account, 1);
+        assert_eq!(txns[0].sequence_number(), 3);
+    }
+
+    #[test]
+    fn test_fetch_sequence_number() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let fetcher: SequenceNumberFetcher = Arc::new(|_| Some(7));
+        let mut generator = SequenceNumberSyncGenerator::new(
+            transfer_generator(),
+            Some(fetcher),
+            Default::default(),
+        );
+
+        let txns = generator.generate_transactions(&mut account, 2);
+        assert_eq!(txns[0].sequence_number(), 7);
+        assert_eq!(txns[1].sequence_number(), 8);
+
+        // Chain state lagging behind in-flight transactions doesn't rewind the account.
+        let txns = generator.generate_transactions(&mut account, 1);
+        assert_eq!(txns[0].sequence_number(), 9);
+
+        // A reset point takes precedence over chain state.
+        generator.reset_sequence_number(account.address(), 3);
+        let txns = generator.generate_transactions(&mut account, 1);
+        assert_eq!(txns[0].sequence_number(), 3);
+    }
+}
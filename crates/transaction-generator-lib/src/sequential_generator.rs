@@ -0,0 +1,142 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{GeneratorMetrics, TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::types::{transaction::SignedTransaction, LocalAccount};
+
+/// Generator that goes through an ordered list of phases (e.g. account creation, then coin
+/// registration, then module publishing), producing the transactions of a phase until it is
+/// exhausted (i.e. its generator returns no transactions), before moving to the next one.
+/// A single call never mixes transactions of different phases, so each phase can depend on
+/// the previous ones. Phases should be bounded (e.g. with BoundedGeneratorCreator), as an
+/// unbounded phase is never exhausted.
+pub struct SequentialGenerator {
+    phases: Vec<Box<dyn TransactionGenerator>>,
+    cur_phase: usize,
+}
+
+impl SequentialGenerator {
+    pub fn new(phases: Vec<Box<dyn TransactionGenerator>>) -> Self {
+        Self {
+            phases,
+            cur_phase: 0,
+        }
+    }
+
+    fn generate_from_phases(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        mut generate: impl FnMut(
+            &mut dyn TransactionGenerator,
+            &mut LocalAccount,
+            usize,
+        ) -> Vec<SignedTransaction>,
+    ) -> Vec<SignedTransaction> {
+        while let Some(phase) = self.phases.get_mut(self.cur_phase) {
+            let txns = generate(phase.as_mut(), account, num_to_create);
+            if !txns.is_empty() {
+                return txns;
+            }
+            self.cur_phase += 1;
+        }
+        Vec::new()
+    }
+}
+
+impl TransactionGenerator for SequentialGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        self.generate_from_phases(account, num_to_create, |phase, account, num_to_create| {
+            phase.generate_transactions(account, num_to_create)
+        })
+    }
+
+    fn generate_transactions_with_metrics(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+        metrics: &dyn GeneratorMetrics,
+    ) -> Vec<SignedTransaction> {
+        self.generate_from_phases(account, num_to_create, |phase, account, num_to_create| {
+            phase.generate_transactions_with_metrics(account, num_to_create, metrics)
+        })
+    }
+}
+
+pub struct SequentialCreator {
+    phases: Vec<Box<dyn TransactionGeneratorCreator>>,
+}
+
+impl SequentialCreator {
+    pub fn new(phases: Vec<Box<dyn TransactionGeneratorCreator>>) -> Self {
+        Self { phases }
+    }
+}
+
+impl TransactionGeneratorCreator for SequentialCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(SequentialGenerator::new(
+            self.phases
+                .iter_mut()
+                .map(|phase| phase.create_transaction_generator())
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::TransferGeneratorCreator, BoundedGeneratorCreator};
+    use aptos_sdk::{
+        bcs, move_types::account_address::AccountAddress, types::transaction::TransactionPayload,
+    };
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn bounded_phase(to: AccountAddress, max_total: usize) -> Box<dyn TransactionGeneratorCreator> {
+        Box::new(BoundedGeneratorCreator::new(
+            Box::new(TransferGeneratorCreator::new(to)),
+            Some(max_total),
+        ))
+    }
+
+    fn phase_of(txn: &SignedTransaction) -> AccountAddress {
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                bcs::from_bytes(&entry_function.args()[0]).unwrap()
+            },
+            _ => panic!("Transfer must be an entry function"),
+        }
+    }
+
+    #[test]
+    fn test_phases_are_sequential() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator = SequentialCreator::new(vec![
+            bounded_phase(AccountAddress::ONE, 5),
+            bounded_phase(AccountAddress::TWO, 3),
+        ]);
+        let mut generator = creator.create_transaction_generator();
+
+        let mut batches = vec![];
+        loop {
+            let txns = generator.generate_transactions(&mut account, 4);
+            if txns.is_empty() {
+                break;
+            }
+            batches.push(txns.iter().map(phase_of).collect::<Vec<_>>());
+        }
+
+        // Phase 1's transactions all precede phase 2's, and batches don't mix phases.
+        assert_eq!(batches, vec![
+            vec![AccountAddress::ONE; 4],
+            vec![AccountAddress::ONE; 1],
+            vec![AccountAddress::TWO; 3],
+        ]);
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{TransactionGenerator, TransactionGeneratorCreator};
+use aptos_sdk::{
+    move_types::account_address::AccountAddress,
+    transaction_builder::{aptos_stdlib::aptos_token_objects_stdlib, TransactionFactory},
+    types::{transaction::SignedTransaction, LocalAccount},
+};
+use std::collections::HashSet;
+
+/// Generates transactions minting v2 (object based) tokens via `0x4::aptos_token::mint`,
+/// e.g. so that end-to-end tests produce v2 token data for the indexer. Tokens are minted
+/// into a collection of the given name and max supply, owned by the account. The first time
+/// the generator is called with an account, a `0x4::aptos_token::create_collection`
+/// transaction (in addition to the requested ones) creates the collection. Each token is
+/// named after the sequence number of its transaction, so names are unique. Once the max
+/// supply of the collection is reached, mint transactions abort.
+pub struct TokenV2MintGenerator {
+    txn_factory: TransactionFactory,
+    collection_name: String,
+    max_supply: u64,
+    created_collections: HashSet<AccountAddress>,
+}
+
+impl TokenV2MintGenerator {
+    pub fn new(txn_factory: TransactionFactory, collection_name: String, max_supply: u64) -> Self {
+        Self {
+            txn_factory,
+            collection_name,
+            max_supply,
+            created_collections: HashSet::new(),
+        }
+    }
+
+    fn create_collection(&self, account: &mut LocalAccount) -> SignedTransaction {
+        account.sign_with_transaction_builder(self.txn_factory.payload(
+            aptos_token_objects_stdlib::aptos_token_create_collection(
+                b"Token v2 mint load test".to_vec(),
+                self.max_supply,
+                self.collection_name.clone().into_bytes(),
+                b"https://aptoslabs.com".to_vec(),
+                true,
+                true,
+                true,
+                true,
+                true,
+                true,
+                true,
+                true,
+                true,
+                0,
+                1,
+            ),
+        ))
+    }
+
+    fn mint(&self, account: &mut LocalAccount) -> SignedTransaction {
+        let token_name = format!("token-{}", account.sequence_number());
+        account.sign_with_transaction_builder(self.txn_factory.payload(
+            aptos_token_objects_stdlib::aptos_token_mint(
+                self.collection_name.clone().into_bytes(),
+                b"Token v2 mint load test".to_vec(),
+                token_name.into_bytes(),
+                b"https://aptoslabs.com".to_vec(),
+                vec![],
+                vec![],
+                vec![],
+            ),
+        ))
+    }
+}
+
+impl TransactionGenerator for TokenV2MintGenerator {
+    fn generate_transactions(
+        &mut self,
+        account: &mut LocalAccount,
+        num_to_create: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut requests = Vec::with_capacity(num_to_create + 1);
+        if self.created_collections.insert(account.address()) {
+            requests.push(self.create_collection(account));
+        }
+        for _ in 0..num_to_create {
+            requests.push(self.mint(account));
+        }
+        requests
+    }
+}
+
+pub struct TokenV2MintGeneratorCreator {
+    txn_factory: TransactionFactory,
+    collection_name: String,
+    max_supply: u64,
+}
+
+impl TokenV2MintGeneratorCreator {
+    pub fn new(txn_factory: TransactionFactory, collection_name: String, max_supply: u64) -> Self {
+        Self {
+            txn_factory,
+            collection_name,
+            max_supply,
+        }
+    }
+}
+
+impl TransactionGeneratorCreator for TokenV2MintGeneratorCreator {
+    fn create_transaction_generator(&mut self) -> Box<dyn TransactionGenerator> {
+        Box::new(TokenV2MintGenerator::new(
+            self.txn_factory.clone(),
+            self.collection_name.clone(),
+            self.max_supply,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_sdk::types::{chain_id::ChainId, transaction::TransactionPayload};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn entry_function_name(txn: &SignedTransaction) -> String {
+        match txn.payload() {
+            TransactionPayload::EntryFunction(entry_function) => {
+                assert_eq!(
+                    *entry_function.module().address(),
+                    AccountAddress::from_hex_literal("0x4").unwrap()
+                );
+                assert_eq!(entry_function.module().name().as_str(), "aptos_token");
+                entry_function.function().to_string()
+            },
+            _ => panic!("Expected an entry function payload"),
+        }
+    }
+
+    #[test]
+    fn test_token_v2_mint() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut account = LocalAccount::generate(&mut rng);
+        let mut creator = TokenV2MintGeneratorCreator::new(
+            TransactionFactory::new(ChainId::test()),
+            "collection".to_string(),
+            100,
+        );
+        let mut generator = creator.create_transaction_generator();
+
+        // The collection is created before the first mint.
+        let txns = generator.generate_transactions(&mut account, 2);
+        assert_eq!(
+            txns.iter().map(entry_function_name).collect::<Vec<_>>(),
+            vec!["create_collection", "mint", "mint"]
+        );
+        for (i, txn) in txns.iter().enumerate() {
+            assert_eq!(txn.sequence_number(), i as u64);
+        }
+
+        let txns = generator.generate_transactions(&mut account, 2);
+        assert_eq!(
+            txns.iter().map(entry_function_name).collect::<Vec<_>>(),
+            vec!["mint", "mint"]
+        );
+    }
+}
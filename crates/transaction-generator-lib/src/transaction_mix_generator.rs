@@ -10,7 +10,8 @@ use std::sync::{
 
 pub struct PhasedTxnMixGenerator {
     rng: StdRng,
-    // for each phase, list of transaction mixes.
+    // for each phase, list of transaction mixes. Generators with zero weight are never picked,
+    // and a phase where all weights are zero produces no transactions.
     txn_mix_per_phase: Vec<Vec<(Box<dyn TransactionGenerator>, usize)>>,
     total_weight_per_phase: Vec<usize>,
     phase: Arc<AtomicUsize>,
@@ -48,6 +49,9 @@ impl TransactionGenerator for PhasedTxnMixGenerator {
             self.phase.load(Ordering::Relaxed)
         };
 
+        if self.total_weight_per_phase[phase] == 0 {
+            return Vec::new();
+        }
         let mut picked = self.rng.gen_range(0, self.total_weight_per_phase[phase]);
         for (gen, weight) in &mut self.txn_mix_per_phase[phase] {
             if picked < *weight {
@@ -97,3 +101,72 @@ impl TransactionGeneratorCreator for PhasedTxnMixGeneratorCreator {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TransferGenerator;
+
+    /// Counts how often the mix picks the wrapped transfer generator.
+    struct CountingGenerator {
+        inner: TransferGenerator,
+        num_calls: Arc<AtomicUsize>,
+    }
+
+    impl TransactionGenerator for CountingGenerator {
+        fn generate_transactions(
+            &mut self,
+            account: &mut LocalAccount,
+            num_to_create: usize,
+        ) -> Vec<SignedTransaction> {
+            self.num_calls.fetch_add(1, Ordering::Relaxed);
+            self.inner.generate_transactions(account, num_to_create)
+        }
+    }
+
+    fn counting_mix(weights: &[usize]) -> (PhasedTxnMixGenerator, Vec<Arc<AtomicUsize>>) {
+        let num_calls: Vec<_> = weights
+            .iter()
+            .map(|_| Arc::new(AtomicUsize::new(0)))
+            .collect();
+        let txn_mix = weights
+            .iter()
+            .zip(&num_calls)
+            .map(|(weight, num_calls)| {
+                let generator: Box<dyn TransactionGenerator> = Box::new(CountingGenerator {
+                    inner: TransferGenerator::default(),
+                    num_calls: num_calls.clone(),
+                });
+                (generator, *weight)
+            })
+            .collect();
+        let generator = PhasedTxnMixGenerator::new(
+            StdRng::seed_from_u64(0),
+            vec![txn_mix],
+            Arc::new(AtomicUsize::new(0)),
+        );
+        (generator, num_calls)
+    }
+
+    #[test]
+    fn test_zero_weights_are_never_picked() {
+        let mut account = LocalAccount::generate(&mut StdRng::seed_from_u64(1));
+
+        let (mut generator, num_calls) = counting_mix(&[0, 1, 0]);
+        for _ in 0..100 {
+            assert_eq!(generator.generate_transactions(&mut account, 1).len(), 1);
+        }
+        let num_calls: Vec<usize> = num_calls
+            .iter()
+            .map(|num_calls| num_calls.load(Ordering::Relaxed))
+            .collect();
+        assert_eq!(num_calls, vec![0, 100, 0]);
+
+        // With all generators disabled, no transactions are produced.
+        let (mut generator, num_calls) = counting_mix(&[0, 0]);
+        assert!(generator.generate_transactions(&mut account, 1).is_empty());
+        assert!(num_calls
+            .iter()
+            .all(|num_calls| num_calls.load(Ordering::Relaxed) == 0));
+    }
+}
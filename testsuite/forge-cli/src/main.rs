@@ -360,6 +360,23 @@ fn main() -> Result<()> {
     }
 }
 
+/// Cancels the run on the first Ctrl-C, so that the network tests remove the chaos they are
+/// injecting instead of leaving it on the swarm. A second Ctrl-C exits right away.
+fn cancel_on_ctrl_c(cancellation: CancellationToken) {
+    thread::spawn(move || {
+        let runtime = Runtime::new().unwrap();
+        runtime.block_on(async {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                println!("Cancelling the run, press Ctrl-C again to exit");
+                cancellation.cancel();
+                if tokio::signal::ctrl_c().await.is_ok() {
+                    process::exit(130);
+                }
+            }
+        });
+    });
+}
+
 pub fn run_forge<F: Factory>(
     global_duration: Duration,
     tests: ForgeConfig<'_>,
@@ -375,6 +392,8 @@ pub fn run_forge<F: Factory>(
         return Ok(());
     }
 
+    cancel_on_ctrl_c(forge.cancellation_token());
+
     match forge.run() {
         Ok(report) => {
             if let Some(mut changelog) = logs {
@@ -1094,20 +1113,25 @@ fn network_bandwidth(config: ForgeConfig) -> ForgeConfig {
 }
 
 fn three_region_simulation_with_different_node_speed(config: ForgeConfig) -> ForgeConfig {
+    static EXECUTION_DELAY_TEST: ExecutionDelayTest =
+        ExecutionDelayTest::new(ExecutionDelayConfig {
+            inject_delay_node_fraction: 0.5,
+            inject_delay_max_transaction_percentage: 40,
+            inject_delay_per_transaction_ms: 2,
+            target_region: None,
+            regions: None,
+            ramp_duration: None,
+        });
+    static TEST: CompositeNetworkTest = CompositeNetworkTest {
+        wrapper: &EXECUTION_DELAY_TEST,
+        test: &ThreeRegionSameCloudSimulationTest,
+    };
+
     config
         .with_initial_validator_count(NonZeroUsize::new(30).unwrap())
         .with_initial_fullnode_count(30)
         .with_emit_job(EmitJobRequest::default().mode(EmitJobMode::ConstTps { tps: 5000 }))
-        .with_network_tests(vec![&CompositeNetworkTest {
-            wrapper: &ExecutionDelayTest {
-                add_execution_delay: ExecutionDelayConfig {
-                    inject_delay_node_fraction: 0.5,
-                    inject_delay_max_transaction_percentage: 40,
-                    inject_delay_per_transaction_ms: 2,
-                },
-            },
-            test: &ThreeRegionSameCloudSimulationTest,
-        }])
+        .with_network_tests(vec![&TEST])
         .with_node_helm_config_fn(Arc::new(move |helm_values| {
             helm_values["validator"]["config"]["api"]["failpoints_enabled"] = true.into();
             // helm_values["validator"]["config"]["consensus"]["max_sending_block_txns"] =
@@ -1584,6 +1608,9 @@ fn quorum_store_reconfig_enable_test(forge_config: ForgeConfig<'static>) -> Forg
 }
 
 fn multi_region_multi_cloud_simulation_test(config: ForgeConfig<'static>) -> ForgeConfig<'static> {
+    static MULTI_REGION_TEST: MultiRegionMultiCloudSimulationTest =
+        MultiRegionMultiCloudSimulationTest::new(None, None, false, None, None, None);
+
     config
         .with_initial_validator_count(NonZeroUsize::new(100).unwrap())
         .with_emit_job(
@@ -1593,7 +1620,7 @@ fn multi_region_multi_cloud_simulation_test(config: ForgeConfig<'static>) -> For
                 })
                 .txn_expiration_time_secs(5 * 60),
         )
-        .with_network_tests(vec![&MultiRegionMultiCloudSimulationTest {}])
+        .with_network_tests(vec![&MULTI_REGION_TEST])
         .with_genesis_helm_config_fn(Arc::new(|helm_values| {
             // no epoch change.
             helm_values["chain"]["epoch_duration_secs"] = (24 * 3600).into();
@@ -7,6 +7,7 @@ use crate::{
 };
 use anyhow::bail;
 use aptos_logger::info;
+use aptos_sdk::types::PeerId;
 use std::process::{Command, Stdio};
 use tempfile::TempDir;
 
@@ -25,6 +26,11 @@ macro_rules! BANDWIDTH_NETWORK_CHAOS_TEMPLATE {
         "chaos/network_bandwidth.yaml"
     };
 }
+macro_rules! BANDWIDTH_NETWORK_CHAOS_TARGET_TEMPLATE {
+    () => {
+        "chaos/network_bandwidth_target.yaml"
+    };
+}
 
 macro_rules! NETWORK_LOSS_CHAOS_TEMPLATE {
     () => {
@@ -135,25 +141,6 @@ impl K8sSwarm {
         ))
     }
 
-    fn create_network_bandwidth_template(
-        &self,
-        swarm_network_bandwidth: &SwarmNetworkBandwidth,
-    ) -> Result<String> {
-        let mut network_chaos_specs = vec![];
-
-        for group_network_bandwidth in &swarm_network_bandwidth.group_network_bandwidths {
-            network_chaos_specs.push(format!(
-                include_str!(BANDWIDTH_NETWORK_CHAOS_TEMPLATE!()),
-                namespace = self.kube_namespace,
-                rate = group_network_bandwidth.rate,
-                limit = group_network_bandwidth.limit,
-                buffer = group_network_bandwidth.buffer,
-            ));
-        }
-
-        Ok(network_chaos_specs.join("\n---\n"))
-    }
-
     fn create_network_loss_template(
         &self,
         swarm_network_loss: &SwarmNetworkLoss,
@@ -170,7 +157,11 @@ impl K8sSwarm {
         match chaos {
             SwarmChaos::Delay(c) => self.create_network_delay_template(c),
             SwarmChaos::Partition(c) => self.create_network_partition_template(c),
-            SwarmChaos::Bandwidth(c) => self.create_network_bandwidth_template(c),
+            SwarmChaos::Bandwidth(c) => Ok(create_network_bandwidth_template(
+                &self.kube_namespace,
+                c,
+                |node| self.validator(node).map(|v| v.name()),
+            )),
             SwarmChaos::Loss(c) => self.create_network_loss_template(c),
         }
     }
@@ -219,3 +210,141 @@ impl K8sSwarm {
         Ok(())
     }
 }
+
+/// Creates the NetworkChaos specs limiting the bandwidth of each group. Nodes are selected by
+/// instance, i.e. by the name of their validator, as returned by 'validator_name'. Without
+/// target nodes, all the outgoing traffic of the source nodes is limited.
+fn create_network_bandwidth_template<'a>(
+    kube_namespace: &str,
+    swarm_network_bandwidth: &SwarmNetworkBandwidth,
+    validator_name: impl Fn(PeerId) -> Option<&'a str>,
+) -> String {
+    let instance_selector = |nodes: &[PeerId]| {
+        let instance_labels = nodes
+            .iter()
+            .map(|node| validator_name(*node).unwrap_or("invalid-node"))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "{{ key: app.kubernetes.io/instance, operator: In, values: [{}] }}",
+            instance_labels
+        )
+    };
+
+    let mut network_chaos_specs = vec![];
+    for group_network_bandwidth in &swarm_network_bandwidth.group_network_bandwidths {
+        let source_selector = if group_network_bandwidth.source_nodes.is_empty() {
+            "labelSelectors:\n      app.kubernetes.io/name: validator".to_string()
+        } else {
+            format!(
+                "expressionSelectors: [{}]",
+                instance_selector(&group_network_bandwidth.source_nodes)
+            )
+        };
+        let mut network_chaos_spec = format!(
+            include_str!(BANDWIDTH_NETWORK_CHAOS_TEMPLATE!()),
+            name = &group_network_bandwidth.name,
+            namespace = kube_namespace,
+            rate = group_network_bandwidth.rate,
+            limit = group_network_bandwidth.limit,
+            buffer = group_network_bandwidth.buffer,
+            source_selector = &source_selector,
+        );
+        if !group_network_bandwidth.target_nodes.is_empty() {
+            network_chaos_spec.push_str(&format!(
+                include_str!(BANDWIDTH_NETWORK_CHAOS_TARGET_TEMPLATE!()),
+                namespace = kube_namespace,
+                target_selectors = instance_selector(&group_network_bandwidth.target_nodes),
+            ));
+        }
+        network_chaos_specs.push(network_chaos_spec);
+    }
+
+    network_chaos_specs.join("\n---\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GroupNetworkBandwidth;
+
+    fn group_network_bandwidth(
+        source_nodes: Vec<PeerId>,
+        target_nodes: Vec<PeerId>,
+    ) -> SwarmNetworkBandwidth {
+        SwarmNetworkBandwidth {
+            group_network_bandwidths: vec![GroupNetworkBandwidth {
+                name: "forge-namespace-1000mbps-bandwidth".to_owned(),
+                source_nodes,
+                target_nodes,
+                rate: 1000,
+                limit: 20971520,
+                buffer: 10000,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_network_bandwidth_template_without_targets() {
+        let template = create_network_bandwidth_template(
+            "forge",
+            &group_network_bandwidth(vec![], vec![]),
+            |_| None,
+        );
+        assert_eq!(
+            template,
+            r#"kind: NetworkChaos
+apiVersion: chaos-mesh.org/v1alpha1
+metadata:
+  namespace: forge
+  name: forge-namespace-1000mbps-bandwidth
+spec:
+  action: bandwidth
+  mode: all
+  selector:
+    namespaces:
+      - forge
+    labelSelectors:
+      app.kubernetes.io/name: validator
+  bandwidth:
+    rate: "1000mbps"
+    limit: 20971520
+    buffer: 10000
+"#
+        );
+    }
+
+    #[test]
+    fn test_network_bandwidth_template_with_targets() {
+        let (source, target) = (PeerId::random(), PeerId::random());
+        let template = create_network_bandwidth_template(
+            "forge",
+            &group_network_bandwidth(vec![source], vec![target]),
+            |node| {
+                if node == source {
+                    Some("aptos-node-0-validator")
+                } else if node == target {
+                    Some("aptos-node-1-validator")
+                } else {
+                    None
+                }
+            },
+        );
+        assert!(template.contains(
+            "    expressionSelectors: [{ key: app.kubernetes.io/instance, operator: In, values: [aptos-node-0-validator] }]\n"
+        ));
+        assert!(template.ends_with(
+            r#"    buffer: 10000
+  # Only limit the packets sent to the target, so that each direction of a pair of groups
+  # can be limited separately
+  direction: to
+  target:
+    selector:
+      namespaces:
+        - forge
+      expressionSelectors: [{ key: app.kubernetes.io/instance, operator: In, values: [aptos-node-1-validator] }]
+    mode: all
+"#
+        ));
+    }
+}
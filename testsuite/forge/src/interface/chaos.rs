@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_sdk::types::PeerId;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
 
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
@@ -12,7 +13,7 @@ pub enum SwarmChaos {
     Loss(SwarmNetworkLoss),
 }
 
-#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Deserialize, Serialize)]
 pub struct SwarmNetworkDelay {
     pub group_network_delays: Vec<GroupNetworkDelay>,
 }
@@ -23,7 +24,7 @@ impl Display for SwarmNetworkDelay {
     }
 }
 
-#[derive(Eq, Hash, PartialEq, Debug, Clone)]
+#[derive(Eq, Hash, PartialEq, Debug, Clone, Deserialize, Serialize)]
 pub struct GroupNetworkDelay {
     pub name: String,
     pub source_nodes: Vec<PeerId>,
@@ -58,6 +59,10 @@ impl Display for SwarmNetworkBandwidth {
 #[derive(Eq, Hash, PartialEq, Debug, Clone)]
 pub struct GroupNetworkBandwidth {
     pub name: String,
+    /// Nodes whose outgoing traffic is limited, all validators if empty
+    pub source_nodes: Vec<PeerId>,
+    /// Nodes the limited traffic is sent to, every node in the namespace if empty
+    pub target_nodes: Vec<PeerId>,
     /// Rate in megabytes per second
     pub rate: u64,
     pub limit: u64,
@@ -8,7 +8,13 @@ use crate::{
     CoreContext, Result, Swarm, TestReport,
 };
 use aptos_transaction_emitter_lib::{EmitJobRequest, TxnStats};
-use std::time::Duration;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 use tokio::runtime::Runtime;
 
 /// The testing interface which defines a test written with full control over an existing network.
@@ -19,6 +25,25 @@ pub trait NetworkTest: Test {
     fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> Result<()>;
 }
 
+/// Signals an ongoing chaos injection to stop. Clones share the same state, so the token can
+/// be cancelled from another thread while the injection is running.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
 pub struct NetworkContext<'t> {
     core: CoreContext,
     swarm: &'t mut dyn Swarm,
@@ -27,6 +52,7 @@ pub struct NetworkContext<'t> {
     pub emit_job: EmitJobRequest,
     pub success_criteria: SuccessCriteria,
     pub runtime: Runtime,
+    cancellation: CancellationToken,
 }
 
 impl<'t> NetworkContext<'t> {
@@ -37,6 +63,7 @@ impl<'t> NetworkContext<'t> {
         global_duration: Duration,
         emit_job: EmitJobRequest,
         success_criteria: SuccessCriteria,
+        cancellation: CancellationToken,
     ) -> Self {
         Self {
             core,
@@ -46,6 +73,7 @@ impl<'t> NetworkContext<'t> {
             emit_job,
             success_criteria,
             runtime: Runtime::new().unwrap(),
+            cancellation,
         }
     }
 
@@ -57,6 +85,11 @@ impl<'t> NetworkContext<'t> {
         &mut self.core
     }
 
+    /// The token cancelling the test run, that chaos injection should check to stop early
+    pub fn cancellation(&self) -> &CancellationToken {
+        &self.cancellation
+    }
+
     pub fn check_for_success(
         &mut self,
         stats: &TxnStats,
@@ -318,6 +318,7 @@ pub struct Forge<'cfg, F> {
     tests: ForgeConfig<'cfg>,
     global_duration: Duration,
     factory: F,
+    cancellation: CancellationToken,
 }
 
 impl<'cfg, F: Factory> Forge<'cfg, F> {
@@ -332,9 +333,16 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
             tests,
             global_duration,
             factory,
+            cancellation: CancellationToken::new(),
         }
     }
 
+    /// Returns the token to cancel the run (e.g. from a signal handler), in which case the
+    /// network tests stop injecting chaos and remove what they injected so far.
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+
     pub fn list(&self) -> Result<()> {
         for test in self.filter_tests(self.tests.all_tests()) {
             println!("{}: test", test.name());
@@ -427,6 +435,7 @@ impl<'cfg, F: Factory> Forge<'cfg, F> {
                     self.global_duration,
                     self.tests.emit_job_request.clone(),
                     self.tests.success_criteria.clone(),
+                    self.cancellation.clone(),
                 );
                 let result = run_test(|| test.run(&mut network_ctx));
                 report.report_text(result.to_string());
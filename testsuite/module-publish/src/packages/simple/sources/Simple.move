@@ -54,6 +54,13 @@ module 0xABCD::simple {
     public entry fun nop_5_signers(_s1: &signer, _s2: &signer, _s3: &signer, _s4: &signer, _s5: &signer) {
     }
 
+    // Always abort with the given `code`.
+    // Measures the cost of a failing transaction, which goes through
+    // the abort path (and still charges gas) instead of the success one.
+    public entry fun abort_with_code(_s: &signer, code: u64) {
+        abort code
+    }
+
     // Test simple CPU usage. Loop as defined by the input `count`.
     // Not a true test of CPU usage given the number of instructions
     // used, but a simple reference to computation with no data access.
@@ -63,6 +70,17 @@ module 0xABCD::simple {
         }
     }
 
+    // Test pure computation cost. Runs a tight arithmetic loop `iterations`
+    // times, with no data access, so that only execution is measured.
+    public entry fun arithmetic_loop(_s: &signer, iterations: u64) {
+        let sum = 0;
+        let i = 0;
+        while (i < iterations) {
+            sum = (sum * 31 + i % 1000007) % 1000007;
+            i = i + 1;
+        }
+    }
+
     // Counter
     // This is a constant to change to check versioning of the module published.
     // In a simple way this can be used as a verion info, and incremented by 1
@@ -1,50 +1,284 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{LoadDestination, NetworkLoadTest};
-use aptos_forge::{NetworkContext, NetworkTest, Swarm, SwarmExt, Test};
-use aptos_logger::info;
+use crate::{
+    multi_region_simulation_test::{get_swarm_validators_by_region, RegionAssignment},
+    LoadDestination, NetworkLoadTest,
+};
+use aptos_forge::{CancellationToken, NetworkContext, NetworkTest, NodeExt, Swarm, SwarmExt, Test};
+use aptos_logger::{info, warn};
+use aptos_rest_client::Client as RestClient;
+use aptos_types::PeerId;
 use rand::Rng;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::{
+        mpsc::{self, RecvTimeoutError, Sender},
+        Mutex,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
 use tokio::runtime::Runtime;
 
-fn add_execution_delay(swarm: &mut dyn Swarm, config: &ExecutionDelayConfig) -> anyhow::Result<()> {
-    let runtime = Runtime::new().unwrap();
-    let validators = swarm.get_validator_clients_with_names();
+/// Interval at which the execution delay is updated while it is ramping up.
+const RAMP_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
 
-    runtime.block_on(async {
-        let mut rng = rand::thread_rng();
-        for (name, validator) in validators {
-            let sleep_percentage = if rng.gen_bool(config.inject_delay_node_fraction) {
+/// Injects the items into the target one by one, checking the token before each. If it is
+/// cancelled, the items injected so far are removed (in reverse order, failures are only
+/// logged) and an error is returned, so that no partial chaos is left behind.
+pub(crate) fn inject_or_rollback<S: ?Sized, T>(
+    target: &mut S,
+    items: &[T],
+    cancellation: &CancellationToken,
+    mut inject: impl FnMut(&mut S, &T) -> anyhow::Result<()>,
+    mut remove: impl FnMut(&mut S, &T) -> anyhow::Result<()>,
+) -> anyhow::Result<()> {
+    for (num_injected, item) in items.iter().enumerate() {
+        if cancellation.is_cancelled() {
+            info!(
+                "Injection cancelled, removing the {} injected item(s)",
+                num_injected
+            );
+            for injected in items[..num_injected].iter().rev() {
+                if let Err(e) = remove(target, injected) {
+                    warn!("Failed to remove injected item: {:?}", e);
+                }
+            }
+            anyhow::bail!("Injection cancelled");
+        }
+        inject(target, item)?;
+    }
+    Ok(())
+}
+
+/// Returns the validators on which execution delay can be injected, i.e. all validators,
+/// or only the ones assigned to the target region of the multi-region simulation.
+fn get_execution_delay_targets(
+    validators_by_region: &BTreeMap<String, Vec<PeerId>>,
+    target_region: Option<&str>,
+) -> anyhow::Result<HashSet<PeerId>> {
+    match target_region {
+        Some(region) => validators_by_region
+            .get(region)
+            .map(|validators| validators.iter().cloned().collect())
+            .ok_or_else(|| anyhow::anyhow!("Unknown region {} for execution delay", region)),
+        None => Ok(validators_by_region.values().flatten().cloned().collect()),
+    }
+}
+
+/// Adds execution delay as configured. If it ramps up, returns the background task doing
+/// so, which the caller has to stop.
+fn add_execution_delay(
+    swarm: &mut dyn Swarm,
+    config: &ExecutionDelayConfig,
+    cancellation: &CancellationToken,
+) -> anyhow::Result<Option<ExecutionDelayRamp>> {
+    let validators_by_region = match config.regions {
+        Some(regions) => regions.get_or_assign(&*swarm),
+        None => get_swarm_validators_by_region(&*swarm, None),
+    };
+    let targets =
+        get_execution_delay_targets(&validators_by_region, config.target_region.as_deref())?;
+    let node_fractions = targets
+        .into_iter()
+        .map(|peer_id| (peer_id, config.inject_delay_node_fraction))
+        .collect();
+
+    match config.ramp_duration {
+        Some(ramp_duration) => {
+            if cancellation.is_cancelled() {
+                anyhow::bail!("Injection cancelled");
+            }
+            let validators = choose_sleep_percentages(swarm, config, &node_fractions);
+            Ok(Some(start_execution_delay_ramp(
+                validators,
+                config,
+                ramp_duration,
+                cancellation.clone(),
+            )))
+        },
+        None => {
+            add_execution_delay_with_node_fractions(swarm, config, &node_fractions, cancellation)?;
+            Ok(None)
+        },
+    }
+}
+
+/// Returns the percentage of transactions to delay at the given time since the start of the
+/// ramp, growing linearly from 0 to 'max_percentage' over 'ramp_duration', then held.
+fn ramp_percentage(max_percentage: u32, elapsed: Duration, ramp_duration: Duration) -> u32 {
+    if elapsed >= ramp_duration {
+        return max_percentage;
+    }
+    (max_percentage as u128 * elapsed.as_millis() / ramp_duration.as_millis()) as u32
+}
+
+/// The background task ramping up the execution delay, stopped when dropping the sender.
+struct ExecutionDelayRamp {
+    stop_sender: Sender<()>,
+    handle: JoinHandle<()>,
+}
+
+impl ExecutionDelayRamp {
+    fn stop(self) {
+        drop(self.stop_sender);
+        if self.handle.join().is_err() {
+            warn!("Execution delay ramp-up task panicked");
+        }
+    }
+}
+
+/// Starts a background task that periodically raises the percentage of delayed transactions
+/// of each validator, from 0 to the chosen percentage, over the ramp duration. If the token
+/// is cancelled meanwhile, the task removes the delay added so far and stops.
+fn start_execution_delay_ramp(
+    validators: Vec<(String, RestClient, u32)>,
+    config: &ExecutionDelayConfig,
+    ramp_duration: Duration,
+    cancellation: CancellationToken,
+) -> ExecutionDelayRamp {
+    let delay_ms = config.inject_delay_per_transaction_ms;
+    let (stop_sender, stop_receiver) = mpsc::channel::<()>();
+    let handle = thread::spawn(move || {
+        let runtime = Runtime::new().unwrap();
+        let start = Instant::now();
+        loop {
+            if cancellation.is_cancelled() {
+                info!("Execution delay ramp-up cancelled, removing the delay");
+                for (name, validator, _) in &validators {
+                    if let Err(e) =
+                        runtime.block_on(set_execution_delay(name, validator, 0, delay_ms))
+                    {
+                        warn!("Failed to remove execution delay: {:?}", e);
+                    }
+                }
+                return;
+            }
+            let elapsed = start.elapsed();
+            for (name, validator, max_percentage) in &validators {
+                let sleep_percentage = ramp_percentage(*max_percentage, elapsed, ramp_duration);
+                if let Err(e) = runtime.block_on(set_execution_delay(
+                    name,
+                    validator,
+                    sleep_percentage,
+                    delay_ms,
+                )) {
+                    warn!("Failed to ramp up execution delay: {:?}", e);
+                }
+            }
+            if elapsed >= ramp_duration {
+                info!("Execution delay ramp-up finished");
+                return;
+            }
+            match stop_receiver.recv_timeout(RAMP_UPDATE_INTERVAL) {
+                Err(RecvTimeoutError::Timeout) => {},
+                Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    });
+
+    ExecutionDelayRamp {
+        stop_sender,
+        handle,
+    }
+}
+
+/// Chooses the percentage of delayed transactions of each validator: validators are
+/// delayed with their probability in 'node_fractions' (0 if absent), with a random
+/// percentage up to `inject_delay_max_transaction_percentage`.
+fn choose_sleep_percentages(
+    swarm: &mut dyn Swarm,
+    config: &ExecutionDelayConfig,
+    node_fractions: &HashMap<PeerId, f64>,
+) -> Vec<(String, RestClient, u32)> {
+    let mut rng = rand::thread_rng();
+    swarm
+        .validators()
+        .map(|v| {
+            let node_fraction = node_fractions.get(&v.peer_id()).cloned().unwrap_or(0.0);
+            let sleep_percentage = if rng.gen_bool(node_fraction) {
                 rng.gen_range(1_u32, config.inject_delay_max_transaction_percentage)
             } else {
                 0
             };
-            info!(
-                "Validator {} adding {}% of transactions with {}ms execution delay",
-                name, sleep_percentage, config.inject_delay_per_transaction_ms
-            );
-            validator
-                .set_failpoint(
-                    "aptos_vm::execution::user_transaction".to_string(),
-                    format!(
-                        "{}%delay({})",
-                        sleep_percentage, config.inject_delay_per_transaction_ms
-                    ),
-                )
-                .await
-                .map_err(|e| {
-                    anyhow::anyhow!(
-                        "set_failpoint to add execution delay on {} failed, {:?}",
-                        name,
-                        e
-                    )
-                })?;
-        }
-        Ok(())
-    })
+            (v.name().to_string(), v.rest_client(), sleep_percentage)
+        })
+        .collect()
+}
+
+async fn set_execution_delay(
+    name: &str,
+    validator: &RestClient,
+    sleep_percentage: u32,
+    delay_ms: u32,
+) -> anyhow::Result<()> {
+    info!(
+        "Validator {} adding {}% of transactions with {}ms execution delay",
+        name, sleep_percentage, delay_ms
+    );
+    validator
+        .set_failpoint(
+            "aptos_vm::execution::user_transaction".to_string(),
+            format!("{}%delay({})", sleep_percentage, delay_ms),
+        )
+        .await
+        .map_err(|e| {
+            anyhow::anyhow!(
+                "set_failpoint to add execution delay on {} failed, {:?}",
+                name,
+                e
+            )
+        })?;
+    Ok(())
+}
+
+/// Adds execution delay as configured, except that the probability of a validator getting
+/// the delay is given per validator (validators not in the map are not delayed), instead of
+/// `inject_delay_node_fraction`. If the token is cancelled, the delay already added to
+/// validators is removed (see inject_or_rollback). The delay is added at once, so a
+/// configured ramp duration is rejected.
+pub(crate) fn add_execution_delay_with_node_fractions(
+    swarm: &mut dyn Swarm,
+    config: &ExecutionDelayConfig,
+    node_fractions: &HashMap<PeerId, f64>,
+    cancellation: &CancellationToken,
+) -> anyhow::Result<()> {
+    check_no_ramp_duration(config)?;
+    let mut runtime = Runtime::new().unwrap();
+    let validators = choose_sleep_percentages(swarm, config, node_fractions);
+    let delay_ms = config.inject_delay_per_transaction_ms;
+
+    inject_or_rollback(
+        &mut runtime,
+        &validators,
+        cancellation,
+        |runtime, (name, validator, sleep_percentage)| {
+            runtime.block_on(set_execution_delay(
+                name,
+                validator,
+                *sleep_percentage,
+                delay_ms,
+            ))
+        },
+        |runtime, (name, validator, _)| {
+            runtime.block_on(set_execution_delay(name, validator, 0, delay_ms))
+        },
+    )
+}
+
+/// Fails if the execution delay is configured to ramp up, which only ExecutionDelayTest does.
+pub(crate) fn check_no_ramp_duration(config: &ExecutionDelayConfig) -> anyhow::Result<()> {
+    if let Some(ramp_duration) = config.ramp_duration {
+        anyhow::bail!(
+            "Execution delay ramp duration {:?} is only supported by ExecutionDelayTest",
+            ramp_duration
+        );
+    }
+    Ok(())
 }
 
-fn remove_execution_delay(swarm: &mut dyn Swarm) -> anyhow::Result<()> {
+pub(crate) fn remove_execution_delay(swarm: &mut dyn Swarm) -> anyhow::Result<()> {
     let runtime = Runtime::new().unwrap();
     let validators = swarm.get_validator_clients_with_names();
 
@@ -81,19 +315,51 @@ pub struct ExecutionDelayConfig {
     /// Fixed busy-loop delay applied to each transaction that is delayed,
     /// before it is executed.
     pub inject_delay_per_transaction_ms: u32,
+    /// If set, delay is only introduced on nodes in the given region of the
+    /// multi-region simulation (to model a slow datacenter), and the fraction
+    /// above applies to the nodes of that region.
+    pub target_region: Option<String>,
+    /// If set, the assignment of the validators to the regions of the multi-region
+    /// simulation, to find the nodes of the target region, e.g. the one of the simulation
+    /// this test is composed with. Otherwise, validators are split into regions in chunks.
+    pub regions: Option<&'static RegionAssignment>,
+    /// If set, the percentage of delayed transactions of each node ramps up linearly from
+    /// 0 over the given duration (from the start of the test), and is held afterwards, to
+    /// observe how the network adapts. Only applies to ExecutionDelayTest, the correlated
+    /// execution delay of the multi-region simulation rejects it.
+    pub ramp_duration: Option<Duration>,
 }
 
 pub struct ExecutionDelayTest {
     pub add_execution_delay: ExecutionDelayConfig,
+    /// The task ramping up the execution delay (if configured), stopped in finish.
+    ramp: Mutex<Option<ExecutionDelayRamp>>,
+}
+
+impl ExecutionDelayTest {
+    pub const fn new(add_execution_delay: ExecutionDelayConfig) -> Self {
+        Self {
+            add_execution_delay,
+            ramp: Mutex::new(None),
+        }
+    }
 }
 
 impl NetworkLoadTest for ExecutionDelayTest {
     fn setup(&self, ctx: &mut NetworkContext) -> anyhow::Result<LoadDestination> {
-        add_execution_delay(ctx.swarm(), &self.add_execution_delay)?;
+        let cancellation = ctx.cancellation().clone();
+        let ramp = add_execution_delay(ctx.swarm(), &self.add_execution_delay, &cancellation)?;
+        if let Some(previous_ramp) = std::mem::replace(&mut *self.ramp.lock().unwrap(), ramp) {
+            previous_ramp.stop();
+        }
         Ok(LoadDestination::FullnodesOtherwiseValidators)
     }
 
     fn finish(&self, swarm: &mut dyn Swarm) -> anyhow::Result<()> {
+        let ramp = self.ramp.lock().unwrap().take();
+        if let Some(ramp) = ramp {
+            ramp.stop();
+        }
         remove_execution_delay(swarm)
     }
 }
@@ -194,3 +460,98 @@ impl Test for NetworkUnreliabilityTest {
         "NetworkUnreliabilityWrapper"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::multi_region_simulation_test::get_validators_by_region;
+
+    #[test]
+    fn test_get_execution_delay_targets() {
+        let all_validators: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        let validators_by_region = get_validators_by_region(&all_validators);
+
+        let targets = get_execution_delay_targets(&validators_by_region, None).unwrap();
+        assert_eq!(targets.len(), 8);
+
+        let targets =
+            get_execution_delay_targets(&validators_by_region, Some("aws--eu-west-1")).unwrap();
+        assert_eq!(targets, all_validators[2..4].iter().cloned().collect());
+
+        assert!(get_execution_delay_targets(&validators_by_region, Some("unknown")).is_err());
+    }
+
+    #[test]
+    fn test_inject_or_rollback() {
+        let items = ["bandwidth", "delay", "loss"];
+        let mut log = vec![];
+        let cancellation = CancellationToken::new();
+        inject_or_rollback(
+            &mut log,
+            &items,
+            &cancellation,
+            |log, item| {
+                log.push(format!("inject {}", item));
+                Ok(())
+            },
+            |log, item| {
+                log.push(format!("remove {}", item));
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(log, vec!["inject bandwidth", "inject delay", "inject loss"]);
+
+        // Cancelled mid-injection, the injected items are removed.
+        let mut log = vec![];
+        let cancellation = CancellationToken::new();
+        let result = inject_or_rollback(
+            &mut log,
+            &items,
+            &cancellation,
+            |log, item| {
+                log.push(format!("inject {}", item));
+                if *item == "delay" {
+                    cancellation.cancel();
+                }
+                Ok(())
+            },
+            |log, item| {
+                log.push(format!("remove {}", item));
+                Ok(())
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(log, vec![
+            "inject bandwidth",
+            "inject delay",
+            "remove delay",
+            "remove bandwidth"
+        ]);
+    }
+
+    #[test]
+    fn test_ramp_percentage() {
+        let ramp_duration = Duration::from_secs(100);
+        assert_eq!(ramp_percentage(40, Duration::ZERO, ramp_duration), 0);
+        assert_eq!(
+            ramp_percentage(40, Duration::from_secs(25), ramp_duration),
+            10
+        );
+        assert_eq!(
+            ramp_percentage(40, Duration::from_secs(50), ramp_duration),
+            20
+        );
+        assert_eq!(
+            ramp_percentage(40, Duration::from_secs(99), ramp_duration),
+            39
+        );
+        // Held at the max after the ramp.
+        assert_eq!(ramp_percentage(40, ramp_duration, ramp_duration), 40);
+        assert_eq!(
+            ramp_percentage(40, Duration::from_secs(500), ramp_duration),
+            40
+        );
+        assert_eq!(ramp_percentage(40, Duration::ZERO, Duration::ZERO), 40);
+    }
+}
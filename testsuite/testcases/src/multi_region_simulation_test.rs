@@ -1,16 +1,28 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{LoadDestination, NetworkLoadTest};
+use crate::{
+    modifiers::{
+        add_execution_delay_with_node_fractions, check_no_ramp_duration, inject_or_rollback,
+        remove_execution_delay, ExecutionDelayConfig,
+    },
+    LoadDestination, NetworkLoadTest,
+};
+use anyhow::Context;
 use aptos_forge::{
-    GroupNetworkBandwidth, GroupNetworkDelay, NetworkContext, NetworkTest, Swarm, SwarmChaos,
-    SwarmNetworkBandwidth, SwarmNetworkDelay, Test,
+    CancellationToken, GroupNetworkBandwidth, GroupNetworkDelay, NetworkContext, NetworkTest,
+    NodeExt, Swarm, SwarmChaos, SwarmNetworkBandwidth, SwarmNetworkDelay, Test,
 };
-use aptos_logger::info;
+use aptos_logger::{info, warn};
 use aptos_types::PeerId;
-use csv::Reader;
-use itertools::{self, Itertools};
-use std::collections::BTreeMap;
+use csv::{Reader, Writer};
+use itertools::Itertools;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tokio::runtime::Runtime;
 
 /// The link stats are obtained from https://github.com/doitintl/intercloud-throughput/blob/master/results_202202/results.csv
 /// The four regions were hand-picked from the dataset to simulate a multi-region setup
@@ -23,7 +35,93 @@ macro_rules! FOUR_REGION_LINK_STATS_CSV {
 
 /// A test to simulate network between multiple regions in different clouds.
 /// It currently supports only 4 regions, due to ChaosMesh limitations.
-pub struct MultiRegionMultiCloudSimulationTest {}
+pub struct MultiRegionMultiCloudSimulationTest {
+    /// If set, the network delay is loaded from the given (json serialized) SwarmNetworkDelay,
+    /// instead of being computed from the link stats table and the validators, to reproduce
+    /// an exact network scenario.
+    pub delay_override: Option<PathBuf>,
+    /// If set, execution delay is also injected, with the probability of a validator being
+    /// delayed weighted by the average latency of its region (the given node fraction
+    /// applies to the highest-latency region), so network-distant nodes are also slow.
+    pub correlated_execution_delay: Option<ExecutionDelayConfig>,
+    /// If set, the network topology (and execution delay) is only computed and logged, but
+    /// not injected, e.g. to review a new configuration without perturbing the swarm.
+    pub dry_run: bool,
+    /// If set, the number of transactions committed by each validator is collected when the
+    /// test finishes, and written as CSV to the given path, aggregated per region (see
+    /// write_region_throughput_csv), to correlate region latency with throughput.
+    pub throughput_output: Option<PathBuf>,
+    /// The assignment of the validators to regions, made in setup (unless a test composed
+    /// with this one already made it), so that the throughput written in finish and the
+    /// execution delay of a target region (see ExecutionDelayConfig) use the same regions as
+    /// the injected chaos.
+    pub regions: RegionAssignment,
+    /// If set, the (bitrate, latency) used for any pair of regions missing from the link
+    /// stats table, so that partial tables can be used. Otherwise, a missing pair panics.
+    pub default_link_stats: Option<(u64, f64)>,
+}
+
+impl MultiRegionMultiCloudSimulationTest {
+    pub const fn new(
+        delay_override: Option<PathBuf>,
+        correlated_execution_delay: Option<ExecutionDelayConfig>,
+        dry_run: bool,
+        throughput_output: Option<PathBuf>,
+        region_labels: Option<HashMap<String, String>>,
+        default_link_stats: Option<(u64, f64)>,
+    ) -> Self {
+        Self {
+            delay_override,
+            correlated_execution_delay,
+            dry_run,
+            throughput_output,
+            regions: RegionAssignment::new(region_labels),
+            default_link_stats,
+        }
+    }
+
+    /// Computes the chaos to inject into the swarm, and the execution delay probability of
+    /// each validator (if correlated execution delay is configured). In dry run mode, the
+    /// computed chaos is logged, and nothing is returned to be injected.
+    fn get_chaos(
+        &self,
+        validators_by_region: &BTreeMap<String, Vec<PeerId>>,
+    ) -> anyhow::Result<(Vec<SwarmChaos>, Option<HashMap<PeerId, f64>>)> {
+        // fail before anything is injected, as the correlated execution delay can't ramp up
+        if let Some(config) = &self.correlated_execution_delay {
+            check_no_ramp_duration(config)?;
+        }
+        let (delay, bandwidth) = create_multi_region_swarm_network_chaos(
+            &get_link_stats_table(),
+            validators_by_region,
+            self.default_link_stats,
+        );
+        let delay = match &self.delay_override {
+            Some(path) => {
+                info!("Loading network delay from {:?}", path);
+                load_swarm_network_delay(path)?
+            },
+            None => delay,
+        };
+        let node_fractions = self.correlated_execution_delay.as_ref().map(|config| {
+            get_correlated_node_fractions(validators_by_region, config.inject_delay_node_fraction)
+        });
+
+        if self.dry_run {
+            info!(
+                "Dry run, not injecting: {}, {}, execution delay node fractions {:?}",
+                delay, bandwidth, node_fractions
+            );
+            return Ok((vec![], None));
+        }
+
+        // bandwidth limit is injected before network delay
+        Ok((
+            vec![SwarmChaos::Bandwidth(bandwidth), SwarmChaos::Delay(delay)],
+            node_fractions,
+        ))
+    }
+}
 
 impl Test for MultiRegionMultiCloudSimulationTest {
     fn name(&self) -> &'static str {
@@ -32,9 +130,16 @@ impl Test for MultiRegionMultiCloudSimulationTest {
 }
 
 fn get_link_stats_table() -> BTreeMap<String, BTreeMap<String, (u64, f64)>> {
+    parse_link_stats_table(include_bytes!(FOUR_REGION_LINK_STATS_CSV!()))
+}
+
+/// Parses link stats CSV rows (sending region, receiving region, bitrate in bps, average
+/// rtt) into a table from sending to receiving region. Links are directional: the stats
+/// of the way back are only known if the table also has the reverse row.
+fn parse_link_stats_table(csv: &[u8]) -> BTreeMap<String, BTreeMap<String, (u64, f64)>> {
     let mut stats_table = BTreeMap::new();
 
-    let mut rdr = Reader::from_reader(include_bytes!(FOUR_REGION_LINK_STATS_CSV!()).as_slice());
+    let mut rdr = Reader::from_reader(csv);
     rdr.deserialize()
         .for_each(|result: Result<(String, String, u64, f64), _>| {
             if let Ok((from, to, bitrate, latency)) = result {
@@ -47,101 +152,413 @@ fn get_link_stats_table() -> BTreeMap<String, BTreeMap<String, (u64, f64)>> {
     stats_table
 }
 
-/// Creates a SwarmNetworkDelay
-fn create_multi_region_swarm_network_chaos(
-    all_validators: Vec<PeerId>,
-) -> (SwarmNetworkDelay, SwarmNetworkBandwidth) {
-    let link_stats_table = get_link_stats_table();
+/// Returns the (bitrate, latency) from one region to another, falling back to the default
+/// (and logging the pair) if the table has no stats for the pair.
+fn get_link_stats(
+    link_stats_table: &BTreeMap<String, BTreeMap<String, (u64, f64)>>,
+    from_region: &str,
+    to_region: &str,
+    default_link_stats: Option<(u64, f64)>,
+) -> (u64, f64) {
+    if let Some(link_stats) = link_stats_table
+        .get(from_region)
+        .and_then(|stats| stats.get(to_region))
+    {
+        return *link_stats;
+    }
+    let default_link_stats = default_link_stats.unwrap_or_else(|| {
+        panic!(
+            "No link stats from {} to {}, and no default link stats",
+            from_region, to_region
+        )
+    });
+    warn!(
+        "No link stats from {} to {}, using the default {:?}",
+        from_region, to_region, default_link_stats
+    );
+    default_link_stats
+}
 
-    assert!(all_validators.len() >= link_stats_table.len());
+/// Assigns the validators to the given regions, in equally sized chunks following the order
+/// of the validators and the regions. The remaining validators are assigned to the first
+/// region. The assignment only depends on the order of the validators (and of the regions),
+/// so the network delay and bandwidth computed from it are consistent.
+pub(crate) fn assign_validators_to_regions(
+    all_validators: &[PeerId],
+    regions: impl IntoIterator<Item = String>,
+) -> BTreeMap<String, Vec<PeerId>> {
+    let regions = regions.into_iter().collect::<Vec<_>>();
+    let number_of_regions = regions.len();
+    assert!(number_of_regions > 0);
+    assert!(all_validators.len() >= number_of_regions);
 
-    let number_of_regions = link_stats_table.len();
     let approx_validators_per_region = all_validators.len() / number_of_regions;
 
     let validator_chunks = all_validators.chunks_exact(approx_validators_per_region);
+    let mut validators_by_region: BTreeMap<String, Vec<PeerId>> = regions
+        .into_iter()
+        .zip(validator_chunks.clone())
+        .map(|(region, chunk)| (region, chunk.to_vec()))
+        .collect();
 
-    let (mut group_network_delays, group_network_bandwidths): (
-        Vec<GroupNetworkDelay>,
-        Vec<GroupNetworkBandwidth>,
-    ) = validator_chunks
+    let remaining_validators = validator_chunks
         .clone()
-        .zip(link_stats_table.iter().clone())
+        .skip(number_of_regions)
+        .flatten()
+        .chain(validator_chunks.remainder().iter())
+        .cloned();
+    if let Some(first_region_validators) = validators_by_region.values_mut().next() {
+        first_region_validators.extend(remaining_validators);
+    }
+
+    validators_by_region
+}
+
+/// Assigns the validators to the (sorted) regions of the link stats table, see
+/// assign_validators_to_regions.
+pub(crate) fn get_validators_by_region(all_validators: &[PeerId]) -> BTreeMap<String, Vec<PeerId>> {
+    assign_validators_to_regions(all_validators, get_link_stats_table().into_keys())
+}
+
+/// Groups the validators by their region label, if all of them are labeled with a region of
+/// the link stats table (otherwise returns None).
+fn get_validators_by_region_label(
+    all_validators: &[PeerId],
+    region_labels: &HashMap<PeerId, String>,
+) -> Option<BTreeMap<String, Vec<PeerId>>> {
+    let link_stats_table = get_link_stats_table();
+
+    let mut validators_by_region: BTreeMap<String, Vec<PeerId>> = BTreeMap::new();
+    for peer_id in all_validators {
+        let region = region_labels
+            .get(peer_id)
+            .filter(|region| link_stats_table.contains_key(*region))?;
+        validators_by_region
+            .entry(region.clone())
+            .or_default()
+            .push(*peer_id);
+    }
+    Some(validators_by_region)
+}
+
+/// Assigns the validators of the swarm to regions, by their region label (by validator name)
+/// if all of them are labeled (see get_validators_by_region_label), otherwise in chunks (see
+/// get_validators_by_region).
+pub(crate) fn get_swarm_validators_by_region(
+    swarm: &dyn Swarm,
+    region_labels: Option<&HashMap<String, String>>,
+) -> BTreeMap<String, Vec<PeerId>> {
+    let all_validators = swarm.validators().map(|v| v.peer_id()).collect::<Vec<_>>();
+    let region_labels = match region_labels {
+        Some(region_labels) => region_labels,
+        None => return get_validators_by_region(&all_validators),
+    };
+
+    let region_labels: HashMap<PeerId, String> = swarm
+        .validators()
+        .filter_map(|v| {
+            region_labels
+                .get(v.name())
+                .map(|region| (v.peer_id(), region.clone()))
+        })
+        .collect();
+    get_validators_by_region_label(&all_validators, &region_labels).unwrap_or_else(|| {
+        info!("Region labels unavailable for some validators, splitting in chunks");
+        get_validators_by_region(&all_validators)
+    })
+}
+
+/// The assignment of the validators of the swarm to regions, made once and shared by the
+/// tests using it, e.g. the multi-region simulation and the execution delay of one of its
+/// regions, when composed.
+pub struct RegionAssignment {
+    /// If set, the region of each validator (by validator name), to place the validators in
+    /// their configured region instead of splitting them into chunks. Falls back to chunking
+    /// if any validator has no (known) region.
+    region_labels: Option<HashMap<String, String>>,
+    validators_by_region: Mutex<Option<BTreeMap<String, Vec<PeerId>>>>,
+}
+
+impl RegionAssignment {
+    pub const fn new(region_labels: Option<HashMap<String, String>>) -> Self {
+        Self {
+            region_labels,
+            validators_by_region: Mutex::new(None),
+        }
+    }
+
+    /// Returns the validators of each region, assigning them (see
+    /// get_swarm_validators_by_region) if they are not already.
+    pub fn get_or_assign(&self, swarm: &dyn Swarm) -> BTreeMap<String, Vec<PeerId>> {
+        self.validators_by_region
+            .lock()
+            .unwrap()
+            .get_or_insert_with(|| {
+                get_swarm_validators_by_region(swarm, self.region_labels.as_ref())
+            })
+            .clone()
+    }
+
+    /// Returns the validators of each region, if assigned, and resets the assignment.
+    pub fn take(&self) -> Option<BTreeMap<String, Vec<PeerId>>> {
+        self.validators_by_region.lock().unwrap().take()
+    }
+}
+
+/// Returns the average latency from each region to all other regions, relative to the
+/// highest average latency, i.e. the highest-latency region has weight 1.0.
+fn get_region_latency_weights() -> BTreeMap<String, f64> {
+    let average_latencies: BTreeMap<String, f64> = get_link_stats_table()
+        .into_iter()
+        .map(|(region, stats)| {
+            let total_latency: f64 = stats.values().map(|(_, latency)| latency).sum();
+            (region, total_latency / stats.len() as f64)
+        })
+        .collect();
+    let max_latency = average_latencies.values().cloned().fold(0.0, f64::max);
+
+    average_latencies
+        .into_iter()
+        .map(|(region, latency)| (region, latency / max_latency))
+        .collect()
+}
+
+/// Returns the probability of each validator being delayed in execution, i.e. the given
+/// node fraction weighted by the latency of the region the validator is assigned to.
+fn get_correlated_node_fractions(
+    validators_by_region: &BTreeMap<String, Vec<PeerId>>,
+    node_fraction: f64,
+) -> HashMap<PeerId, f64> {
+    let region_latency_weights = get_region_latency_weights();
+
+    validators_by_region
+        .iter()
+        .flat_map(|(region, validators)| {
+            let region_fraction = node_fraction * region_latency_weights[region];
+            validators
+                .iter()
+                .map(move |peer_id| (*peer_id, region_fraction))
+        })
+        .collect()
+}
+
+/// Loads a json serialized SwarmNetworkDelay from the given file
+fn load_swarm_network_delay(path: &Path) -> anyhow::Result<SwarmNetworkDelay> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read network delay from {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to deserialize network delay from {:?}", path))
+}
+
+/// Returns the number of transactions committed by each (reachable) validator, i.e. its
+/// latest ledger version.
+fn get_committed_transactions(swarm: &dyn Swarm) -> HashMap<PeerId, u64> {
+    let runtime = Runtime::new().unwrap();
+    let validators = swarm
+        .validators()
+        .map(|v| (v.peer_id(), v.name().to_string(), v.rest_client()))
+        .collect::<Vec<_>>();
+
+    runtime.block_on(async {
+        let mut committed_transactions = HashMap::new();
+        for (peer_id, name, client) in validators {
+            match client.get_ledger_information().await {
+                Ok(state) => {
+                    committed_transactions.insert(peer_id, state.into_inner().version);
+                },
+                Err(e) => warn!("Failed to get ledger information from {}: {:?}", name, e),
+            }
+        }
+        committed_transactions
+    })
+}
+
+/// Writes the committed transactions of the validators as CSV, with one row per region
+/// (of the same assignment as for the network delay): the number of validators, and the
+/// min / average / max committed transactions across the validators of the region that
+/// reported them (empty if none did).
+fn write_region_throughput_csv(
+    path: &Path,
+    validators_by_region: &BTreeMap<String, Vec<PeerId>>,
+    committed_transactions: &HashMap<PeerId, u64>,
+) -> anyhow::Result<()> {
+    let mut writer = Writer::from_path(path)
+        .with_context(|| format!("Failed to create throughput csv {:?}", path))?;
+    writer.write_record([
+        "region",
+        "num_validators",
+        "min_committed_transactions",
+        "avg_committed_transactions",
+        "max_committed_transactions",
+    ])?;
+    for (region, validators) in validators_by_region {
+        let counts: Vec<u64> = validators
+            .iter()
+            .filter_map(|peer_id| committed_transactions.get(peer_id).cloned())
+            .collect();
+        let avg =
+            (!counts.is_empty()).then(|| counts.iter().sum::<u64>() as f64 / counts.len() as f64);
+        writer.serialize((
+            region,
+            validators.len(),
+            counts.iter().min(),
+            avg,
+            counts.iter().max(),
+        ))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Creates a GroupNetworkBandwidth limiting the traffic from the validators of one region to
+/// the validators of another to the given bitrate (in bps).
+fn create_group_network_bandwidth(
+    (from_region, from_validators): (&str, &[PeerId]),
+    (to_region, to_validators): (&str, &[PeerId]),
+    bitrate: u64,
+) -> GroupNetworkBandwidth {
+    GroupNetworkBandwidth {
+        name: format!("{}-to-{}-bandwidth", from_region, to_region),
+        source_nodes: from_validators.to_vec(),
+        target_nodes: to_validators.to_vec(),
+        rate: bitrate / 8,
+        limit: 20971520,
+        buffer: 10000,
+    }
+}
+
+/// Creates a SwarmNetworkDelay and SwarmNetworkBandwidth between every pair of regions,
+/// given the validators of each region and the link stats table. Pairs missing from the
+/// table use the default link stats (see get_link_stats). The bandwidth is limited in each
+/// direction of a pair, by the bitrate of the link in that direction, e.g. so that a
+/// region's downlink can be wider than its uplink. If the table has no stats for the way
+/// back, the limit is symmetric.
+fn create_multi_region_swarm_network_chaos(
+    link_stats_table: &BTreeMap<String, BTreeMap<String, (u64, f64)>>,
+    validators_by_region: &BTreeMap<String, Vec<PeerId>>,
+    default_link_stats: Option<(u64, f64)>,
+) -> (SwarmNetworkDelay, SwarmNetworkBandwidth) {
+    let (group_network_delays, group_network_bandwidths): (
+        Vec<GroupNetworkDelay>,
+        Vec<[GroupNetworkBandwidth; 2]>,
+    ) = validators_by_region
+        .iter()
         .combinations(2)
         .map(|comb| {
-            let (from_chunk, (from_region, stats)) = &comb[0];
-            let (to_chunk, (to_region, _)) = &comb[1];
+            let (from_region, from_validators) = comb[0];
+            let (to_region, to_validators) = comb[1];
 
-            let (bandwidth, latency) = stats.get(*to_region).unwrap();
+            let (bandwidth, latency) =
+                get_link_stats(link_stats_table, from_region, to_region, default_link_stats);
             let delay = GroupNetworkDelay {
                 name: format!("{}-to-{}-delay", from_region, to_region),
-                source_nodes: from_chunk.to_vec(),
-                target_nodes: to_chunk.to_vec(),
-                latency_ms: *latency as u64,
+                source_nodes: from_validators.clone(),
+                target_nodes: to_validators.clone(),
+                latency_ms: latency as u64,
                 jitter_ms: 5,
                 correlation_percentage: 50,
             };
             info!("delay {:?}", delay);
 
-            let bandwidth = GroupNetworkBandwidth {
-                name: format!("{}-to-{}-bandwidth", from_region, to_region),
-                // source_nodes: from_chunk.to_vec(),
-                // target_nodes: to_chunk.to_vec(),
-                rate: bandwidth / 8,
-                limit: 20971520,
-                buffer: 10000,
-            };
-            info!("bandwidth {:?}", bandwidth);
+            let reverse_bandwidth = link_stats_table
+                .get(to_region)
+                .and_then(|stats| stats.get(from_region))
+                .map_or(bandwidth, |(reverse_bandwidth, _)| *reverse_bandwidth);
+            let bandwidths = [
+                create_group_network_bandwidth(
+                    (from_region, from_validators),
+                    (to_region, to_validators),
+                    bandwidth,
+                ),
+                create_group_network_bandwidth(
+                    (to_region, to_validators),
+                    (from_region, from_validators),
+                    reverse_bandwidth,
+                ),
+            ];
+            info!("bandwidths {:?}", bandwidths);
 
-            (delay, bandwidth)
+            (delay, bandwidths)
         })
         .unzip();
 
-    let remainder = validator_chunks.remainder();
-    let remaining_validators: Vec<PeerId> = validator_chunks
-        .skip(number_of_regions)
-        .flatten()
-        .chain(remainder.iter())
-        .cloned()
-        .collect();
-    info!("remaining: {:?}", remaining_validators);
-    if !remaining_validators.is_empty() {
-        group_network_delays[0]
-            .source_nodes
-            .append(remaining_validators.to_vec().as_mut());
-    }
-
     (
         SwarmNetworkDelay {
             group_network_delays,
         },
         SwarmNetworkBandwidth {
-            group_network_bandwidths,
+            group_network_bandwidths: group_network_bandwidths.into_iter().flatten().collect(),
         },
     )
 }
 
-impl NetworkLoadTest for MultiRegionMultiCloudSimulationTest {
-    fn setup(&self, ctx: &mut NetworkContext) -> anyhow::Result<LoadDestination> {
-        let all_validators = ctx
-            .swarm()
-            .validators()
-            .map(|v| v.peer_id())
-            .collect::<Vec<_>>();
-
-        let (delay, bandwidth) = create_multi_region_swarm_network_chaos(all_validators);
+impl MultiRegionMultiCloudSimulationTest {
+    /// Same as setup, but the injection of chaos and execution delay stops when the token
+    /// is cancelled, in which case everything injected so far is removed and an error is
+    /// returned (e.g. so that a test cancelled early doesn't leave chaos on the swarm).
+    pub fn setup_with_cancellation(
+        &self,
+        ctx: &mut NetworkContext,
+        cancellation: &CancellationToken,
+    ) -> anyhow::Result<LoadDestination> {
+        let validators_by_region = self.regions.get_or_assign(ctx.swarm());
+        let (chaos, node_fractions) = self.get_chaos(&validators_by_region)?;
 
-        // inject bandwidth limit
-        let chaos = SwarmChaos::Bandwidth(bandwidth);
-        ctx.swarm().inject_chaos(chaos)?;
+        inject_or_rollback(
+            ctx.swarm(),
+            &chaos,
+            cancellation,
+            |swarm, chaos| swarm.inject_chaos(chaos.clone()),
+            |swarm, chaos| swarm.remove_chaos(chaos.clone()),
+        )?;
 
-        // inject network delay
-        let chaos = SwarmChaos::Delay(delay);
-        ctx.swarm().inject_chaos(chaos)?;
+        // inject execution delay, correlated with the network delay
+        if let (Some(config), Some(node_fractions)) =
+            (&self.correlated_execution_delay, &node_fractions)
+        {
+            if let Err(e) = add_execution_delay_with_node_fractions(
+                ctx.swarm(),
+                config,
+                node_fractions,
+                cancellation,
+            ) {
+                if cancellation.is_cancelled() {
+                    for chaos in chaos.into_iter().rev() {
+                        if let Err(e) = ctx.swarm().remove_chaos(chaos) {
+                            warn!("Failed to remove injected chaos: {:?}", e);
+                        }
+                    }
+                }
+                return Err(e);
+            }
+        }
 
         Ok(LoadDestination::FullnodesOtherwiseValidators)
     }
+}
+
+impl NetworkLoadTest for MultiRegionMultiCloudSimulationTest {
+    fn setup(&self, ctx: &mut NetworkContext) -> anyhow::Result<LoadDestination> {
+        let cancellation = ctx.cancellation().clone();
+        self.setup_with_cancellation(ctx, &cancellation)
+    }
 
     fn finish(&self, swarm: &mut dyn Swarm) -> anyhow::Result<()> {
+        // the next run of the test assigns the validators again
+        let validators_by_region = self.regions.take();
+        if let Some(path) = &self.throughput_output {
+            let validators_by_region =
+                validators_by_region.context("Validators were not assigned to regions in setup")?;
+            let committed_transactions = get_committed_transactions(swarm);
+            write_region_throughput_csv(path, &validators_by_region, &committed_transactions)?;
+            info!("Wrote per-region throughput to {:?}", path);
+        }
+        if self.dry_run {
+            return Ok(());
+        }
+        if self.correlated_execution_delay.is_some() {
+            remove_execution_delay(swarm)?;
+        }
         swarm.remove_all_chaos()
     }
 }
@@ -155,32 +572,344 @@ impl NetworkTest for MultiRegionMultiCloudSimulationTest {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::time::Duration;
 
     #[test]
     fn test_create_multi_region_swarm_network_chaos() {
         aptos_logger::Logger::new().init();
 
-        let all_validators = (0..8).map(|_| PeerId::random()).collect();
-        let (delay, bandwidth) = create_multi_region_swarm_network_chaos(all_validators);
+        let all_validators: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        let (delay, bandwidth) = create_multi_region_swarm_network_chaos(
+            &get_link_stats_table(),
+            &get_validators_by_region(&all_validators),
+            None,
+        );
 
         assert_eq!(delay.group_network_delays.len(), 6);
-        assert_eq!(bandwidth.group_network_bandwidths.len(), 6);
+        assert_eq!(bandwidth.group_network_bandwidths.len(), 12);
 
         let all_validators: Vec<PeerId> = (0..10).map(|_| PeerId::random()).collect();
-        let (delay, bandwidth) = create_multi_region_swarm_network_chaos(all_validators);
+        let (delay, bandwidth) = create_multi_region_swarm_network_chaos(
+            &get_link_stats_table(),
+            &get_validators_by_region(&all_validators),
+            None,
+        );
 
         assert_eq!(delay.group_network_delays.len(), 6);
-        assert_eq!(bandwidth.group_network_bandwidths.len(), 6);
+        assert_eq!(bandwidth.group_network_bandwidths.len(), 12);
         assert_eq!(delay.group_network_delays[0].source_nodes.len(), 4);
         assert_eq!(delay.group_network_delays[0].target_nodes.len(), 2);
+        // the way back is limited separately, from the target validators to the source ones
+        assert_eq!(
+            bandwidth.group_network_bandwidths[1].source_nodes,
+            delay.group_network_delays[0].target_nodes
+        );
+        assert_eq!(
+            bandwidth.group_network_bandwidths[1].target_nodes,
+            delay.group_network_delays[0].source_nodes
+        );
         assert_eq!(
             bandwidth.group_network_bandwidths[0],
             GroupNetworkBandwidth {
                 name: "aws--ap-northeast-1-to-aws--eu-west-1-bandwidth".to_owned(),
+                source_nodes: delay.group_network_delays[0].source_nodes.clone(),
+                target_nodes: delay.group_network_delays[0].target_nodes.clone(),
                 rate: 5160960,
                 limit: 20971520,
                 buffer: 10000,
             }
         )
     }
+
+    #[test]
+    fn test_asymmetric_bandwidth() {
+        let link_stats_table = parse_link_stats_table(
+            b"sending_region,receiving_region,bitrate_bps,avgrtt
+region-0,region-1,8000,50.0
+region-1,region-0,16000,50.0
+region-0,region-2,24000,80.0
+",
+        );
+        let all_validators: Vec<PeerId> = (0..3).map(|_| PeerId::random()).collect();
+        let validators_by_region =
+            assign_validators_to_regions(&all_validators, (0..3).map(|i| format!("region-{}", i)));
+        let (_, bandwidth) = create_multi_region_swarm_network_chaos(
+            &link_stats_table,
+            &validators_by_region,
+            Some((4000, 100.0)),
+        );
+
+        let rates: BTreeMap<String, u64> = bandwidth
+            .group_network_bandwidths
+            .into_iter()
+            .map(|bandwidth| (bandwidth.name, bandwidth.rate))
+            .collect();
+        assert_eq!(
+            rates,
+            BTreeMap::from([
+                // Both directions are in the table.
+                ("region-0-to-region-1-bandwidth".to_string(), 1000),
+                ("region-1-to-region-0-bandwidth".to_string(), 2000),
+                // The way back is missing, so the limit is symmetric.
+                ("region-0-to-region-2-bandwidth".to_string(), 3000),
+                ("region-2-to-region-0-bandwidth".to_string(), 3000),
+                // The pair is missing, so the default applies in both directions.
+                ("region-1-to-region-2-bandwidth".to_string(), 500),
+                ("region-2-to-region-1-bandwidth".to_string(), 500),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_get_link_stats_with_missing_pair() {
+        let link_stats_table: BTreeMap<String, BTreeMap<String, (u64, f64)>> = BTreeMap::from([
+            (
+                "region-0".to_string(),
+                BTreeMap::from([("region-1".to_string(), (1000, 50.0))]),
+            ),
+            ("region-1".to_string(), BTreeMap::new()),
+        ]);
+        let default_link_stats = Some((500, 100.0));
+
+        assert_eq!(
+            get_link_stats(
+                &link_stats_table,
+                "region-0",
+                "region-1",
+                default_link_stats
+            ),
+            (1000, 50.0)
+        );
+        assert_eq!(
+            get_link_stats(
+                &link_stats_table,
+                "region-1",
+                "region-0",
+                default_link_stats
+            ),
+            (500, 100.0)
+        );
+        assert_eq!(
+            get_link_stats(
+                &link_stats_table,
+                "region-2",
+                "region-0",
+                default_link_stats
+            ),
+            (500, 100.0)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_get_link_stats_without_default() {
+        get_link_stats(&BTreeMap::new(), "region-0", "region-1", None);
+    }
+
+    #[test]
+    fn test_get_validators_by_region() {
+        let all_validators: Vec<PeerId> = (0..10).map(|_| PeerId::random()).collect();
+        let validators_by_region = get_validators_by_region(&all_validators);
+
+        assert_eq!(validators_by_region.len(), 4);
+        let mut expected_first_region = all_validators[0..2].to_vec();
+        expected_first_region.extend_from_slice(&all_validators[8..10]);
+        assert_eq!(
+            validators_by_region["aws--ap-northeast-1"],
+            expected_first_region
+        );
+        assert_eq!(
+            validators_by_region["aws--eu-west-1"],
+            all_validators[2..4].to_vec()
+        );
+        assert_eq!(
+            validators_by_region["gcp--us-central1"],
+            all_validators[6..8].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_assign_validators_to_regions() {
+        let regions = || (0..3).map(|i| format!("region-{}", i));
+        for num_validators in [3, 7, 10] {
+            let all_validators: Vec<PeerId> =
+                (0..num_validators).map(|_| PeerId::random()).collect();
+            let validators_by_region = assign_validators_to_regions(&all_validators, regions());
+
+            // The assignment is stable.
+            assert_eq!(
+                validators_by_region,
+                assign_validators_to_regions(&all_validators, regions())
+            );
+
+            // Every validator is assigned to exactly one region.
+            assert_eq!(validators_by_region.len(), 3);
+            let mut assigned: Vec<PeerId> =
+                validators_by_region.values().flatten().cloned().collect();
+            let mut expected = all_validators.clone();
+            assigned.sort();
+            expected.sort();
+            assert_eq!(assigned, expected);
+        }
+    }
+
+    #[test]
+    fn test_get_validators_by_region_label() {
+        let all_validators: Vec<PeerId> = (0..6).map(|_| PeerId::random()).collect();
+        let mut region_labels: HashMap<PeerId, String> = all_validators
+            .iter()
+            .enumerate()
+            .map(|(i, peer_id)| {
+                let region = if i % 3 == 0 {
+                    "gcp--us-central1"
+                } else {
+                    "aws--eu-west-1"
+                };
+                (*peer_id, region.to_owned())
+            })
+            .collect();
+
+        let validators_by_region =
+            get_validators_by_region_label(&all_validators, &region_labels).unwrap();
+        assert_eq!(validators_by_region.len(), 2);
+        assert_eq!(validators_by_region["gcp--us-central1"], vec![
+            all_validators[0],
+            all_validators[3]
+        ]);
+        assert_eq!(validators_by_region["aws--eu-west-1"], vec![
+            all_validators[1],
+            all_validators[2],
+            all_validators[4],
+            all_validators[5]
+        ]);
+
+        let (delay, _) = create_multi_region_swarm_network_chaos(
+            &get_link_stats_table(),
+            &validators_by_region,
+            None,
+        );
+        assert_eq!(delay.group_network_delays.len(), 1);
+        assert_eq!(delay.group_network_delays[0].source_nodes.len(), 4);
+        assert_eq!(delay.group_network_delays[0].target_nodes.len(), 2);
+
+        // Falls back to chunking if a validator is not labeled with a known region.
+        region_labels.insert(all_validators[0], "unknown".to_owned());
+        assert!(get_validators_by_region_label(&all_validators, &region_labels).is_none());
+        region_labels.remove(&all_validators[0]);
+        assert!(get_validators_by_region_label(&all_validators, &region_labels).is_none());
+    }
+
+    #[test]
+    fn test_swarm_network_delay_round_trip() {
+        let all_validators: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        let (delay, _) = create_multi_region_swarm_network_chaos(
+            &get_link_stats_table(),
+            &get_validators_by_region(&all_validators),
+            None,
+        );
+
+        let path = aptos_temppath::TempPath::new();
+        std::fs::write(path.path(), serde_json::to_string(&delay).unwrap()).unwrap();
+
+        assert_eq!(load_swarm_network_delay(path.path()).unwrap(), delay);
+    }
+
+    #[test]
+    fn test_get_correlated_node_fractions() {
+        let all_validators: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        let node_fractions =
+            get_correlated_node_fractions(&get_validators_by_region(&all_validators), 0.5);
+        assert_eq!(node_fractions.len(), 8);
+
+        // aws--ap-northeast-1 has the highest average latency to the other regions.
+        let max_fraction = node_fractions.values().cloned().fold(0.0, f64::max);
+        assert_eq!(max_fraction, 0.5);
+        for peer_id in &all_validators[0..2] {
+            assert_eq!(node_fractions[peer_id], max_fraction);
+        }
+        for peer_id in &all_validators[2..8] {
+            assert!(node_fractions[peer_id] < max_fraction);
+        }
+    }
+
+    #[test]
+    fn test_dry_run_injects_no_chaos() {
+        let all_validators: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        let validators_by_region = get_validators_by_region(&all_validators);
+        let mut test = MultiRegionMultiCloudSimulationTest::new(
+            None,
+            Some(ExecutionDelayConfig {
+                inject_delay_node_fraction: 0.5,
+                inject_delay_max_transaction_percentage: 50,
+                inject_delay_per_transaction_ms: 2,
+                target_region: None,
+                regions: None,
+                ramp_duration: None,
+            }),
+            false,
+            None,
+            None,
+            None,
+        );
+
+        let (chaos, node_fractions) = test.get_chaos(&validators_by_region).unwrap();
+        assert_eq!(chaos.len(), 2);
+        assert!(node_fractions.is_some());
+
+        test.dry_run = true;
+        let (chaos, node_fractions) = test.get_chaos(&validators_by_region).unwrap();
+        assert!(chaos.is_empty());
+        assert!(node_fractions.is_none());
+
+        // The correlated execution delay is added at once, so it can't be ramped up.
+        test.correlated_execution_delay
+            .as_mut()
+            .unwrap()
+            .ramp_duration = Some(Duration::from_secs(60));
+        assert!(test.get_chaos(&validators_by_region).is_err());
+    }
+
+    #[test]
+    fn test_write_region_throughput_csv() {
+        let all_validators: Vec<PeerId> = (0..8).map(|_| PeerId::random()).collect();
+        // The last validator did not report.
+        let committed_transactions: HashMap<PeerId, u64> = all_validators[0..7]
+            .iter()
+            .enumerate()
+            .map(|(i, peer_id)| (*peer_id, 100 + i as u64))
+            .collect();
+
+        let path = aptos_temppath::TempPath::new();
+        write_region_throughput_csv(
+            path.path(),
+            &get_validators_by_region(&all_validators),
+            &committed_transactions,
+        )
+        .unwrap();
+
+        let mut reader = Reader::from_path(path.path()).unwrap();
+        let rows: Vec<(String, usize, Option<u64>, Option<f64>, Option<u64>)> =
+            reader.deserialize().map(|row| row.unwrap()).collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[0],
+            (
+                "aws--ap-northeast-1".to_owned(),
+                2,
+                Some(100),
+                Some(100.5),
+                Some(101)
+            )
+        );
+        assert_eq!(
+            rows[3],
+            (
+                "gcp--us-central1".to_owned(),
+                2,
+                Some(106),
+                Some(106.0),
+                Some(106)
+            )
+        );
+    }
 }
@@ -29,6 +29,8 @@ impl NetworkLoadTest for NetworkBandwidthTest {
             .inject_chaos(SwarmChaos::Bandwidth(SwarmNetworkBandwidth {
                 group_network_bandwidths: vec![GroupNetworkBandwidth {
                     name: format!("forge-namespace-{}mbps-bandwidth", RATE_MBPS),
+                    source_nodes: vec![],
+                    target_nodes: vec![],
                     rate: RATE_MBPS,
                     limit: LIMIT_BYTES,
                     buffer: BUFFER_BYTES,
@@ -47,6 +49,8 @@ impl NetworkLoadTest for NetworkBandwidthTest {
         swarm.remove_chaos(SwarmChaos::Bandwidth(SwarmNetworkBandwidth {
             group_network_bandwidths: vec![GroupNetworkBandwidth {
                 name: format!("forge-namespace-{}mbps-bandwidth", RATE_MBPS),
+                source_nodes: vec![],
+                target_nodes: vec![],
                 rate: RATE_MBPS,
                 limit: LIMIT_BYTES,
                 buffer: BUFFER_BYTES,
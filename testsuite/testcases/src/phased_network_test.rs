@@ -0,0 +1,171 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{LoadDestination, NetworkLoadTest};
+use aptos_forge::{NetworkContext, NetworkTest, Swarm, SwarmChaos, Test};
+use aptos_logger::info;
+use std::time::Duration;
+
+/// A phase of PhasedNetworkTest: the setup of its chaos (returning where to send the load),
+/// and for how long the phase lasts.
+pub struct ChaosPhase {
+    pub duration: Duration,
+    pub setup: Box<dyn Fn(&mut dyn Swarm) -> anyhow::Result<LoadDestination> + Send + Sync>,
+}
+
+impl ChaosPhase {
+    pub fn new(
+        duration: Duration,
+        setup: impl Fn(&mut dyn Swarm) -> anyhow::Result<LoadDestination> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            duration,
+            setup: Box::new(setup),
+        }
+    }
+
+    /// A phase injecting the given chaos, sending the load to the fullnodes (or validators).
+    pub fn from_chaos(duration: Duration, chaos: Vec<SwarmChaos>) -> Self {
+        Self::new(duration, move |swarm| {
+            for chaos in &chaos {
+                swarm.inject_chaos(chaos.clone())?;
+            }
+            Ok(LoadDestination::FullnodesOtherwiseValidators)
+        })
+    }
+}
+
+/// A test running a sequence of chaos profiles under load, e.g. multi-region network delay
+/// for the first half, and additional bandwidth degradation for the second half, to observe
+/// the transition. The first phase is set up with the test (so it also covers the warmup),
+/// and its load destination is the one of the test. At each phase boundary, all chaos is
+/// removed and the next phase is set up (the load keeps going to the same destination).
+/// After the last phase, the swarm stays as is until the end of the test.
+pub struct PhasedNetworkTest {
+    pub phases: Vec<ChaosPhase>,
+}
+
+/// Runs the phases within the given duration, the first one being already set up:
+/// 'start_phase' replaces the chaos of the target with the one of the phase at the given
+/// index, and 'sleep' waits for the phase to end. Phases that don't fit into the duration
+/// are cut short or skipped.
+fn run_chaos_phases<S: ?Sized>(
+    target: &mut S,
+    phases: &[ChaosPhase],
+    duration: Duration,
+    mut start_phase: impl FnMut(&mut S, usize) -> anyhow::Result<()>,
+    mut sleep: impl FnMut(&mut S, Duration),
+) -> anyhow::Result<()> {
+    let mut remaining = duration;
+    for (i, phase) in phases.iter().enumerate() {
+        if remaining.is_zero() {
+            break;
+        }
+        if i > 0 {
+            info!("Starting chaos phase {} out of {}", i + 1, phases.len());
+            start_phase(target, i)?;
+        }
+        let phase_duration = phase.duration.min(remaining);
+        sleep(target, phase_duration);
+        remaining -= phase_duration;
+    }
+    if !remaining.is_zero() {
+        sleep(target, remaining);
+    }
+    Ok(())
+}
+
+impl Test for PhasedNetworkTest {
+    fn name(&self) -> &'static str {
+        "network::phased-chaos"
+    }
+}
+
+impl NetworkLoadTest for PhasedNetworkTest {
+    fn setup(&self, ctx: &mut NetworkContext) -> anyhow::Result<LoadDestination> {
+        match self.phases.first() {
+            Some(phase) => {
+                info!("Starting chaos phase 1 out of {}", self.phases.len());
+                (phase.setup)(ctx.swarm())
+            },
+            None => Ok(LoadDestination::FullnodesOtherwiseValidators),
+        }
+    }
+
+    fn test(&self, swarm: &mut dyn Swarm, duration: Duration) -> anyhow::Result<()> {
+        run_chaos_phases(
+            swarm,
+            &self.phases,
+            duration,
+            |swarm, i| {
+                swarm.remove_all_chaos()?;
+                // the load keeps going to the destination of the first phase
+                (self.phases[i].setup)(swarm)?;
+                Ok(())
+            },
+            |_, duration| std::thread::sleep(duration),
+        )
+    }
+
+    fn finish(&self, swarm: &mut dyn Swarm) -> anyhow::Result<()> {
+        swarm.remove_all_chaos()
+    }
+}
+
+impl NetworkTest for PhasedNetworkTest {
+    fn run<'t>(&self, ctx: &mut NetworkContext<'t>) -> anyhow::Result<()> {
+        <dyn NetworkLoadTest>::run(self, ctx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_forge::{SwarmNetworkBandwidth, SwarmNetworkDelay};
+
+    #[derive(Debug, PartialEq)]
+    enum Event {
+        StartPhase(usize),
+        Sleep(Duration),
+    }
+
+    #[test]
+    fn test_chaos_changes_at_phase_boundary() {
+        let delay = SwarmChaos::Delay(SwarmNetworkDelay {
+            group_network_delays: vec![],
+        });
+        let bandwidth = SwarmChaos::Bandwidth(SwarmNetworkBandwidth {
+            group_network_bandwidths: vec![],
+        });
+        let phases = vec![
+            ChaosPhase::from_chaos(Duration::from_secs(10), vec![delay.clone()]),
+            ChaosPhase::from_chaos(Duration::from_secs(10), vec![delay, bandwidth]),
+        ];
+
+        let run = |duration| {
+            let mut events = vec![];
+            run_chaos_phases(
+                &mut events,
+                &phases,
+                duration,
+                |events, i| {
+                    events.push(Event::StartPhase(i));
+                    Ok(())
+                },
+                |events, duration| events.push(Event::Sleep(duration)),
+            )
+            .unwrap();
+            events
+        };
+        assert_eq!(run(Duration::from_secs(25)), vec![
+            Event::Sleep(Duration::from_secs(10)),
+            Event::StartPhase(1),
+            Event::Sleep(Duration::from_secs(10)),
+            Event::Sleep(Duration::from_secs(5)),
+        ]);
+        // The second phase doesn't start if the test ends during the first one.
+        assert_eq!(run(Duration::from_secs(5)), vec![Event::Sleep(
+            Duration::from_secs(5)
+        )]);
+    }
+}
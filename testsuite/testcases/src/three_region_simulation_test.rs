@@ -73,6 +73,8 @@ fn create_bandwidth_limit() -> SwarmNetworkBandwidth {
     SwarmNetworkBandwidth {
         group_network_bandwidths: vec![GroupNetworkBandwidth {
             name: "forge-namespace-1000mbps-bandwidth".to_owned(),
+            source_nodes: vec![],
+            target_nodes: vec![],
             rate: 1000, // 1000 megabytes per second
             limit: 20971520,
             buffer: 10000,
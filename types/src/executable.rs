@@ -41,7 +41,7 @@ pub trait Executable: Clone {
     fn size_bytes(&self) -> usize;
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct ExecutableTestType(());
 
 impl Executable for ExecutableTestType {